@@ -0,0 +1,50 @@
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use simple_program_1::random::Rng;
+use simple_program_1::{BitLife, GameOfLife, Grid, Rule, Simulator};
+
+const SIZES: [usize; 3] = [64, 256, 1024];
+
+// A reproducible soup at roughly one-third density.
+fn soup(size: usize) -> Grid {
+    let mut rng = Rng::new(size as u64);
+    let mut grid = Grid::new(size, size);
+    for row in 0..size {
+        for col in 0..size {
+            grid.set(row, col, rng.chance(0.33));
+        }
+    }
+    grid
+}
+
+fn bench_engine(c: &mut Criterion, name: &str, build: impl Fn(Grid) -> Box<dyn Simulator>) {
+    let mut group = c.benchmark_group(name);
+    // One element per generation, so throughput reads as generations/sec.
+    group.throughput(Throughput::Elements(1));
+    for size in SIZES {
+        let mut sim = build(soup(size));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| b.iter(|| sim.step()));
+    }
+    group.finish();
+}
+
+fn step(c: &mut Criterion) {
+    // Full recounts of every cell, one thread.
+    bench_engine(c, "naive", |grid| {
+        Box::new(
+            GameOfLife::new(grid)
+                .with_parallel(false)
+                .with_active_cells(false)
+                .with_neighbor_counts(false),
+        )
+    });
+    // Full recounts split across rayon's pool.
+    bench_engine(c, "parallel", |grid| {
+        Box::new(GameOfLife::new(grid).with_active_cells(false).with_neighbor_counts(false))
+    });
+    // Every cell visited, reading counts kept up to date between steps.
+    bench_engine(c, "counts", |grid| Box::new(GameOfLife::new(grid).with_active_cells(false)));
+    bench_engine(c, "bitpacked", |grid| Box::new(BitLife::from_grid(&grid, Rule::life())));
+}
+
+criterion_group!(benches, step);
+criterion_main!(benches);
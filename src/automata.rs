@@ -0,0 +1,17 @@
+pub mod ant;
+pub mod brians_brain;
+pub mod colored;
+pub mod cyclic;
+pub mod elementary;
+pub mod forest_fire;
+pub mod golly;
+pub mod isotropic;
+pub mod lenia;
+pub mod life3d;
+pub mod ltl;
+pub mod margolus;
+pub mod sandpile;
+pub mod smoothlife;
+pub mod turmite;
+pub mod wator;
+pub mod wireworld;
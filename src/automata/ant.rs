@@ -0,0 +1,92 @@
+use std::str::FromStr;
+
+use crate::automata::turmite::{TurmiteTable, Turmites};
+use crate::{Error, Grid};
+
+pub(crate) const ANT_COLORS: [[u8; 4]; 4] = [
+    [220, 30, 30, 255],
+    [30, 160, 60, 255],
+    [30, 80, 220, 255],
+    [230, 140, 0, 255],
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        self.turn_right().turn_right().turn_right()
+    }
+
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::East => (0, 1),
+            Direction::South => (1, 0),
+            Direction::West => (0, -1),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ant {
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+}
+
+impl Ant {
+    pub fn new(row: usize, col: usize, direction: Direction) -> Self {
+        Self { row, col, direction }
+    }
+
+    // Moves one cell forward, wrapping around the board edges.
+    pub fn advance(&mut self, rows: usize, cols: usize) {
+        let (dr, dc) = self.direction.offset();
+        self.row = (self.row as isize + dr).rem_euclid(rows as isize) as usize;
+        self.col = (self.col as isize + dc).rem_euclid(cols as isize) as usize;
+    }
+}
+
+// Parses "ROW,COL" or "ROW,COL,DIR" where DIR is one of N, E, S, W.
+impl FromStr for Ant {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || Error::Config(format!("invalid ant {s:?}, expected ROW,COL[,N|E|S|W]"));
+        let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+        let (row, col, dir) = match parts.as_slice() {
+            [row, col] => (row, col, "N"),
+            [row, col, dir] => (row, col, *dir),
+            _ => return Err(bad()),
+        };
+        let direction = match dir.to_ascii_uppercase().as_str() {
+            "N" => Direction::North,
+            "E" => Direction::East,
+            "S" => Direction::South,
+            "W" => Direction::West,
+            _ => return Err(bad()),
+        };
+        Ok(Self::new(row.parse().map_err(|_| bad())?, col.parse().map_err(|_| bad())?, direction))
+    }
+}
+
+// Langton's Ant is the one-state, two-color turmite: turn right on white,
+// left on black, flipping the cell either way.
+pub fn langtons_ant(grid: Grid, ants: Vec<Ant>) -> Turmites {
+    Turmites::new(grid, TurmiteTable::langtons_ant(), ants)
+}
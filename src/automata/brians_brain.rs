@@ -0,0 +1,14 @@
+use crate::{GameOfLife, Grid, Rule};
+
+pub const OFF: u8 = 0;
+pub const ON: u8 = 1;
+pub const DYING: u8 = 2;
+
+const PALETTE: [[u8; 4]; 3] = [[0, 0, 0, 255], [255, 255, 255, 255], [40, 90, 230, 255]];
+
+// Brian's Brain is the Generations rule B2/S/C3: every live cell dies after
+// one generation and spends one more as "dying" before switching off.
+pub fn brians_brain(initial: Grid) -> GameOfLife {
+    let rule = Rule::preset("brians-brain").expect("Brian's Brain is a built-in preset");
+    GameOfLife::with_behavior(initial, Box::new(rule)).with_palette(PALETTE.to_vec())
+}
@@ -0,0 +1,207 @@
+use crate::boundary::{BoundaryCondition, Resolved};
+use crate::neighborhood::{Moore, Neighborhood};
+use crate::random::Rng;
+use crate::{Anchor, Grid, Lattice, Memory, Population, Rule, Shade, Simulator, Snapshot};
+
+pub const COLORS: [[u8; 4]; 4] = [
+    [220, 40, 40, 255],
+    [40, 90, 220, 255],
+    [30, 160, 60, 255],
+    [230, 170, 0, 255],
+];
+const NAMES: [&str; 4] = ["red", "blue", "green", "yellow"];
+
+// Multi-color Life: live cells carry a color 1..=colors and the usual rule
+// decides who lives. A newborn takes the most common color among its live
+// neighbors; when every color but one is tied (three distinct parents in
+// QuadLife) it takes the missing one instead.
+pub struct ColoredLife {
+    grid: Grid,
+    colors: u8,
+    rule: Rule,
+    neighborhood: Box<dyn Neighborhood>,
+    boundary: BoundaryCondition,
+    generation: u64,
+}
+
+impl ColoredLife {
+    pub fn new(grid: Grid, colors: u8, rule: Rule) -> Self {
+        Self {
+            grid,
+            colors: colors.clamp(1, COLORS.len() as u8),
+            rule,
+            neighborhood: Box::new(Moore::new()),
+            boundary: BoundaryCondition::default(),
+            generation: 0,
+        }
+    }
+
+    // Two colors under Conway's rule.
+    pub fn immigration(grid: Grid) -> Self {
+        Self::new(grid, 2, Rule::life())
+    }
+
+    // Four colors under Conway's rule.
+    pub fn quadlife(grid: Grid) -> Self {
+        Self::new(grid, 4, Rule::life())
+    }
+
+    // Gives every live cell a random color.
+    pub fn paint(mut self, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        for row in 0..self.grid.rows() {
+            for col in 0..self.grid.cols() {
+                if self.grid.get(row, col) {
+                    let color = 1 + rng.below(self.colors as u64) as u8;
+                    self.grid.set_state(row, col, color);
+                }
+            }
+        }
+        self
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: Box<dyn Neighborhood>) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    pub fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn colors(&self) -> u8 {
+        self.colors
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // Live neighbor count plus how many of them hold each color.
+    fn neighbors(&self, row: usize, col: usize) -> (u8, [u8; 4]) {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        let mut total = 0;
+        let mut by_color = [0; 4];
+        for (dr, dc) in self.neighborhood.offsets_at(row, col) {
+            match self.boundary.resolve(row as isize + dr, col as isize + dc, rows, cols) {
+                Resolved::Cell(r, c) => {
+                    let state = self.grid.state(r, c);
+                    if state != 0 {
+                        total += 1;
+                        by_color[state as usize - 1] += 1;
+                    }
+                }
+                Resolved::Fixed(alive) => total += alive as u8,
+            }
+        }
+        (total, by_color)
+    }
+
+    fn newborn(&self, by_color: &[u8; 4]) -> u8 {
+        let by_color = &by_color[..self.colors as usize];
+        let most = by_color.iter().copied().max().unwrap_or(0);
+        let missing: Vec<usize> = (0..by_color.len()).filter(|&i| by_color[i] == 0).collect();
+        let tied = by_color.iter().all(|&n| n == 0 || n == most);
+        if by_color.len() > 2 && missing.len() == 1 && tied {
+            return missing[0] as u8 + 1;
+        }
+        // Boundary cells count towards births but carry no color; fall back
+        // to the first color if they were the only parents.
+        by_color.iter().position(|&n| n == most).unwrap_or(0) as u8 + 1
+    }
+}
+
+impl Simulator for ColoredLife {
+    fn step(&mut self) {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        let mut next = Grid::new(rows, cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let state = self.grid.state(row, col);
+                let (total, by_color) = self.neighbors(row, col);
+                let state = match state {
+                    0 if self.rule.births(total) => self.newborn(&by_color),
+                    0 => 0,
+                    color if self.rule.survives(total) => color,
+                    _ => 0,
+                };
+                next.set_state(row, col, state);
+            }
+        }
+        self.grid = next;
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.grid.rows(), self.grid.cols())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        self.grid = self.grid.resized(rows, cols, anchor);
+    }
+
+    fn boundary(&self) -> Option<BoundaryCondition> {
+        Some(self.boundary)
+    }
+
+    fn set_boundary(&mut self, boundary: BoundaryCondition) {
+        self.boundary = boundary;
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.grid.get(row, col)
+    }
+
+    fn lattice(&self) -> Lattice {
+        self.neighborhood.lattice()
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        match self.grid.state(row, col) {
+            0 => Shade::Dead,
+            color => Shade::Rgba(COLORS[color as usize - 1]),
+        }
+    }
+
+    fn populations(&self) -> Vec<Population> {
+        let mut counts = [0; 4];
+        for &state in self.grid.cells() {
+            if state != 0 {
+                counts[state as usize - 1] += 1;
+            }
+        }
+        (0..self.colors as usize)
+            .map(|i| Population { name: NAMES[i], color: COLORS[i], count: counts[i] })
+            .collect()
+    }
+
+    // Drawn cells take the first color.
+    fn set_cell(&mut self, row: usize, col: usize, alive: bool) {
+        self.grid.set(row, col, alive);
+    }
+
+    fn save(&self) -> Option<Snapshot> {
+        Some(Snapshot {
+            generation: self.generation,
+            grid: self.grid.clone(),
+        })
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        self.grid = snapshot.grid.clone();
+        self.generation = snapshot.generation;
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::of("grid", self.grid.cells())]
+    }
+}
@@ -0,0 +1,135 @@
+use crate::boundary::{BoundaryCondition, Resolved};
+use crate::neighborhood::{Moore, Neighborhood};
+use crate::random::Rng;
+use crate::render::hue;
+use crate::{Anchor, Grid, Lattice, Memory, Shade, Simulator, Snapshot};
+
+// A cell in state k is eaten by state k+1 (mod `states`) once at least
+// `threshold` of its neighbors already hold that successor.
+pub struct Cyclic {
+    grid: Grid,
+    states: u8,
+    threshold: usize,
+    neighborhood: Box<dyn Neighborhood>,
+    boundary: BoundaryCondition,
+    generation: u64,
+}
+
+impl Cyclic {
+    pub fn new(grid: Grid, states: u8, threshold: usize) -> Self {
+        Self {
+            grid,
+            states: states.max(2),
+            threshold,
+            neighborhood: Box::new(Moore::new()),
+            boundary: BoundaryCondition::Wrap,
+            generation: 0,
+        }
+    }
+
+    pub fn random(rows: usize, cols: usize, states: u8, threshold: usize, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut grid = Grid::new(rows, cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                grid.set_state(row, col, rng.below(states.max(2) as u64) as u8);
+            }
+        }
+        Self::new(grid, states, threshold)
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: Box<dyn Neighborhood>) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    pub fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn successor_neighbors(&self, row: usize, col: usize, successor: u8) -> usize {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        self.neighborhood
+            .offsets_at(row, col)
+            .iter()
+            .filter(|(dr, dc)| match self.boundary.resolve(row as isize + dr, col as isize + dc, rows, cols) {
+                Resolved::Cell(r, c) => self.grid.state(r, c) == successor,
+                Resolved::Fixed(_) => false,
+            })
+            .count()
+    }
+}
+
+impl Simulator for Cyclic {
+    fn step(&mut self) {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        let mut next = self.grid.clone();
+        for row in 0..rows {
+            for col in 0..cols {
+                let successor = (self.grid.state(row, col) + 1) % self.states;
+                if self.successor_neighbors(row, col, successor) >= self.threshold {
+                    next.set_state(row, col, successor);
+                }
+            }
+        }
+        self.grid = next;
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.grid.rows(), self.grid.cols())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        self.grid = self.grid.resized(rows, cols, anchor);
+    }
+
+    fn boundary(&self) -> Option<BoundaryCondition> {
+        Some(self.boundary)
+    }
+
+    fn set_boundary(&mut self, boundary: BoundaryCondition) {
+        self.boundary = boundary;
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.grid.state(row, col) != 0
+    }
+
+    fn lattice(&self) -> Lattice {
+        self.neighborhood.lattice()
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        Shade::Rgba(hue(self.grid.state(row, col) as f32 / self.states as f32))
+    }
+
+    fn save(&self) -> Option<Snapshot> {
+        Some(Snapshot {
+            generation: self.generation,
+            grid: self.grid.clone(),
+        })
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        self.grid = snapshot.grid.clone();
+        self.generation = snapshot.generation;
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::of("grid", self.grid.cells())]
+    }
+}
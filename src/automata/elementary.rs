@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+
+use crate::boundary::{BoundaryCondition, Resolved};
+use crate::{Memory, Simulator};
+
+// Wolfram's elementary automata. Each generation is appended as a new bottom
+// row; once the history fills the board the oldest row scrolls off the top.
+pub struct Elementary {
+    rule: u8,
+    rows: usize,
+    history: VecDeque<Vec<bool>>,
+    boundary: BoundaryCondition,
+    generation: u64,
+}
+
+impl Elementary {
+    pub fn new(rule: u8, rows: usize, first: Vec<bool>) -> Self {
+        Self {
+            rule,
+            rows,
+            history: VecDeque::from([first]),
+            boundary: BoundaryCondition::default(),
+            generation: 0,
+        }
+    }
+
+    pub fn single_seed(rule: u8, rows: usize, cols: usize) -> Self {
+        let mut first = vec![false; cols];
+        first[cols / 2] = true;
+        Self::new(rule, rows, first)
+    }
+
+    pub fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn rule(&self) -> u8 {
+        self.rule
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn current(&self) -> &[bool] {
+        self.history.back().expect("history always holds the current row")
+    }
+
+    fn next_row(&self) -> Vec<bool> {
+        let row = self.current();
+        let at = |i: isize| match self.boundary.resolve(0, i, 1, row.len()) {
+            Resolved::Cell(_, i) => row[i],
+            Resolved::Fixed(alive) => alive,
+        };
+        (0..row.len() as isize)
+            .map(|i| {
+                let pattern = (at(i - 1) as u8) << 2 | (at(i) as u8) << 1 | at(i + 1) as u8;
+                self.rule & (1 << pattern) != 0
+            })
+            .collect()
+    }
+}
+
+impl Simulator for Elementary {
+    fn step(&mut self) {
+        let next = self.next_row();
+        if self.history.len() == self.rows {
+            self.history.pop_front();
+        }
+        self.history.push_back(next);
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.current().len())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn boundary(&self) -> Option<BoundaryCondition> {
+        Some(self.boundary)
+    }
+
+    fn set_boundary(&mut self, boundary: BoundaryCondition) {
+        self.boundary = boundary;
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.history.get(row).is_some_and(|line| line[col])
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::new("history", self.history.iter().map(|row| std::mem::size_of_val(&row[..])).sum())]
+    }
+}
@@ -0,0 +1,174 @@
+use crate::boundary::{BoundaryCondition, Resolved};
+use crate::neighborhood::{Neighborhood, VonNeumann};
+use crate::random::Rng;
+use crate::{Anchor, Grid, Lattice, Memory, Parameter, Shade, Simulator, Snapshot, Step};
+
+pub const EMPTY: u8 = 0;
+pub const TREE: u8 = 1;
+pub const BURNING: u8 = 2;
+
+pub const COLORS: [[u8; 4]; 3] = [
+    [0, 0, 0, 255],
+    [30, 140, 40, 255],
+    [255, 120, 0, 255],
+];
+
+// Drossel–Schwabl forest fire: burning cells burn out, trees next to a fire
+// catch it, lightning strikes a tree with probability `lightning` and empty
+// ground grows a tree with probability `growth`.
+pub struct ForestFire {
+    grid: Grid,
+    growth: f64,
+    lightning: f64,
+    rng: Rng,
+    neighborhood: Box<dyn Neighborhood>,
+    boundary: BoundaryCondition,
+    generation: u64,
+}
+
+impl ForestFire {
+    pub fn new(grid: Grid, growth: f64, lightning: f64, seed: u64) -> Self {
+        Self {
+            grid,
+            growth: growth.clamp(0.0, 1.0),
+            lightning: lightning.clamp(0.0, 1.0),
+            rng: Rng::new(seed),
+            neighborhood: Box::new(VonNeumann::new()),
+            boundary: BoundaryCondition::default(),
+            generation: 0,
+        }
+    }
+
+    // Starts from an empty forest, which fills in before the first fires.
+    pub fn empty(rows: usize, cols: usize, growth: f64, lightning: f64, seed: u64) -> Self {
+        Self::new(Grid::new(rows, cols), growth, lightning, seed)
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: Box<dyn Neighborhood>) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    pub fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn growth(&self) -> f64 {
+        self.growth
+    }
+
+    pub fn set_growth(&mut self, growth: f64) {
+        self.growth = growth.clamp(0.0, 1.0);
+    }
+
+    pub fn lightning(&self) -> f64 {
+        self.lightning
+    }
+
+    pub fn set_lightning(&mut self, lightning: f64) {
+        self.lightning = lightning.clamp(0.0, 1.0);
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn near_fire(&self, row: usize, col: usize) -> bool {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        self.neighborhood.offsets_at(row, col).iter().any(|(dr, dc)| {
+            match self.boundary.resolve(row as isize + dr, col as isize + dc, rows, cols) {
+                Resolved::Cell(r, c) => self.grid.state(r, c) == BURNING,
+                Resolved::Fixed(_) => false,
+            }
+        })
+    }
+}
+
+impl Simulator for ForestFire {
+    fn step(&mut self) {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        let mut next = Grid::new(rows, cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let state = match self.grid.state(row, col) {
+                    BURNING => EMPTY,
+                    TREE if self.near_fire(row, col) || self.rng.chance(self.lightning) => BURNING,
+                    EMPTY if self.rng.chance(self.growth) => TREE,
+                    state => state,
+                };
+                next.set_state(row, col, state);
+            }
+        }
+        self.grid = next;
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.grid.rows(), self.grid.cols())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        self.grid = self.grid.resized(rows, cols, anchor);
+    }
+
+    fn boundary(&self) -> Option<BoundaryCondition> {
+        Some(self.boundary)
+    }
+
+    fn set_boundary(&mut self, boundary: BoundaryCondition) {
+        self.boundary = boundary;
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.grid.state(row, col) != EMPTY
+    }
+
+    fn lattice(&self) -> Lattice {
+        self.neighborhood.lattice()
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        Shade::Rgba(COLORS[self.grid.state(row, col) as usize])
+    }
+
+    // Both probabilities span orders of magnitude, so they move by doubling.
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![
+            Parameter::new("growth", self.growth, Step::Scale(2.0)),
+            Parameter::new("lightning", self.lightning, Step::Scale(2.0)),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) {
+        match name {
+            "growth" => self.set_growth(value),
+            "lightning" => self.set_lightning(value),
+            _ => {}
+        }
+    }
+
+    fn save(&self) -> Option<Snapshot> {
+        Some(Snapshot {
+            generation: self.generation,
+            grid: self.grid.clone(),
+        })
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        self.grid = snapshot.grid.clone();
+        self.generation = snapshot.generation;
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::of("grid", self.grid.cells())]
+    }
+}
@@ -0,0 +1,618 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::boundary::{BoundaryCondition, Resolved};
+use crate::render::hue;
+use crate::{Anchor, Error, Grid, Memory, Shade, Simulator, Snapshot};
+
+fn bad(msg: impl Into<String>) -> Error {
+    Error::Rule(msg.into())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableNeighborhood {
+    Moore,
+    VonNeumann,
+    Hexagonal,
+    OneDimensional,
+}
+
+impl TableNeighborhood {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "moore" => Some(TableNeighborhood::Moore),
+            "vonneumann" => Some(TableNeighborhood::VonNeumann),
+            "hexagonal" => Some(TableNeighborhood::Hexagonal),
+            "onedimensional" => Some(TableNeighborhood::OneDimensional),
+            _ => None,
+        }
+    }
+
+    // Neighbors in the order transitions list them, clockwise from north.
+    // Hexagonal boards are the usual square-grid emulation that drops NE and
+    // SW.
+    pub fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            TableNeighborhood::Moore => &[(-1, 0), (-1, 1), (0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1)],
+            TableNeighborhood::VonNeumann => &[(-1, 0), (0, 1), (1, 0), (0, -1)],
+            TableNeighborhood::Hexagonal => &[(-1, 0), (0, 1), (1, 1), (1, 0), (0, -1), (-1, -1)],
+            TableNeighborhood::OneDimensional => &[(0, -1), (0, 1)],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct StateSet([u64; 4]);
+
+impl StateSet {
+    fn single(state: u8) -> Self {
+        let mut set = Self::default();
+        set.insert(state);
+        set
+    }
+
+    fn insert(&mut self, state: u8) {
+        self.0[state as usize / 64] |= 1 << (state % 64);
+    }
+
+    fn union(&mut self, other: StateSet) {
+        for (word, other) in self.0.iter_mut().zip(other.0) {
+            *word |= other;
+        }
+    }
+
+    fn contains(self, state: u8) -> bool {
+        self.0[state as usize / 64] & (1 << (state % 64)) != 0
+    }
+
+    fn states(self) -> impl Iterator<Item = u8> {
+        (0..=u8::MAX).filter(move |&state| self.contains(state))
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Entry {
+    Set(StateSet),
+    Var(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Transition {
+    center: StateSet,
+    neighbors: Vec<StateSet>,
+    result: u8,
+}
+
+impl Transition {
+    fn matches(&self, center: u8, neighbors: &[u8], permute: bool) -> bool {
+        if !self.center.contains(center) {
+            return false;
+        }
+        if permute {
+            Self::assign(&self.neighbors, neighbors, 0)
+        } else {
+            self.neighbors.iter().zip(neighbors).all(|(set, &state)| set.contains(state))
+        }
+    }
+
+    // Whether each neighbor can be paired with a distinct entry holding its
+    // state, trying entries in turn for the first unpaired neighbor.
+    fn assign(entries: &[StateSet], neighbors: &[u8], used: u32) -> bool {
+        let Some((&state, rest)) = neighbors.split_first() else {
+            return true;
+        };
+        (0..entries.len()).any(|i| {
+            used & (1 << i) == 0 && entries[i].contains(state) && Self::assign(entries, rest, used | 1 << i)
+        })
+    }
+}
+
+// Golly's @TABLE: state transitions listed as center, neighbors, new center,
+// with variables standing for sets of states and a symmetry that expands
+// each line into its rotations and reflections. The first matching line
+// wins; cells no line matches keep their state.
+#[derive(Clone, Debug)]
+pub struct RuleTable {
+    states: u8,
+    neighborhood: TableNeighborhood,
+    permute: bool,
+    transitions: Vec<Transition>,
+}
+
+// Rearrangements of a ring of `n` neighbors generated by rotating `step`
+// places and, optionally, mirroring about the first position.
+fn symmetries(n: usize, step: usize, reflect: bool) -> Vec<Vec<usize>> {
+    let mut perms = Vec::new();
+    for turn in (0..n).step_by(step.max(1)) {
+        perms.push((0..n).map(|i| (i + turn) % n).collect::<Vec<_>>());
+        if reflect {
+            perms.push((0..n).map(|i| (n - i + turn) % n).collect());
+        }
+    }
+    perms
+}
+
+fn split_entries(line: &str) -> Vec<String> {
+    if !line.contains([',', '{']) {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        return match words.as_slice() {
+            [compact] => compact.chars().map(String::from).collect(),
+            _ => words.into_iter().map(String::from).collect(),
+        };
+    }
+    let mut entries = vec![String::new()];
+    let mut depth = 0;
+    for ch in line.chars() {
+        match ch {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+        match ch {
+            ',' if depth == 0 => entries.push(String::new()),
+            ch if ch.is_whitespace() && depth == 0 => {}
+            ch => entries.last_mut().expect("starts with one entry").push(ch),
+        }
+    }
+    entries
+}
+
+impl RuleTable {
+    fn parse(lines: &[&str]) -> Result<Self, Error> {
+        let mut states = None;
+        let mut neighborhood = TableNeighborhood::Moore;
+        let mut symmetry = "none".to_string();
+        let mut vars: HashMap<String, StateSet> = HashMap::new();
+        let mut lines_out: Vec<Vec<Entry>> = Vec::new();
+
+        for line in lines {
+            if let Some((key, value)) = line.split_once(':') {
+                let value = value.trim();
+                match key.trim() {
+                    "n_states" => {
+                        let n: u16 = value.parse().map_err(|_| bad(format!("bad n_states {value:?}")))?;
+                        if !(2..=256).contains(&n) {
+                            return Err(bad("n_states must be between 2 and 256"));
+                        }
+                        states = Some((n - 1) as u8);
+                    }
+                    "neighborhood" => {
+                        neighborhood = TableNeighborhood::parse(value)
+                            .ok_or_else(|| bad(format!("unsupported neighborhood {value:?}")))?;
+                    }
+                    "symmetries" => symmetry = value.to_string(),
+                    key => return Err(bad(format!("unknown @TABLE setting {key:?}"))),
+                }
+                continue;
+            }
+            let max = states.ok_or_else(|| bad("n_states must come before the transitions"))?;
+            let state = |token: &str| match token.parse::<u16>() {
+                Ok(n) if n <= max as u16 => Ok(n as u8),
+                Ok(n) => Err(bad(format!("state {n} is out of range"))),
+                Err(_) => Err(bad(format!("unknown state or variable {token:?}"))),
+            };
+            let set = |inner: &str, vars: &HashMap<String, StateSet>| {
+                let mut set = StateSet::default();
+                for item in inner.split(',').map(str::trim) {
+                    match vars.get(item) {
+                        Some(&other) => set.union(other),
+                        None => set.insert(state(item)?),
+                    }
+                }
+                Ok::<_, Error>(set)
+            };
+            if let Some(def) = line.strip_prefix("var ") {
+                let (name, value) = def.split_once('=').ok_or_else(|| bad(format!("bad variable {line:?}")))?;
+                let inner = value.trim().trim_start_matches('{').trim_end_matches('}');
+                let value = set(inner, &vars)?;
+                vars.insert(name.trim().to_string(), value);
+                continue;
+            }
+            let entries = split_entries(line)
+                .iter()
+                .map(|token| {
+                    if let Some(inner) = token.strip_prefix('{') {
+                        Ok(Entry::Set(set(inner.trim_end_matches('}'), &vars)?))
+                    } else if vars.contains_key(token) {
+                        Ok(Entry::Var(token.clone()))
+                    } else {
+                        Ok(Entry::Set(StateSet::single(state(token)?)))
+                    }
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            lines_out.push(entries);
+        }
+        let states = states.ok_or_else(|| bad("@TABLE needs n_states"))?;
+
+        let n = neighborhood.offsets().len();
+        let (step, reflect, permute) = match symmetry.as_str() {
+            "none" => (n, false, false),
+            "reflect" | "reflect_horizontal" => (n, true, false),
+            "permute" => (n, false, true),
+            name => {
+                let (turns, reflect) = match name.strip_suffix("reflect") {
+                    Some(rotate) => (rotate, true),
+                    None => (name, false),
+                };
+                let turns: usize = turns
+                    .strip_prefix("rotate")
+                    .and_then(|turns| turns.parse().ok())
+                    .filter(|&turns| turns > 0 && n.is_multiple_of(turns))
+                    .ok_or_else(|| bad(format!("unsupported symmetry {name:?} for this neighborhood")))?;
+                (n / turns, reflect, false)
+            }
+        };
+        let perms = symmetries(n, step, reflect);
+
+        let mut transitions = Vec::new();
+        for entries in &lines_out {
+            if entries.len() != n + 2 {
+                return Err(bad(format!("transitions need {} entries, got {}", n + 2, entries.len())));
+            }
+            for bound in Self::bindings(entries, &vars)? {
+                let resolve = |entry: &Entry| match entry {
+                    Entry::Set(set) => *set,
+                    Entry::Var(name) => bound.get(name.as_str()).map_or(vars[name], |&s| StateSet::single(s)),
+                };
+                let result = match &entries[n + 1] {
+                    Entry::Var(name) => bound[name.as_str()],
+                    Entry::Set(set) => {
+                        let mut states = set.states();
+                        match (states.next(), states.next()) {
+                            (Some(state), None) => state,
+                            _ => return Err(bad("the new state must be a single state")),
+                        }
+                    }
+                };
+                let neighbors: Vec<StateSet> = entries[1..=n].iter().map(resolve).collect();
+                for perm in &perms {
+                    let transition = Transition {
+                        center: resolve(&entries[0]),
+                        neighbors: perm.iter().map(|&i| neighbors[i]).collect(),
+                        result,
+                    };
+                    if !transitions.contains(&transition) {
+                        transitions.push(transition);
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            states: states + 1,
+            neighborhood,
+            permute,
+            transitions,
+        })
+    }
+
+    // Every assignment of the variables that are bound within a line: those
+    // used more than once, and the one (if any) naming the new state.
+    fn bindings<'a>(entries: &'a [Entry], vars: &HashMap<String, StateSet>) -> Result<Vec<HashMap<&'a str, u8>>, Error> {
+        let names: Vec<&str> = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                Entry::Var(name) => Some(name.as_str()),
+                Entry::Set(_) => None,
+            })
+            .collect();
+        let (inputs, output) = names.split_at(names.len() - matches!(entries.last(), Some(Entry::Var(_))) as usize);
+        if let Some(output) = output.first().filter(|output| !inputs.contains(output)) {
+            return Err(bad(format!("the new state uses unbound variable {output:?}")));
+        }
+        let mut bound: Vec<&str> = names
+            .iter()
+            .copied()
+            .filter(|name| names.iter().filter(|other| *other == name).count() > 1)
+            .collect();
+        bound.sort_unstable();
+        bound.dedup();
+        let mut assignments = vec![HashMap::new()];
+        for name in bound {
+            assignments = assignments
+                .into_iter()
+                .flat_map(|assignment: HashMap<&str, u8>| {
+                    vars[name].states().map(move |state| {
+                        let mut assignment = assignment.clone();
+                        assignment.insert(name, state);
+                        assignment
+                    })
+                })
+                .collect();
+        }
+        Ok(assignments)
+    }
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn neighborhood(&self) -> TableNeighborhood {
+        self.neighborhood
+    }
+
+    pub fn next_state(&self, center: u8, neighbors: &[u8]) -> u8 {
+        self.transitions
+            .iter()
+            .find(|t| t.matches(center, neighbors, self.permute))
+            .map_or(center, |t| t.result)
+    }
+}
+
+// Golly's @TREE: a decision diagram with one level per input. The root
+// reads NW, NE, SW, SE, N, W, E, S (or N, W, E, S for four neighbors), the
+// bottom level reads the center cell and holds the new states.
+#[derive(Clone, Debug)]
+pub struct RuleTree {
+    states: u8,
+    neighbors: usize,
+    nodes: Vec<Vec<usize>>,
+}
+
+impl RuleTree {
+    fn parse(lines: &[&str]) -> Result<Self, Error> {
+        let mut settings: HashMap<&str, usize> = HashMap::new();
+        let mut nodes: Vec<Vec<usize>> = Vec::new();
+        let mut levels: Vec<usize> = Vec::new();
+        for line in lines {
+            if let Some((key, value)) = line.split_once('=') {
+                let value = value.trim().parse().map_err(|_| bad(format!("bad @TREE setting {line:?}")))?;
+                settings.insert(key.trim(), value);
+                continue;
+            }
+            let numbers = line
+                .split_whitespace()
+                .map(|n| n.parse().map_err(|_| bad(format!("bad @TREE node {line:?}"))))
+                .collect::<Result<Vec<usize>, Error>>()?;
+            let (&level, values) = numbers.split_first().ok_or_else(|| bad("empty @TREE node"))?;
+            levels.push(level);
+            nodes.push(values.to_vec());
+        }
+        let setting = |key: &str| settings.get(key).copied().ok_or_else(|| bad(format!("@TREE needs {key}")));
+        let states = setting("num_states")?;
+        let neighbors = setting("num_neighbors")?;
+        if !(2..=256).contains(&states) || ![4, 8].contains(&neighbors) {
+            return Err(bad("@TREE supports 2 to 256 states and 4 or 8 neighbors"));
+        }
+        if nodes.len() != setting("num_nodes")? {
+            return Err(bad("@TREE node count doesn't match num_nodes"));
+        }
+        for (index, (node, &level)) in nodes.iter().zip(&levels).enumerate() {
+            let leaf_ok = |&v: &usize| if level == 1 { v < states } else { v < index && levels[v] == level - 1 };
+            if node.len() != states || level == 0 || !node.iter().all(leaf_ok) {
+                return Err(bad(format!("@TREE node {index} is malformed")));
+            }
+        }
+        if levels.last() != Some(&(neighbors + 1)) {
+            return Err(bad("the last @TREE node must be the root"));
+        }
+        Ok(Self {
+            states: states as u8,
+            neighbors,
+            nodes,
+        })
+    }
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn neighborhood(&self) -> TableNeighborhood {
+        if self.neighbors == 8 { TableNeighborhood::Moore } else { TableNeighborhood::VonNeumann }
+    }
+
+    // `neighbors` are in @TABLE order, clockwise from north.
+    pub fn next_state(&self, center: u8, neighbors: &[u8]) -> u8 {
+        let order: &[usize] = if self.neighbors == 8 { &[7, 1, 5, 3, 0, 6, 2, 4] } else { &[0, 3, 1, 2] };
+        let inputs = order.iter().map(|&i| neighbors[i]).chain([center]);
+        let mut node = self.nodes.len() - 1;
+        for state in inputs {
+            node = self.nodes[node][state as usize];
+        }
+        node as u8
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum RuleBody {
+    Table(RuleTable),
+    Tree(RuleTree),
+}
+
+// A Golly .rule file: its name, its @TABLE or @TREE, and any @COLORS.
+// Other sections such as @ICONS are skipped.
+#[derive(Clone, Debug)]
+pub struct RuleFile {
+    pub name: String,
+    pub body: RuleBody,
+    colors: HashMap<u8, [u8; 4]>,
+}
+
+impl RuleFile {
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut name = String::new();
+        let mut sections: HashMap<String, Vec<&str>> = HashMap::new();
+        let mut current = None;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('@') {
+                let (section, rest) = header.split_once(char::is_whitespace).unwrap_or((header, ""));
+                if section == "RULE" {
+                    name = rest.trim().to_string();
+                }
+                current = Some(section.to_string());
+                sections.entry(section.to_string()).or_default();
+            } else if let Some(section) = &current {
+                sections.get_mut(section).expect("section was created").push(line);
+            }
+        }
+        let body = match (sections.get("TABLE"), sections.get("TREE")) {
+            (Some(lines), _) => RuleBody::Table(RuleTable::parse(lines)?),
+            (None, Some(lines)) => RuleBody::Tree(RuleTree::parse(lines)?),
+            (None, None) => return Err(bad("rule file has no @TABLE or @TREE")),
+        };
+        let mut colors = HashMap::new();
+        for line in sections.get("COLORS").into_iter().flatten() {
+            let numbers: Vec<u8> = line.split_whitespace().filter_map(|n| n.parse().ok()).collect();
+            if let [state, r, g, b] = numbers[..] {
+                colors.insert(state, [r, g, b, 255]);
+            }
+        }
+        Ok(Self { name, body, colors })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn states(&self) -> u8 {
+        match &self.body {
+            RuleBody::Table(table) => table.states(),
+            RuleBody::Tree(tree) => tree.states(),
+        }
+    }
+
+    pub fn neighborhood(&self) -> TableNeighborhood {
+        match &self.body {
+            RuleBody::Table(table) => table.neighborhood(),
+            RuleBody::Tree(tree) => tree.neighborhood(),
+        }
+    }
+
+    pub fn next_state(&self, center: u8, neighbors: &[u8]) -> u8 {
+        match &self.body {
+            RuleBody::Table(table) => table.next_state(center, neighbors),
+            RuleBody::Tree(tree) => tree.next_state(center, neighbors),
+        }
+    }
+
+    pub fn color(&self, state: u8) -> Option<[u8; 4]> {
+        self.colors.get(&state).copied()
+    }
+}
+
+// Runs a loaded rule file. Lookups are memoized per neighborhood since
+// tables can be long and the same handful of patterns cover most cells.
+pub struct RuleFileAutomaton {
+    grid: Grid,
+    rule: RuleFile,
+    boundary: BoundaryCondition,
+    cache: HashMap<[u8; 9], u8>,
+    generation: u64,
+}
+
+impl RuleFileAutomaton {
+    pub fn new(grid: Grid, rule: RuleFile) -> Self {
+        Self {
+            grid,
+            rule,
+            boundary: BoundaryCondition::default(),
+            cache: HashMap::new(),
+            generation: 0,
+        }
+    }
+
+    pub fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn rule(&self) -> &RuleFile {
+        &self.rule
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl Simulator for RuleFileAutomaton {
+    fn step(&mut self) {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        let offsets = self.rule.neighborhood().offsets();
+        let mut next = Grid::new(rows, cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let mut key = [0; 9];
+                key[0] = self.grid.state(row, col);
+                for (slot, (dr, dc)) in key[1..].iter_mut().zip(offsets) {
+                    *slot = match self.boundary.resolve(row as isize + dr, col as isize + dc, rows, cols) {
+                        Resolved::Cell(r, c) => self.grid.state(r, c),
+                        Resolved::Fixed(alive) => alive as u8,
+                    };
+                }
+                let rule = &self.rule;
+                let state = *self
+                    .cache
+                    .entry(key)
+                    .or_insert_with(|| rule.next_state(key[0], &key[1..=offsets.len()]));
+                next.set_state(row, col, state);
+            }
+        }
+        self.grid = next;
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.grid.rows(), self.grid.cols())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        self.grid = self.grid.resized(rows, cols, anchor);
+    }
+
+    fn boundary(&self) -> Option<BoundaryCondition> {
+        Some(self.boundary)
+    }
+
+    fn set_boundary(&mut self, boundary: BoundaryCondition) {
+        self.boundary = boundary;
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.grid.state(row, col) != 0
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        let state = self.grid.state(row, col);
+        match (self.rule.color(state), state) {
+            (Some(rgba), _) => Shade::Rgba(rgba),
+            (None, 0) => Shade::Dead,
+            (None, 1) if self.rule.states() == 2 => Shade::Alive,
+            (None, state) => Shade::Rgba(hue((state - 1) as f32 / (self.rule.states() - 1) as f32)),
+        }
+    }
+
+    fn set_cell(&mut self, row: usize, col: usize, alive: bool) {
+        self.grid.set(row, col, alive);
+    }
+
+    fn save(&self) -> Option<Snapshot> {
+        Some(Snapshot {
+            generation: self.generation,
+            grid: self.grid.clone(),
+        })
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        self.grid = snapshot.grid.clone();
+        self.generation = snapshot.generation;
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![
+            Memory::of("grid", self.grid.cells()),
+            Memory::new("cache", self.cache.capacity() * std::mem::size_of::<([u8; 9], u8)>()),
+        ]
+    }
+}
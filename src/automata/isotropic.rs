@@ -0,0 +1,302 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::boundary::{BoundaryCondition, Resolved};
+use crate::{Anchor, Error, Grid, Memory, Shade, Simulator, Snapshot};
+
+// Neighbors in clockwise order starting from north; bit `i` of a
+// configuration is set when the neighbor at `RING[i]` is alive.
+const RING: [(isize, isize); 8] = [(-1, 0), (-1, 1), (0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1)];
+
+const N: u8 = 1 << 0;
+const NE: u8 = 1 << 1;
+const E: u8 = 1 << 2;
+const SE: u8 = 1 << 3;
+const S: u8 = 1 << 4;
+const SW: u8 = 1 << 5;
+const W: u8 = 1 << 6;
+const NW: u8 = 1 << 7;
+
+// One representative configuration per Hensel letter for counts 1-4; the
+// classes for 5-7 neighbors are the complements of those for 3-1.
+const HENSEL: &[(u8, char, u8)] = &[
+    (1, 'e', N),
+    (1, 'c', NE),
+    (2, 'a', N | NE),
+    (2, 'e', N | E),
+    (2, 'k', N | SE),
+    (2, 'i', N | S),
+    (2, 'c', NE | SE),
+    (2, 'n', NE | SW),
+    (3, 'a', N | NE | E),
+    (3, 'n', N | NE | SE),
+    (3, 'r', N | NE | S),
+    (3, 'q', N | NE | SW),
+    (3, 'j', N | NE | W),
+    (3, 'i', N | NE | NW),
+    (3, 'e', N | E | S),
+    (3, 'k', N | E | SW),
+    (3, 'y', N | SE | SW),
+    (3, 'c', NE | SE | SW),
+    (4, 'a', N | NE | E | SE),
+    (4, 'r', N | NE | E | S),
+    (4, 'q', N | NE | E | SW),
+    (4, 'i', N | NE | SE | S),
+    (4, 'y', N | NE | SE | SW),
+    (4, 'k', N | NE | SE | W),
+    (4, 'n', N | NE | SE | NW),
+    (4, 'z', N | NE | S | SW),
+    (4, 'j', N | NE | S | W),
+    (4, 't', N | SE | S | SW),
+    (4, 'w', N | NE | SW | W),
+    (4, 'e', N | E | S | W),
+    (4, 'c', NE | SE | SW | NW),
+];
+
+fn rotate(config: u8) -> u8 {
+    config.rotate_left(2)
+}
+
+fn reflect(config: u8) -> u8 {
+    (0..8).filter(|i| config & (1 << i) != 0).fold(0, |out, i| out | 1 << ((8 - i) % 8))
+}
+
+fn symmetries(config: u8) -> impl Iterator<Item = u8> {
+    [config, reflect(config)]
+        .into_iter()
+        .flat_map(|base| std::iter::successors(Some(base), |&c| Some(rotate(c))).take(4))
+}
+
+// Every configuration with `count` live neighbors, optionally restricted to
+// the given Hensel letters.
+fn configurations(count: u8, letters: Option<&[char]>) -> Vec<u8> {
+    let letter_of = |config: u8| {
+        HENSEL.iter().find_map(|&(n, letter, rep)| {
+            let rep = if count > 4 && n == 8 - count { !rep } else { rep };
+            (n == count.min(8 - count) && symmetries(rep).any(|c| c == config)).then_some(letter)
+        })
+    };
+    (0..=255u8)
+        .filter(|config| config.count_ones() as u8 == count)
+        .filter(|&config| match letters {
+            None => true,
+            Some(letters) => letter_of(config).is_some_and(|letter| letters.contains(&letter)),
+        })
+        .collect()
+}
+
+fn valid_letters(count: u8) -> Vec<char> {
+    let n = count.min(8 - count);
+    HENSEL.iter().filter(|&&(c, _, _)| c == n).map(|&(_, letter, _)| letter).collect()
+}
+
+fn parse_conditions(spec: &str, rule: &str) -> Result<[bool; 256], Error> {
+    let mut table = [false; 256];
+    let mut chars = spec.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let count = match ch.to_digit(10) {
+            Some(n) if n <= 8 => n as u8,
+            _ => return Err(Error::Rule(format!("{rule:?}: unexpected {ch:?}"))),
+        };
+        let negated = chars.next_if_eq(&'-').is_some();
+        let mut letters = Vec::new();
+        while let Some(letter) = chars.next_if(|c| c.is_ascii_lowercase()) {
+            if !valid_letters(count).contains(&letter) {
+                return Err(Error::Rule(format!("{rule:?}: {count}{letter} is not a Hensel class")));
+            }
+            letters.push(letter);
+        }
+        let selected = if letters.is_empty() {
+            configurations(count, None)
+        } else if negated {
+            let excluded = configurations(count, Some(&letters));
+            configurations(count, None)
+                .into_iter()
+                .filter(|config| !excluded.contains(config))
+                .collect()
+        } else {
+            configurations(count, Some(&letters))
+        };
+        for config in selected {
+            table[config as usize] = true;
+        }
+    }
+    Ok(table)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IsotropicRule {
+    notation: String,
+    birth: [bool; 256],
+    survival: [bool; 256],
+    states: u8,
+}
+
+impl IsotropicRule {
+    // Hensel notation such as "B2-a/S12" or "B3/S23-a4i", optionally with a
+    // trailing "/C<n>" for Generations-style decay.
+    pub fn parse(rule: &str) -> Result<Self, Error> {
+        let mut parsed = Self {
+            notation: rule.trim().to_string(),
+            birth: [false; 256],
+            survival: [false; 256],
+            states: 2,
+        };
+        for part in parsed.notation.clone().split('/') {
+            let (tag, spec) = part.split_at(part.chars().next().map_or(0, char::len_utf8));
+            match tag {
+                "B" | "b" => parsed.birth = parse_conditions(spec, rule)?,
+                "S" | "s" => parsed.survival = parse_conditions(spec, rule)?,
+                "C" | "c" | "G" | "g" => {
+                    parsed.states = match spec.parse::<u8>() {
+                        Ok(states) if states >= 2 => states,
+                        _ => return Err(Error::Rule(format!("{rule:?}: bad state count"))),
+                    }
+                }
+                _ => return Err(Error::Rule(format!("{rule:?}: expected B.../S... sections"))),
+            }
+        }
+        Ok(parsed)
+    }
+
+    pub fn births(&self, config: u8) -> bool {
+        self.birth[config as usize]
+    }
+
+    pub fn survives(&self, config: u8) -> bool {
+        self.survival[config as usize]
+    }
+}
+
+impl FromStr for IsotropicRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for IsotropicRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.notation)
+    }
+}
+
+pub struct IsotropicLife {
+    grid: Grid,
+    generation: u64,
+    rule: IsotropicRule,
+    boundary: BoundaryCondition,
+}
+
+impl IsotropicLife {
+    pub fn new(initial: Grid, rule: IsotropicRule) -> Self {
+        Self {
+            grid: initial,
+            generation: 0,
+            rule,
+            boundary: BoundaryCondition::default(),
+        }
+    }
+
+    pub fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn rule(&self) -> &IsotropicRule {
+        &self.rule
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn configuration(&self, row: usize, col: usize) -> u8 {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        RING.iter().enumerate().fold(0, |config, (bit, (dr, dc))| {
+            let alive = match self.boundary.resolve(row as isize + dr, col as isize + dc, rows, cols) {
+                Resolved::Cell(r, c) => self.grid.state(r, c) == 1,
+                Resolved::Fixed(alive) => alive,
+            };
+            if alive { config | 1 << bit } else { config }
+        })
+    }
+}
+
+impl Simulator for IsotropicLife {
+    fn step(&mut self) {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        let mut next = Grid::new(rows, cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let config = self.configuration(row, col);
+                let state = match self.grid.state(row, col) {
+                    0 if self.rule.births(config) => 1,
+                    0 => 0,
+                    1 if self.rule.survives(config) => 1,
+                    dying => (dying + 1) % self.rule.states,
+                };
+                next.set_state(row, col, state);
+            }
+        }
+        self.grid = next;
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.grid.rows(), self.grid.cols())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        self.grid = self.grid.resized(rows, cols, anchor);
+    }
+
+    fn boundary(&self) -> Option<BoundaryCondition> {
+        Some(self.boundary)
+    }
+
+    fn set_boundary(&mut self, boundary: BoundaryCondition) {
+        self.boundary = boundary;
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.grid.state(row, col) == 1
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        match self.grid.state(row, col) {
+            0 => Shade::Dead,
+            1 => Shade::Alive,
+            dying => Shade::Dying((dying - 1) as f32 / (self.rule.states - 1) as f32),
+        }
+    }
+
+    fn set_cell(&mut self, row: usize, col: usize, alive: bool) {
+        self.grid.set(row, col, alive);
+    }
+
+    fn save(&self) -> Option<Snapshot> {
+        Some(Snapshot {
+            generation: self.generation,
+            grid: self.grid.clone(),
+        })
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        self.grid = snapshot.grid.clone();
+        self.generation = snapshot.generation;
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::of("grid", self.grid.cells())]
+    }
+}
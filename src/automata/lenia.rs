@@ -0,0 +1,253 @@
+use crate::random::Rng;
+use crate::render::Colormap;
+use crate::{Anchor, Error, FloatGrid, Memory, Parameter, Shade, Simulator, Step};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KernelCore {
+    #[default]
+    Exponential,
+    Polynomial,
+    Step,
+}
+
+impl KernelCore {
+    // Shell profile over the normalized radius 0 < r < 1.
+    pub fn eval(self, r: f32) -> f32 {
+        match self {
+            KernelCore::Exponential => (4.0 - 1.0 / (r * (1.0 - r))).exp(),
+            KernelCore::Polynomial => (4.0 * r * (1.0 - r)).powi(4),
+            KernelCore::Step => {
+                if (0.25..=0.75).contains(&r) { 1.0 } else { 0.0 }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Growth {
+    #[default]
+    Gaussian,
+    Polynomial,
+    Step,
+}
+
+impl Growth {
+    // Maps a neighborhood potential to a growth rate in [-1, 1].
+    pub fn eval(self, potential: f32, mu: f32, sigma: f32) -> f32 {
+        let d = potential - mu;
+        match self {
+            Growth::Gaussian => 2.0 * (-(d * d) / (2.0 * sigma * sigma)).exp() - 1.0,
+            Growth::Polynomial => 2.0 * (1.0 - d * d / (9.0 * sigma * sigma)).max(0.0).powi(4) - 1.0,
+            Growth::Step => {
+                if d.abs() <= sigma { 1.0 } else { -1.0 }
+            }
+        }
+    }
+}
+
+// Widest kernel radius; the kernel grows with its square, and every cell
+// weighs the whole kernel each step.
+const MAX_RADIUS: usize = 256;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LeniaParams {
+    pub radius: usize,
+    pub dt: f32,
+    pub mu: f32,
+    pub sigma: f32,
+    pub peaks: Vec<f32>,
+    pub core: KernelCore,
+    pub growth: Growth,
+}
+
+impl Default for LeniaParams {
+    fn default() -> Self {
+        Self {
+            radius: 13,
+            dt: 0.1,
+            mu: 0.15,
+            sigma: 0.015,
+            peaks: vec![1.0],
+            core: KernelCore::default(),
+            growth: Growth::default(),
+        }
+    }
+}
+
+impl LeniaParams {
+    // Turns away parameters that can't make a kernel or a step.
+    pub fn check(&self) -> Result<(), Error> {
+        let bad = |msg: &str| Err(Error::Rule(format!("Lenia {msg}")));
+        if !(1..=MAX_RADIUS).contains(&self.radius) {
+            return bad(&format!("needs a kernel radius from 1 to {MAX_RADIUS}"));
+        }
+        if self.peaks.is_empty() || self.peaks.iter().any(|peak| !(0.0..=1.0).contains(peak)) {
+            return bad("needs one or more kernel peaks, each from 0 to 1");
+        }
+        if self.peaks.iter().all(|&peak| peak == 0.0) {
+            return bad("needs a kernel peak above 0");
+        }
+        if !(self.sigma > 0.0 && self.sigma.is_finite() && self.mu.is_finite()) {
+            return bad("needs a finite mu and a sigma above 0");
+        }
+        if !(self.dt > 0.0 && self.dt <= 1.0) {
+            return bad("needs a time step above 0 and at most 1");
+        }
+        Ok(())
+    }
+
+    // Non-zero kernel weights as (dr, dc, weight), normalized to sum to one.
+    pub fn kernel(&self) -> Vec<(isize, isize, f32)> {
+        let r = self.radius as isize;
+        let mut weights = Vec::new();
+        // Without rings there's nothing to weigh; `check` turns these away.
+        if self.peaks.is_empty() {
+            return weights;
+        }
+        for dr in -r..=r {
+            for dc in -r..=r {
+                let distance = ((dr * dr + dc * dc) as f32).sqrt() / self.radius as f32;
+                if distance <= 0.0 || distance >= 1.0 {
+                    continue;
+                }
+                let shell = distance * self.peaks.len() as f32;
+                let index = (shell as usize).min(self.peaks.len() - 1);
+                let weight = self.peaks[index] * self.core.eval(shell - index as f32);
+                if weight > 0.0 {
+                    weights.push((dr, dc, weight));
+                }
+            }
+        }
+        let total: f32 = weights.iter().map(|w| w.2).sum();
+        weights.iter_mut().for_each(|w| w.2 /= total);
+        weights
+    }
+}
+
+pub struct Lenia {
+    world: FloatGrid,
+    params: LeniaParams,
+    kernel: Vec<(isize, isize, f32)>,
+    colormap: Colormap,
+    generation: u64,
+}
+
+impl Lenia {
+    pub fn new(world: FloatGrid, params: LeniaParams) -> Self {
+        let kernel = params.kernel();
+        Self {
+            world,
+            params,
+            kernel,
+            colormap: Colormap::default(),
+            generation: 0,
+        }
+    }
+
+    // A square of uniform noise in the middle of an empty world.
+    pub fn random(rows: usize, cols: usize, params: LeniaParams, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut world = FloatGrid::new(rows, cols);
+        let side = (params.radius * 4).min(rows).min(cols);
+        let (top, left) = ((rows - side) / 2, (cols - side) / 2);
+        for row in top..top + side {
+            for col in left..left + side {
+                world.set(row, col, rng.next_f64() as f32);
+            }
+        }
+        Self::new(world, params)
+    }
+
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    pub fn params(&self) -> &LeniaParams {
+        &self.params
+    }
+
+    pub fn world(&self) -> &FloatGrid {
+        &self.world
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn potential(&self, row: usize, col: usize) -> f32 {
+        let (rows, cols) = (self.world.rows() as isize, self.world.cols() as isize);
+        self.kernel
+            .iter()
+            .map(|&(dr, dc, weight)| {
+                let r = (row as isize + dr).rem_euclid(rows) as usize;
+                let c = (col as isize + dc).rem_euclid(cols) as usize;
+                weight * self.world.get(r, c)
+            })
+            .sum()
+    }
+}
+
+impl Simulator for Lenia {
+    fn step(&mut self) {
+        let (rows, cols) = (self.world.rows(), self.world.cols());
+        let mut next = FloatGrid::new(rows, cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let growth = self.params.growth.eval(self.potential(row, col), self.params.mu, self.params.sigma);
+                let value = self.world.get(row, col) + self.params.dt * growth;
+                next.set(row, col, value.clamp(0.0, 1.0));
+            }
+        }
+        self.world = next;
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.world.rows(), self.world.cols())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        self.world = self.world.resized(rows, cols, anchor);
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.world.get(row, col) >= 0.5
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        Shade::Rgba(self.colormap.color(self.world.get(row, col)))
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![
+            Parameter::new("radius", self.params.radius as f64, Step::Add(1.0)),
+            Parameter::new("mu", self.params.mu as f64, Step::Add(0.005)),
+            Parameter::new("sigma", self.params.sigma as f64, Step::Add(0.001)),
+            Parameter::new("dt", self.params.dt as f64, Step::Add(0.01)),
+        ]
+    }
+
+    // A new radius means a new kernel.
+    fn set_parameter(&mut self, name: &str, value: f64) {
+        let value = value as f32;
+        match name {
+            "radius" => {
+                self.params.radius = (value.round().max(1.0) as usize).min(MAX_RADIUS);
+                self.kernel = self.params.kernel();
+            }
+            "mu" => self.params.mu = value.max(0.0),
+            "sigma" => self.params.sigma = value.max(0.001),
+            "dt" => self.params.dt = value.clamp(0.01, 1.0),
+            _ => {}
+        }
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::of("world", self.world.cells()), Memory::of("kernel", &self.kernel)]
+    }
+}
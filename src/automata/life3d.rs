@@ -0,0 +1,341 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::random::Rng;
+use crate::render::lerp;
+use crate::{Error, Memory, Parameter, Shade, Simulator, Step};
+
+const NEAR: [u8; 4] = [255, 200, 60, 255];
+const FAR: [u8; 4] = [90, 20, 120, 255];
+
+// Birth and survival counts over the 26 surrounding voxels. Parses Bays'
+// four-digit form (survive lo, survive hi, birth lo, birth hi, so "4555" is
+// S4-5/B5) or B/S sections of comma-separated counts and ranges such as
+// "B6/S5-7" or "B14-19/S13-26".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule3D {
+    birth: u32,
+    survival: u32,
+}
+
+fn range_mask(lo: u32, hi: u32) -> u32 {
+    (lo..=hi.min(26)).fold(0, |mask, n| mask | 1 << n)
+}
+
+fn parse_counts(section: &str, rule: &str) -> Result<u32, Error> {
+    let bad = || Error::Rule(format!("{rule:?}: counts must be numbers from 0 to 26"));
+    let count = |n: &str| n.trim().parse::<u32>().ok().filter(|&n| n <= 26).ok_or_else(bad);
+    section
+        .split(',')
+        .filter(|item| !item.is_empty())
+        .try_fold(0, |mask, item| match item.split_once('-') {
+            Some((lo, hi)) => Ok(mask | range_mask(count(lo)?, count(hi)?)),
+            None => Ok(mask | 1 << count(item)?),
+        })
+}
+
+impl Rule3D {
+    pub fn new(birth: &[u32], survival: &[u32]) -> Self {
+        let mask = |counts: &[u32]| counts.iter().filter(|&&n| n <= 26).fold(0, |mask, n| mask | 1 << n);
+        Self {
+            birth: mask(birth),
+            survival: mask(survival),
+        }
+    }
+
+    // Bays' 4555, the best known glider-supporting 3D rule.
+    pub fn bays() -> Self {
+        Self {
+            birth: range_mask(5, 5),
+            survival: range_mask(4, 5),
+        }
+    }
+
+    pub fn parse(rule: &str) -> Result<Self, Error> {
+        let rule = rule.trim();
+        if rule.len() == 4 && rule.chars().all(|ch| ch.is_ascii_digit()) {
+            let d: Vec<u32> = rule.chars().filter_map(|ch| ch.to_digit(10)).collect();
+            return Ok(Self {
+                birth: range_mask(d[2], d[3]),
+                survival: range_mask(d[0], d[1]),
+            });
+        }
+        let (mut birth, mut survival) = (0, 0);
+        for part in rule.split('/') {
+            let mut chars = part.chars();
+            match chars.next().map(|ch| ch.to_ascii_uppercase()) {
+                Some('B') => birth = parse_counts(chars.as_str(), rule)?,
+                Some('S') => survival = parse_counts(chars.as_str(), rule)?,
+                _ => return Err(Error::Rule(format!("{rule:?}: expected EEFF digits or B.../S... sections"))),
+            }
+        }
+        Ok(Self { birth, survival })
+    }
+
+    pub fn births(&self, alive_neighbors: u32) -> bool {
+        alive_neighbors <= 26 && self.birth & (1 << alive_neighbors) != 0
+    }
+
+    pub fn survives(&self, alive_neighbors: u32) -> bool {
+        alive_neighbors <= 26 && self.survival & (1 << alive_neighbors) != 0
+    }
+}
+
+impl Default for Rule3D {
+    fn default() -> Self {
+        Self::bays()
+    }
+}
+
+impl FromStr for Rule3D {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Rule3D {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |mask: u32| {
+            (0..=26)
+                .filter(|n| mask & (1 << n) != 0)
+                .map(|n: u32| n.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum View {
+    // Every live voxel, rotated and seen from the front, nearer ones brighter.
+    Cloud,
+    // A single layer of the volume.
+    Slice,
+}
+
+// Life on a toroidal depth x rows x cols volume. The 2D board it shows is a
+// square big enough for the volume at any rotation; the view is redrawn
+// into it after every step and every change of angle or layer.
+pub struct Life3D {
+    depth: usize,
+    rows: usize,
+    cols: usize,
+    cells: Vec<bool>,
+    rule: Rule3D,
+    view: View,
+    yaw: f64,
+    pitch: f64,
+    layer: usize,
+    side: usize,
+    image: Vec<Option<f32>>,
+    generation: u64,
+}
+
+impl Life3D {
+    pub fn new(depth: usize, rows: usize, cols: usize, rule: Rule3D) -> Self {
+        let diagonal = ((depth * depth + rows * rows + cols * cols) as f64).sqrt();
+        let side = diagonal.ceil() as usize;
+        let mut life = Self {
+            depth,
+            rows,
+            cols,
+            cells: vec![false; depth * rows * cols],
+            rule,
+            view: View::Cloud,
+            yaw: 30.0,
+            pitch: 20.0,
+            layer: depth / 2,
+            side,
+            image: vec![None; side * side],
+            generation: 0,
+        };
+        life.redraw();
+        life
+    }
+
+    // A random cube of voxels a third of the volume across, in the middle.
+    pub fn random(depth: usize, rows: usize, cols: usize, rule: Rule3D, density: f64, seed: u64) -> Self {
+        let mut life = Self::new(depth, rows, cols, rule);
+        let mut rng = Rng::new(seed);
+        let span = |n: usize| n / 3..n - n / 3;
+        for z in span(depth) {
+            for y in span(rows) {
+                for x in span(cols) {
+                    life.set(z, y, x, rng.chance(density));
+                }
+            }
+        }
+        life.redraw();
+        life
+    }
+
+    pub fn with_view(mut self, view: View) -> Self {
+        self.view = view;
+        self.redraw();
+        self
+    }
+
+    pub fn rule(&self) -> &Rule3D {
+        &self.rule
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn index(&self, z: usize, y: usize, x: usize) -> usize {
+        (z * self.rows + y) * self.cols + x
+    }
+
+    pub fn get(&self, z: usize, y: usize, x: usize) -> bool {
+        self.cells[self.index(z, y, x)]
+    }
+
+    // Changes don't show until the next step or view change.
+    pub fn set(&mut self, z: usize, y: usize, x: usize, alive: bool) {
+        let i = self.index(z, y, x);
+        self.cells[i] = alive;
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&alive| alive).count()
+    }
+
+    fn alive_neighbors(&self, z: usize, y: usize, x: usize) -> u32 {
+        let wrap = |i: usize, d: isize, n: usize| (i as isize + d).rem_euclid(n as isize) as usize;
+        let mut count = 0;
+        for dz in -1..=1 {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if (dz, dy, dx) != (0, 0, 0)
+                        && self.get(wrap(z, dz, self.depth), wrap(y, dy, self.rows), wrap(x, dx, self.cols))
+                    {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    fn redraw(&mut self) {
+        self.image.fill(None);
+        match self.view {
+            View::Cloud => self.project(),
+            View::Slice => {
+                let (top, left) = ((self.side - self.rows) / 2, (self.side - self.cols) / 2);
+                for y in 0..self.rows {
+                    for x in 0..self.cols {
+                        if self.get(self.layer, y, x) {
+                            self.image[(top + y) * self.side + left + x] = Some(0.0);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Orthographic projection after turning by yaw about the vertical axis
+    // and then by pitch about the horizontal one. Depth is kept in 0..=1
+    // with 0 nearest, and the nearest voxel wins each pixel.
+    fn project(&mut self) {
+        let (sin_yaw, cos_yaw) = self.yaw.to_radians().sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.to_radians().sin_cos();
+        let center = |n: usize| (n as f64 - 1.0) / 2.0;
+        let half = self.side as f64 / 2.0;
+        for z in 0..self.depth {
+            for y in 0..self.rows {
+                for x in 0..self.cols {
+                    if !self.get(z, y, x) {
+                        continue;
+                    }
+                    let (px, py, pz) = (x as f64 - center(self.cols), y as f64 - center(self.rows), z as f64 - center(self.depth));
+                    let (rx, rz) = (px * cos_yaw + pz * sin_yaw, pz * cos_yaw - px * sin_yaw);
+                    let (ry, rz) = (py * cos_pitch - rz * sin_pitch, rz * cos_pitch + py * sin_pitch);
+                    let (col, row) = ((rx + half) as usize, (ry + half) as usize);
+                    if row >= self.side || col >= self.side {
+                        continue;
+                    }
+                    let depth = ((rz + half) / self.side as f64).clamp(0.0, 1.0) as f32;
+                    let pixel = &mut self.image[row * self.side + col];
+                    if pixel.is_none_or(|nearest| depth < nearest) {
+                        *pixel = Some(depth);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Simulator for Life3D {
+    fn step(&mut self) {
+        let mut next = vec![false; self.cells.len()];
+        for z in 0..self.depth {
+            for y in 0..self.rows {
+                for x in 0..self.cols {
+                    let neighbors = self.alive_neighbors(z, y, x);
+                    next[self.index(z, y, x)] = if self.get(z, y, x) {
+                        self.rule.survives(neighbors)
+                    } else {
+                        self.rule.births(neighbors)
+                    };
+                }
+            }
+        }
+        self.cells = next;
+        self.generation += 1;
+        self.redraw();
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.side, self.side)
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.image[row * self.side + col].is_some()
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        match self.image[row * self.side + col] {
+            Some(_) if self.view == View::Slice => Shade::Alive,
+            Some(depth) => Shade::Rgba(lerp(NEAR, FAR, depth)),
+            None => Shade::Dead,
+        }
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        let view = match self.view {
+            View::Cloud => 0.0,
+            View::Slice => 1.0,
+        };
+        vec![
+            // 0 for the cloud, 1 for a slice.
+            Parameter::new("view", view, Step::Add(1.0)),
+            Parameter::new("yaw", self.yaw, Step::Add(15.0)),
+            Parameter::new("pitch", self.pitch, Step::Add(15.0)),
+            Parameter::new("layer", self.layer as f64, Step::Add(1.0)),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) {
+        match name {
+            "view" => self.view = if value.rem_euclid(2.0) < 0.5 { View::Cloud } else { View::Slice },
+            "yaw" => self.yaw = value.rem_euclid(360.0),
+            "pitch" => self.pitch = value.rem_euclid(360.0),
+            "layer" => self.layer = (value.round() as isize).rem_euclid(self.depth as isize) as usize,
+            _ => return,
+        }
+        self.redraw();
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::of("volume", &self.cells), Memory::of("view", &self.image)]
+    }
+}
@@ -0,0 +1,295 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::boundary::{BoundaryCondition, Resolved};
+use crate::{Anchor, Error, Grid, Memory, Shade, Simulator, Snapshot};
+
+// Widest range a rule can have, as in Golly.
+const MAX_RANGE: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LtlNeighborhood {
+    Moore,
+    VonNeumann,
+    Circular,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LtlRule {
+    pub range: usize,
+    pub states: u8,
+    pub middle: bool,
+    pub survival: Vec<RangeInclusive<u32>>,
+    pub birth: Vec<RangeInclusive<u32>>,
+    pub neighborhood: LtlNeighborhood,
+}
+
+fn parse_ranges(spec: &str, rule: &str) -> Result<Vec<RangeInclusive<u32>>, Error> {
+    let bad = || Error::Rule(format!("{rule:?}: bad count range {spec:?}"));
+    spec.split('+')
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.split_once("..") {
+            Some((lo, hi)) => Ok(lo.parse().map_err(|_| bad())?..=hi.parse().map_err(|_| bad())?),
+            None => {
+                let n = part.parse().map_err(|_| bad())?;
+                Ok(n..=n)
+            }
+        })
+        .collect()
+}
+
+impl LtlRule {
+    // Golly-style notation, e.g. "R5,C0,M1,S34..58,B34..45,NM" for Bugs.
+    pub fn parse(rule: &str) -> Result<Self, Error> {
+        let mut parsed = Self {
+            range: 1,
+            states: 2,
+            middle: false,
+            survival: Vec::new(),
+            birth: Vec::new(),
+            neighborhood: LtlNeighborhood::Moore,
+        };
+        let mut tokens = rule.trim().split(',').peekable();
+        while let Some(token) = tokens.next() {
+            let (tag, value) = token.split_at(token.chars().next().map_or(0, char::len_utf8));
+            let number = || {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| Error::Rule(format!("{rule:?}: bad value in {token:?}")))
+            };
+            match tag.to_ascii_uppercase().as_str() {
+                "R" => parsed.range = number()?,
+                "C" => parsed.states = number()?.clamp(2, 255) as u8,
+                "M" => parsed.middle = number()? != 0,
+                "S" | "B" => {
+                    let mut spec = value.to_string();
+                    while let Some(next) = tokens.peek() {
+                        if !next.starts_with(|ch: char| ch.is_ascii_digit()) {
+                            break;
+                        }
+                        spec.push('+');
+                        spec.push_str(next);
+                        tokens.next();
+                    }
+                    let ranges = parse_ranges(&spec, rule)?;
+                    if tag.eq_ignore_ascii_case("S") {
+                        parsed.survival = ranges;
+                    } else {
+                        parsed.birth = ranges;
+                    }
+                }
+                "N" => {
+                    parsed.neighborhood = match value.to_ascii_uppercase().as_str() {
+                        "M" => LtlNeighborhood::Moore,
+                        "N" => LtlNeighborhood::VonNeumann,
+                        "C" => LtlNeighborhood::Circular,
+                        _ => return Err(Error::Rule(format!("{rule:?}: unknown neighborhood {token:?}"))),
+                    }
+                }
+                _ => return Err(Error::Rule(format!("{rule:?}: unexpected {token:?}"))),
+            }
+        }
+        if !(1..=MAX_RANGE).contains(&parsed.range) {
+            return Err(Error::Rule(format!("{rule:?}: range must be from 1 to {MAX_RANGE}")));
+        }
+        Ok(parsed)
+    }
+
+    fn half_width(&self, dr: isize) -> isize {
+        let r = self.range as isize;
+        match self.neighborhood {
+            LtlNeighborhood::Moore => r,
+            LtlNeighborhood::VonNeumann => r - dr.abs(),
+            LtlNeighborhood::Circular => (((r * r - dr * dr) as f64).sqrt()).floor() as isize,
+        }
+    }
+}
+
+impl FromStr for LtlRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for LtlRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranges = |ranges: &[RangeInclusive<u32>]| {
+            ranges
+                .iter()
+                .map(|r| format!("{}..{}", r.start(), r.end()))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let states = if self.states > 2 { self.states } else { 0 };
+        let neighborhood = match self.neighborhood {
+            LtlNeighborhood::Moore => 'M',
+            LtlNeighborhood::VonNeumann => 'N',
+            LtlNeighborhood::Circular => 'C',
+        };
+        write!(
+            f,
+            "R{},C{},M{},S{},B{},N{}",
+            self.range,
+            states,
+            self.middle as u8,
+            ranges(&self.survival),
+            ranges(&self.birth),
+            neighborhood
+        )
+    }
+}
+
+pub struct LargerThanLife {
+    grid: Grid,
+    generation: u64,
+    rule: LtlRule,
+    boundary: BoundaryCondition,
+}
+
+impl LargerThanLife {
+    pub fn new(initial: Grid, rule: LtlRule) -> Self {
+        Self {
+            grid: initial,
+            generation: 0,
+            rule,
+            boundary: BoundaryCondition::default(),
+        }
+    }
+
+    pub fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn rule(&self) -> &LtlRule {
+        &self.rule
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // Summed-area table over the board padded by `range` cells of boundary,
+    // so every neighborhood sum is a handful of lookups however large it is.
+    fn summed_area(&self) -> (Vec<u32>, usize) {
+        let r = self.rule.range as isize;
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        let width = cols + 2 * self.rule.range + 1;
+        let height = rows + 2 * self.rule.range + 1;
+        let mut sat = vec![0u32; width * height];
+        for y in 1..height {
+            let mut line = 0;
+            for x in 1..width {
+                let alive = match self.boundary.resolve(y as isize - 1 - r, x as isize - 1 - r, rows, cols) {
+                    Resolved::Cell(row, col) => self.grid.state(row, col) == 1,
+                    Resolved::Fixed(alive) => alive,
+                };
+                line += alive as u32;
+                sat[y * width + x] = sat[(y - 1) * width + x] + line;
+            }
+        }
+        (sat, width)
+    }
+
+    fn count(&self, sat: &[u32], width: usize, row: usize, col: usize) -> u32 {
+        let r = self.rule.range;
+        let (cy, cx) = (row + r, col + r);
+        let rect = |y0: usize, y1: usize, x0: usize, x1: usize| {
+            sat[(y1 + 1) * width + x1 + 1] + sat[y0 * width + x0] - sat[y0 * width + x1 + 1] - sat[(y1 + 1) * width + x0]
+        };
+        let total = match self.rule.neighborhood {
+            LtlNeighborhood::Moore => rect(cy - r, cy + r, cx - r, cx + r),
+            _ => (-(r as isize)..=r as isize)
+                .map(|dr| {
+                    let y = (cy as isize + dr) as usize;
+                    let w = self.rule.half_width(dr) as usize;
+                    rect(y, y, cx - w, cx + w)
+                })
+                .sum(),
+        };
+        let center = self.grid.state(row, col) == 1;
+        if self.rule.middle || !center { total } else { total - 1 }
+    }
+}
+
+impl Simulator for LargerThanLife {
+    fn step(&mut self) {
+        let (sat, width) = self.summed_area();
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        let mut next = Grid::new(rows, cols);
+        let within = |ranges: &[RangeInclusive<u32>], n: u32| ranges.iter().any(|r| r.contains(&n));
+        for row in 0..rows {
+            for col in 0..cols {
+                let n = self.count(&sat, width, row, col);
+                let state = match self.grid.state(row, col) {
+                    0 if within(&self.rule.birth, n) => 1,
+                    0 => 0,
+                    1 if within(&self.rule.survival, n) => 1,
+                    dying => (dying + 1) % self.rule.states,
+                };
+                next.set_state(row, col, state);
+            }
+        }
+        self.grid = next;
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.grid.rows(), self.grid.cols())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        self.grid = self.grid.resized(rows, cols, anchor);
+    }
+
+    fn boundary(&self) -> Option<BoundaryCondition> {
+        Some(self.boundary)
+    }
+
+    fn set_boundary(&mut self, boundary: BoundaryCondition) {
+        self.boundary = boundary;
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.grid.state(row, col) == 1
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        match self.grid.state(row, col) {
+            0 => Shade::Dead,
+            1 => Shade::Alive,
+            dying => Shade::Dying((dying - 1) as f32 / (self.rule.states - 1) as f32),
+        }
+    }
+
+    fn set_cell(&mut self, row: usize, col: usize, alive: bool) {
+        self.grid.set(row, col, alive);
+    }
+
+    fn save(&self) -> Option<Snapshot> {
+        Some(Snapshot {
+            generation: self.generation,
+            grid: self.grid.clone(),
+        })
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        self.grid = snapshot.grid.clone();
+        self.generation = snapshot.generation;
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::of("grid", self.grid.cells())]
+    }
+}
@@ -0,0 +1,178 @@
+use crate::random::Rng;
+use crate::{Anchor, Grid, Memory, Simulator, Snapshot};
+
+// Cells of a 2x2 block as bits of a 4-bit index.
+const NW: u8 = 1;
+const NE: u8 = 2;
+const SW: u8 = 4;
+const SE: u8 = 8;
+
+fn rotate_half(block: u8) -> u8 {
+    (block & NW) << 3 | (block & NE) << 1 | (block & SW) >> 1 | (block & SE) >> 3
+}
+
+// Maps every 2x2 block configuration to its replacement. Reversible rules
+// are exactly the tables that are permutations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockRule {
+    table: [u8; 16],
+}
+
+impl BlockRule {
+    pub fn new(table: [u8; 16]) -> Self {
+        Self { table: table.map(|block| block & 0xf) }
+    }
+
+    // Blocks with exactly two live cells are left alone; every other block
+    // is complemented, and three-cell blocks are also turned half way round.
+    pub fn critters() -> Self {
+        let mut table = [0; 16];
+        for (block, next) in table.iter_mut().enumerate() {
+            let block = block as u8;
+            *next = match block.count_ones() {
+                2 => block,
+                3 => rotate_half(!block & 0xf),
+                _ => !block & 0xf,
+            };
+        }
+        Self::new(table)
+    }
+
+    // Fredkin's billiard ball machine: a lone ball crosses its block
+    // diagonally and two balls meeting head-on leave on the other diagonal.
+    pub fn billiard_ball() -> Self {
+        let mut table: [u8; 16] = std::array::from_fn(|block| block as u8);
+        for single in [NW, NE, SW, SE] {
+            table[single as usize] = rotate_half(single);
+        }
+        table[(NW | SE) as usize] = NE | SW;
+        table[(NE | SW) as usize] = NW | SE;
+        Self::new(table)
+    }
+
+    pub fn is_reversible(&self) -> bool {
+        let mut seen = [false; 16];
+        self.table.iter().all(|&next| !std::mem::replace(&mut seen[next as usize], true))
+    }
+
+    pub fn apply(&self, block: u8) -> u8 {
+        self.table[block as usize & 0xf]
+    }
+}
+
+// Block cellular automaton on the Margolus neighborhood: the board is cut
+// into 2x2 blocks, each replaced as a unit, with the partition shifted one
+// cell diagonally every other generation. Even dimensions wrap around;
+// along an odd dimension the blocks overhanging the edge see empty cells.
+pub struct Margolus {
+    grid: Grid,
+    rule: BlockRule,
+    generation: u64,
+}
+
+impl Margolus {
+    pub fn new(grid: Grid, rule: BlockRule) -> Self {
+        Self {
+            grid,
+            rule,
+            generation: 0,
+        }
+    }
+
+    // Random cells with the given density in a square in the middle.
+    pub fn random(rows: usize, cols: usize, rule: BlockRule, density: f64, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut grid = Grid::new(rows, cols);
+        for row in rows / 4..rows - rows / 4 {
+            for col in cols / 4..cols - cols / 4 {
+                grid.set(row, col, rng.chance(density));
+            }
+        }
+        Self::new(grid, rule)
+    }
+
+    pub fn rule(&self) -> &BlockRule {
+        &self.rule
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // The board coordinate `start + step` along an axis of length `len`, if
+    // it belongs to the block.
+    fn along(start: usize, step: usize, len: usize) -> Option<usize> {
+        match start + step {
+            i if i < len => Some(i),
+            i if len.is_multiple_of(2) => Some(i - len),
+            _ => None,
+        }
+    }
+}
+
+impl Simulator for Margolus {
+    fn step(&mut self) {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        let offset = (self.generation % 2) as usize;
+        for top in (offset..rows).step_by(2) {
+            for left in (offset..cols).step_by(2) {
+                let cells = [(0, 0, NW), (0, 1, NE), (1, 0, SW), (1, 1, SE)].map(|(dr, dc, bit)| {
+                    let row = Self::along(top, dr, rows);
+                    let col = Self::along(left, dc, cols);
+                    (row.zip(col), bit)
+                });
+                let block = cells
+                    .iter()
+                    .filter(|(cell, _)| cell.is_some_and(|(r, c)| self.grid.get(r, c)))
+                    .fold(0, |block, (_, bit)| block | bit);
+                let next = self.rule.apply(block);
+                for (cell, bit) in cells {
+                    if let Some((r, c)) = cell {
+                        self.grid.set(r, c, next & bit != 0);
+                    }
+                }
+            }
+        }
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.grid.rows(), self.grid.cols())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        self.grid = self.grid.resized(rows, cols, anchor);
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.grid.get(row, col)
+    }
+
+    fn set_cell(&mut self, row: usize, col: usize, alive: bool) {
+        self.grid.set(row, col, alive);
+    }
+
+    fn save(&self) -> Option<Snapshot> {
+        Some(Snapshot {
+            generation: self.generation,
+            grid: self.grid.clone(),
+        })
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        self.grid = snapshot.grid.clone();
+        self.generation = snapshot.generation;
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::of("grid", self.grid.cells())]
+    }
+}
@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use rustfft::num_complex::Complex32;
+use rustfft::{Fft, FftPlanner};
+
+use crate::random::Rng;
+use crate::render::Colormap;
+use crate::{FloatGrid, Memory, Shade, Simulator};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SmoothLifeParams {
+    pub outer_radius: f32,
+    pub inner_radius: f32,
+    pub birth: (f32, f32),
+    pub death: (f32, f32),
+    pub alpha_n: f32,
+    pub alpha_m: f32,
+    // 1.0 applies the transition outright, which tends to wipe out the whole
+    // field; smaller steps blend towards it and keep structures alive.
+    pub dt: f32,
+}
+
+impl Default for SmoothLifeParams {
+    fn default() -> Self {
+        Self {
+            outer_radius: 12.0,
+            inner_radius: 4.0,
+            birth: (0.278, 0.365),
+            death: (0.267, 0.445),
+            alpha_n: 0.028,
+            alpha_m: 0.147,
+            dt: 0.1,
+        }
+    }
+}
+
+impl SmoothLifeParams {
+    fn sigma(x: f32, a: f32, alpha: f32) -> f32 {
+        1.0 / (1.0 + (-(x - a) * 4.0 / alpha).exp())
+    }
+
+    // Rafler's transition function over the inner filling `m` and the outer
+    // annulus filling `n`.
+    pub fn transition(&self, n: f32, m: f32) -> f32 {
+        let alive = Self::sigma(m, 0.5, self.alpha_m);
+        let lo = self.birth.0 * (1.0 - alive) + self.death.0 * alive;
+        let hi = self.birth.1 * (1.0 - alive) + self.death.1 * alive;
+        Self::sigma(n, lo, self.alpha_n) * (1.0 - Self::sigma(n, hi, self.alpha_n))
+    }
+}
+
+struct Fft2d {
+    rows: usize,
+    cols: usize,
+    row_forward: Arc<dyn Fft<f32>>,
+    row_inverse: Arc<dyn Fft<f32>>,
+    col_forward: Arc<dyn Fft<f32>>,
+    col_inverse: Arc<dyn Fft<f32>>,
+}
+
+impl Fft2d {
+    fn new(rows: usize, cols: usize) -> Self {
+        let mut planner = FftPlanner::new();
+        Self {
+            rows,
+            cols,
+            row_forward: planner.plan_fft_forward(cols),
+            row_inverse: planner.plan_fft_inverse(cols),
+            col_forward: planner.plan_fft_forward(rows),
+            col_inverse: planner.plan_fft_inverse(rows),
+        }
+    }
+
+    fn transform(&self, data: &mut [Complex32], inverse: bool) {
+        let (row_fft, col_fft) = if inverse {
+            (&self.row_inverse, &self.col_inverse)
+        } else {
+            (&self.row_forward, &self.col_forward)
+        };
+        row_fft.process(data);
+        let mut column = vec![Complex32::default(); self.rows];
+        for c in 0..self.cols {
+            for r in 0..self.rows {
+                column[r] = data[r * self.cols + c];
+            }
+            col_fft.process(&mut column);
+            for r in 0..self.rows {
+                data[r * self.cols + c] = column[r];
+            }
+        }
+        if inverse {
+            let scale = 1.0 / (self.rows * self.cols) as f32;
+            data.iter_mut().for_each(|v| *v *= scale);
+        }
+    }
+
+    // Spectrum of a radially symmetric kernel centered on the origin (with
+    // wrap-around), normalized so convolving gives an average filling.
+    fn kernel(&self, weight: impl Fn(f32) -> f32) -> Vec<Complex32> {
+        let mut data = vec![Complex32::default(); self.rows * self.cols];
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                let dy = r.min(self.rows - r) as f32;
+                let dx = c.min(self.cols - c) as f32;
+                data[r * self.cols + c] = Complex32::new(weight((dx * dx + dy * dy).sqrt()), 0.0);
+            }
+        }
+        let total: f32 = data.iter().map(|v| v.re).sum();
+        data.iter_mut().for_each(|v| *v /= total.max(f32::EPSILON));
+        self.transform(&mut data, false);
+        data
+    }
+}
+
+pub struct SmoothLife {
+    field: FloatGrid,
+    params: SmoothLifeParams,
+    fft: Fft2d,
+    inner: Vec<Complex32>,
+    outer: Vec<Complex32>,
+    colormap: Colormap,
+    generation: u64,
+}
+
+impl SmoothLife {
+    pub fn new(field: FloatGrid, params: SmoothLifeParams) -> Self {
+        let fft = Fft2d::new(field.rows(), field.cols());
+        // Anti-aliased disk and annulus, one cell of linear falloff at each edge.
+        let coverage = |radius: f32, d: f32| (radius + 0.5 - d).clamp(0.0, 1.0);
+        let (ri, ra) = (params.inner_radius, params.outer_radius);
+        let inner = fft.kernel(|d| coverage(ri, d));
+        let outer = fft.kernel(|d| coverage(ra, d) - coverage(ri, d));
+        Self {
+            field,
+            params,
+            fft,
+            inner,
+            outer,
+            colormap: Colormap::Grayscale,
+            generation: 0,
+        }
+    }
+
+    // Scattered filled disks about the size of the outer radius.
+    pub fn random(rows: usize, cols: usize, params: SmoothLifeParams, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut field = FloatGrid::new(rows, cols);
+        let radius = params.outer_radius;
+        let splats = (rows * cols) as f32 / (radius * radius * 8.0);
+        for _ in 0..splats.ceil() as usize {
+            let (cy, cx) = (rng.below(rows as u64) as f32, rng.below(cols as u64) as f32);
+            for r in 0..rows {
+                for c in 0..cols {
+                    let (dy, dx) = (r as f32 - cy, c as f32 - cx);
+                    if dy * dy + dx * dx <= radius * radius {
+                        field.set(r, c, 1.0);
+                    }
+                }
+            }
+        }
+        Self::new(field, params)
+    }
+
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    pub fn params(&self) -> &SmoothLifeParams {
+        &self.params
+    }
+
+    pub fn field(&self) -> &FloatGrid {
+        &self.field
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn convolve(&self, spectrum: &[Complex32], kernel: &[Complex32]) -> Vec<f32> {
+        let mut product: Vec<Complex32> = spectrum.iter().zip(kernel).map(|(a, b)| a * b).collect();
+        self.fft.transform(&mut product, true);
+        product.iter().map(|v| v.re).collect()
+    }
+}
+
+impl Simulator for SmoothLife {
+    fn step(&mut self) {
+        let mut spectrum: Vec<Complex32> = self.field.cells().iter().map(|&v| Complex32::new(v, 0.0)).collect();
+        self.fft.transform(&mut spectrum, false);
+        let m = self.convolve(&spectrum, &self.inner);
+        let n = self.convolve(&spectrum, &self.outer);
+        let dt = self.params.dt;
+        for (i, value) in self.field.cells_mut().iter_mut().enumerate() {
+            let target = self.params.transition(n[i], m[i]);
+            *value = (*value + dt * (target - *value)).clamp(0.0, 1.0);
+        }
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.field.rows(), self.field.cols())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.field.get(row, col) >= 0.5
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        Shade::Rgba(self.colormap.color(self.field.get(row, col)))
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![
+            Memory::of("field", self.field.cells()),
+            Memory::new("kernels", std::mem::size_of_val(&self.inner[..]) + std::mem::size_of_val(&self.outer[..])),
+        ]
+    }
+}
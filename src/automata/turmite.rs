@@ -0,0 +1,274 @@
+use std::str::FromStr;
+
+use crate::automata::ant::{ANT_COLORS, Ant};
+use crate::render::hue;
+use crate::{Anchor, Error, Grid, Memory, Shade, Simulator};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Turn {
+    None,
+    Right,
+    UTurn,
+    Left,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transition {
+    pub write: u8,
+    pub turn: Turn,
+    pub next: usize,
+}
+
+const MAX_COLORS: usize = u8::MAX as usize + 1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TurmiteTable {
+    states: usize,
+    colors: usize,
+    transitions: Vec<Transition>,
+}
+
+impl TurmiteTable {
+    pub fn new(states: usize, colors: usize, transitions: Vec<Transition>) -> Result<Self, Error> {
+        if states == 0 || colors < 2 || transitions.len() != states * colors {
+            return Err(Error::Rule(format!(
+                "a turmite with {states} states and {colors} colors needs {} transitions",
+                states * colors
+            )));
+        }
+        // Colors are written into the grid's byte-sized states.
+        if colors > MAX_COLORS {
+            return Err(Error::Rule(format!("a turmite can have at most {MAX_COLORS} colors, not {colors}")));
+        }
+        if let Some(bad) = transitions
+            .iter()
+            .find(|t| t.next >= states || t.write as usize >= colors)
+        {
+            return Err(Error::Rule(format!("transition {bad:?} leaves the table")));
+        }
+        Ok(Self {
+            states,
+            colors,
+            transitions,
+        })
+    }
+
+    pub fn langtons_ant() -> Self {
+        Self::parse("RL").expect("RL is a valid turmite")
+    }
+
+    // Accepts either a multi-color ant turn string such as "RL" or "LLRR", or
+    // Golly's nested table {{{write, turn, next}, ...}, ...} with turns coded
+    // as 1 (none), 2 (right), 4 (u-turn) and 8 (left).
+    pub fn parse(spec: &str) -> Result<Self, Error> {
+        let spec: String = spec.chars().filter(|ch| !ch.is_whitespace()).collect();
+        if spec.starts_with('{') {
+            Self::parse_golly(&spec)
+        } else {
+            Self::parse_turns(&spec)
+        }
+    }
+
+    fn parse_turns(spec: &str) -> Result<Self, Error> {
+        let colors = spec.chars().count();
+        let transitions = spec
+            .chars()
+            .enumerate()
+            .map(|(color, ch)| {
+                let turn = match ch.to_ascii_uppercase() {
+                    'L' => Turn::Left,
+                    'R' => Turn::Right,
+                    'N' => Turn::None,
+                    'U' => Turn::UTurn,
+                    _ => return Err(Error::Rule(format!("{spec:?}: unknown turn {ch:?}"))),
+                };
+                Ok(Transition {
+                    write: ((color + 1) % colors.max(1)) as u8,
+                    turn,
+                    next: 0,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Self::new(1, colors, transitions)
+    }
+
+    fn parse_golly(spec: &str) -> Result<Self, Error> {
+        let bad = |msg: &str| Error::Rule(format!("{spec:?}: {msg}"));
+        let mut depth = 0;
+        let mut states: Vec<Vec<Vec<usize>>> = Vec::new();
+        let mut number = String::new();
+        for ch in spec.chars() {
+            if ch.is_ascii_digit() {
+                number.push(ch);
+                continue;
+            }
+            if !number.is_empty() {
+                let value = number.parse().map_err(|_| bad("number out of range"))?;
+                let triple = states.last_mut().and_then(|s| s.last_mut()).filter(|_| depth == 3);
+                triple.ok_or_else(|| bad("numbers must sit inside a transition"))?.push(value);
+                number.clear();
+            }
+            match ch {
+                '{' => {
+                    depth += 1;
+                    match depth {
+                        2 => states.push(Vec::new()),
+                        3 => states.last_mut().ok_or_else(|| bad("unbalanced braces"))?.push(Vec::new()),
+                        1 => {}
+                        _ => return Err(bad("too deeply nested")),
+                    }
+                }
+                '}' => depth -= 1,
+                ',' => {}
+                _ => return Err(bad("unexpected character")),
+            }
+        }
+        if depth != 0 {
+            return Err(bad("unbalanced braces"));
+        }
+        let colors = states.first().map_or(0, Vec::len);
+        let mut transitions = Vec::new();
+        for state in &states {
+            if state.len() != colors {
+                return Err(bad("every state needs one transition per color"));
+            }
+            for triple in state {
+                let [write, turn, next] = triple[..] else {
+                    return Err(bad("transitions are {write, turn, next} triples"));
+                };
+                let turn = match turn {
+                    1 => Turn::None,
+                    2 => Turn::Right,
+                    4 => Turn::UTurn,
+                    8 => Turn::Left,
+                    _ => return Err(bad("turns must be 1, 2, 4 or 8")),
+                };
+                transitions.push(Transition {
+                    write: u8::try_from(write).map_err(|_| bad("colors written go up to 255"))?,
+                    turn,
+                    next,
+                });
+            }
+        }
+        Self::new(states.len(), colors, transitions)
+    }
+
+    pub fn states(&self) -> usize {
+        self.states
+    }
+
+    pub fn colors(&self) -> usize {
+        self.colors
+    }
+
+    pub fn transition(&self, state: usize, color: u8) -> Transition {
+        self.transitions[state * self.colors + color as usize]
+    }
+}
+
+impl FromStr for TurmiteTable {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+pub struct Turmites {
+    grid: Grid,
+    table: TurmiteTable,
+    ants: Vec<Ant>,
+    states: Vec<usize>,
+    generation: u64,
+}
+
+impl Turmites {
+    pub fn new(grid: Grid, table: TurmiteTable, ants: Vec<Ant>) -> Self {
+        let (rows, cols) = (grid.rows(), grid.cols());
+        let ants: Vec<Ant> = ants
+            .into_iter()
+            .map(|ant| Ant::new(ant.row % rows, ant.col % cols, ant.direction))
+            .collect();
+        Self {
+            grid,
+            table,
+            states: vec![0; ants.len()],
+            ants,
+            generation: 0,
+        }
+    }
+
+    pub fn ants(&self) -> &[Ant] {
+        &self.ants
+    }
+
+    pub fn table(&self) -> &TurmiteTable {
+        &self.table
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl Simulator for Turmites {
+    fn step(&mut self) {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        for (ant, state) in self.ants.iter_mut().zip(&mut self.states) {
+            let color = self.grid.state(ant.row, ant.col);
+            let rule = self.table.transition(*state, color);
+            ant.direction = match rule.turn {
+                Turn::None => ant.direction,
+                Turn::Right => ant.direction.turn_right(),
+                Turn::UTurn => ant.direction.turn_right().turn_right(),
+                Turn::Left => ant.direction.turn_left(),
+            };
+            self.grid.set_state(ant.row, ant.col, rule.write);
+            *state = rule.next;
+            ant.advance(rows, cols);
+        }
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.grid.rows(), self.grid.cols())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    // Ants move with the cells under them, wrapping back onto the board if
+    // it shrank out from under them.
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        let (dr, dc) = anchor.offset((self.grid.rows(), self.grid.cols()), (rows, cols));
+        self.grid = self.grid.resized(rows, cols, anchor);
+        for ant in &mut self.ants {
+            ant.row = (ant.row as isize + dr).rem_euclid(rows as isize) as usize;
+            ant.col = (ant.col as isize + dc).rem_euclid(cols as isize) as usize;
+        }
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.grid.state(row, col) != 0
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        if let Some(i) = self.ants.iter().position(|ant| (ant.row, ant.col) == (row, col)) {
+            return Shade::Rgba(ANT_COLORS[i % ANT_COLORS.len()]);
+        }
+        match self.grid.state(row, col) {
+            0 => Shade::Dead,
+            1 if self.table.colors() == 2 => Shade::Alive,
+            color => Shade::Rgba(hue((color - 1) as f32 / (self.table.colors() - 1) as f32)),
+        }
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::of("grid", self.grid.cells()), Memory::of("ants", &self.ants)]
+    }
+}
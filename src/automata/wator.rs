@@ -0,0 +1,263 @@
+use crate::random::Rng;
+use crate::{Anchor, Memory, Parameter, Population, Shade, Simulator, Step};
+
+pub const WATER: [u8; 4] = [10, 30, 80, 255];
+pub const FISH: [u8; 4] = [90, 210, 120, 255];
+pub const SHARK: [u8; 4] = [230, 60, 60, 255];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Creature {
+    Water,
+    Fish { age: u32 },
+    Shark { age: u32, energy: u32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WaTorParams {
+    // Chronons a creature must survive before it splits in two.
+    pub fish_breed: u32,
+    pub shark_breed: u32,
+    // Energy a newborn shark starts with; a shark spends one per chronon and
+    // starves at zero.
+    pub shark_energy: u32,
+    // Energy a shark gains from eating a fish.
+    pub fish_energy: u32,
+}
+
+impl Default for WaTorParams {
+    fn default() -> Self {
+        Self {
+            fish_breed: 3,
+            shark_breed: 10,
+            shark_energy: 3,
+            fish_energy: 3,
+        }
+    }
+}
+
+// Dewdney's Wa-Tor: fish and sharks wander a toroidal ocean, fish breed,
+// sharks hunt them and starve without food.
+pub struct WaTor {
+    rows: usize,
+    cols: usize,
+    ocean: Vec<Creature>,
+    params: WaTorParams,
+    rng: Rng,
+    order: Vec<usize>,
+    generation: u64,
+}
+
+impl WaTor {
+    pub fn new(rows: usize, cols: usize, params: WaTorParams, seed: u64) -> Self {
+        Self {
+            rows,
+            cols,
+            ocean: vec![Creature::Water; rows * cols],
+            params,
+            rng: Rng::new(seed),
+            order: (0..rows * cols).collect(),
+            generation: 0,
+        }
+    }
+
+    // Scatters fish and sharks with the given densities, ages staggered so the
+    // first generation doesn't breed in lockstep.
+    pub fn random(rows: usize, cols: usize, params: WaTorParams, fish: f64, sharks: f64, seed: u64) -> Self {
+        let mut wator = Self::new(rows, cols, params, seed);
+        for i in 0..rows * cols {
+            let roll = wator.rng.next_f64();
+            wator.ocean[i] = if roll < sharks {
+                Creature::Shark {
+                    age: wator.rng.below(params.shark_breed.max(1) as u64) as u32,
+                    energy: params.shark_energy,
+                }
+            } else if roll < sharks + fish {
+                Creature::Fish {
+                    age: wator.rng.below(params.fish_breed.max(1) as u64) as u32,
+                }
+            } else {
+                Creature::Water
+            };
+        }
+        wator
+    }
+
+    pub fn params(&self) -> &WaTorParams {
+        &self.params
+    }
+
+    pub fn creature(&self, row: usize, col: usize) -> Creature {
+        self.ocean[row * self.cols + col]
+    }
+
+    pub fn fish(&self) -> usize {
+        self.ocean.iter().filter(|c| matches!(c, Creature::Fish { .. })).count()
+    }
+
+    pub fn sharks(&self) -> usize {
+        self.ocean.iter().filter(|c| matches!(c, Creature::Shark { .. })).count()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn neighbors(&self, index: usize) -> [usize; 4] {
+        let (row, col) = (index / self.cols, index % self.cols);
+        let up = (row + self.rows - 1) % self.rows;
+        let down = (row + 1) % self.rows;
+        let left = (col + self.cols - 1) % self.cols;
+        let right = (col + 1) % self.cols;
+        [up * self.cols + col, row * self.cols + right, down * self.cols + col, row * self.cols + left]
+    }
+
+    // A random neighbor matching `wanted`, if there is one.
+    fn pick(&mut self, index: usize, wanted: impl Fn(Creature) -> bool) -> Option<usize> {
+        let mut found = [0; 4];
+        let mut count = 0;
+        for neighbor in self.neighbors(index) {
+            if wanted(self.ocean[neighbor]) {
+                found[count] = neighbor;
+                count += 1;
+            }
+        }
+        (count > 0).then(|| found[self.rng.below(count as u64) as usize])
+    }
+
+    fn move_fish(&mut self, index: usize, age: u32, moved: &mut [bool]) {
+        let age = age + 1;
+        let Some(target) = self.pick(index, |c| c == Creature::Water) else {
+            self.ocean[index] = Creature::Fish { age };
+            return;
+        };
+        let breeds = age >= self.params.fish_breed;
+        self.ocean[target] = Creature::Fish { age: if breeds { 0 } else { age } };
+        self.ocean[index] = if breeds { Creature::Fish { age: 0 } } else { Creature::Water };
+        moved[target] = true;
+    }
+
+    fn move_shark(&mut self, index: usize, age: u32, energy: u32, moved: &mut [bool]) {
+        let (age, mut energy) = (age + 1, energy.saturating_sub(1));
+        let target = match self.pick(index, |c| matches!(c, Creature::Fish { .. })) {
+            Some(prey) => {
+                energy += self.params.fish_energy;
+                Some(prey)
+            }
+            None => self.pick(index, |c| c == Creature::Water),
+        };
+        if energy == 0 {
+            self.ocean[index] = Creature::Water;
+            return;
+        }
+        let Some(target) = target else {
+            self.ocean[index] = Creature::Shark { age, energy };
+            return;
+        };
+        let breeds = age >= self.params.shark_breed;
+        self.ocean[target] = Creature::Shark { age: if breeds { 0 } else { age }, energy };
+        self.ocean[index] = if breeds {
+            Creature::Shark { age: 0, energy: self.params.shark_energy }
+        } else {
+            Creature::Water
+        };
+        moved[target] = true;
+    }
+}
+
+impl Simulator for WaTor {
+    // Creatures act one at a time in a shuffled order; anything that already
+    // moved into its cell this chronon is skipped.
+    fn step(&mut self) {
+        for i in (1..self.order.len()).rev() {
+            let j = self.rng.below(i as u64 + 1) as usize;
+            self.order.swap(i, j);
+        }
+        let mut moved = vec![false; self.ocean.len()];
+        for k in 0..self.order.len() {
+            let index = self.order[k];
+            if moved[index] {
+                continue;
+            }
+            match self.ocean[index] {
+                Creature::Water => {}
+                Creature::Fish { age } => self.move_fish(index, age, &mut moved),
+                Creature::Shark { age, energy } => self.move_shark(index, age, energy, &mut moved),
+            }
+        }
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        let (dr, dc) = anchor.offset((self.rows, self.cols), (rows, cols));
+        let mut ocean = vec![Creature::Water; rows * cols];
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let (r, c) = (row as isize + dr, col as isize + dc);
+                if (0..rows as isize).contains(&r) && (0..cols as isize).contains(&c) {
+                    ocean[r as usize * cols + c as usize] = self.creature(row, col);
+                }
+            }
+        }
+        (self.rows, self.cols, self.ocean) = (rows, cols, ocean);
+        self.order = (0..rows * cols).collect();
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.creature(row, col) != Creature::Water
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        Shade::Rgba(match self.creature(row, col) {
+            Creature::Water => WATER,
+            Creature::Fish { .. } => FISH,
+            Creature::Shark { .. } => SHARK,
+        })
+    }
+
+    fn describe(&self, row: usize, col: usize) -> String {
+        match self.creature(row, col) {
+            Creature::Water => "water".to_string(),
+            Creature::Fish { age } => format!("fish, age {age}"),
+            Creature::Shark { age, energy } => format!("shark, age {age}, energy {energy}"),
+        }
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![
+            Parameter::new("fish breed", self.params.fish_breed as f64, Step::Add(1.0)),
+            Parameter::new("shark breed", self.params.shark_breed as f64, Step::Add(1.0)),
+            Parameter::new("shark energy", self.params.shark_energy as f64, Step::Add(1.0)),
+            Parameter::new("fish energy", self.params.fish_energy as f64, Step::Add(1.0)),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) {
+        let value = value.round().max(1.0) as u32;
+        match name {
+            "fish breed" => self.params.fish_breed = value,
+            "shark breed" => self.params.shark_breed = value,
+            "shark energy" => self.params.shark_energy = value,
+            "fish energy" => self.params.fish_energy = value,
+            _ => {}
+        }
+    }
+
+    fn populations(&self) -> Vec<Population> {
+        vec![
+            Population { name: "fish", color: FISH, count: self.fish() },
+            Population { name: "sharks", color: SHARK, count: self.sharks() },
+        ]
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::of("ocean", &self.ocean), Memory::of("order", &self.order)]
+    }
+}
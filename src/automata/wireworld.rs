@@ -0,0 +1,150 @@
+use crate::{Anchor, Grid, Memory, Shade, Simulator, Snapshot};
+
+pub const EMPTY: u8 = 0;
+pub const HEAD: u8 = 1;
+pub const TAIL: u8 = 2;
+pub const CONDUCTOR: u8 = 3;
+
+const COLORS: [[u8; 4]; 4] = [
+    [0, 0, 0, 255],
+    [40, 120, 255, 255],
+    [255, 60, 30, 255],
+    [250, 200, 0, 255],
+];
+
+pub struct Wireworld {
+    grid: Grid,
+    generation: u64,
+}
+
+impl Wireworld {
+    pub fn new(initial: Grid) -> Self {
+        Self {
+            grid: initial,
+            generation: 0,
+        }
+    }
+
+    // A ring oscillator with one electron, feeding a wire off to the right;
+    // boards too small for the ring start empty.
+    pub fn sample(rows: usize, cols: usize) -> Self {
+        let mut grid = Grid::new(rows, cols);
+        if rows < 5 || cols < 5 {
+            return Self::new(grid);
+        }
+        let (top, left, bottom, right) = (rows / 2 - 2, 2, rows / 2 + 2, 10.min(cols - 1));
+        for c in left..=right {
+            grid.set_state(top, c, CONDUCTOR);
+            grid.set_state(bottom, c, CONDUCTOR);
+        }
+        for r in top..=bottom {
+            grid.set_state(r, left, CONDUCTOR);
+            grid.set_state(r, right, CONDUCTOR);
+        }
+        for c in right + 1..cols - 1 {
+            grid.set_state(rows / 2, c, CONDUCTOR);
+        }
+        grid.set_state(top, left + 2, HEAD);
+        grid.set_state(top, left + 1, TAIL);
+        Self::new(grid)
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn head_neighbors(&self, row: usize, col: usize) -> usize {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        (row.saturating_sub(1)..=(row + 1).min(rows - 1))
+            .flat_map(|r| (col.saturating_sub(1)..=(col + 1).min(cols - 1)).map(move |c| (r, c)))
+            .filter(|&(r, c)| (r, c) != (row, col) && self.grid.state(r, c) == HEAD)
+            .count()
+    }
+}
+
+impl Simulator for Wireworld {
+    fn step(&mut self) {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        let mut next = Grid::new(rows, cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let state = match self.grid.state(row, col) {
+                    HEAD => TAIL,
+                    TAIL => CONDUCTOR,
+                    CONDUCTOR if matches!(self.head_neighbors(row, col), 1 | 2) => HEAD,
+                    other => other,
+                };
+                next.set_state(row, col, state);
+            }
+        }
+        self.grid = next;
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.grid.rows(), self.grid.cols())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        self.grid = self.grid.resized(rows, cols, anchor);
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.grid.state(row, col) != EMPTY
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        Shade::Rgba(COLORS[self.grid.state(row, col) as usize % COLORS.len()])
+    }
+
+    fn describe(&self, row: usize, col: usize) -> String {
+        match self.grid.state(row, col) {
+            HEAD => "electron head",
+            TAIL => "electron tail",
+            CONDUCTOR => "conductor",
+            _ => "empty",
+        }
+        .to_string()
+    }
+
+    // Drawn cells are wire.
+    fn set_cell(&mut self, row: usize, col: usize, alive: bool) {
+        self.grid.set_state(row, col, if alive { CONDUCTOR } else { EMPTY });
+    }
+
+    // A click steps a cell on from empty to wire, an electron head, its
+    // tail and back to empty.
+    fn poke(&mut self, row: usize, col: usize) {
+        let next = match self.grid.state(row, col) {
+            EMPTY => CONDUCTOR,
+            CONDUCTOR => HEAD,
+            HEAD => TAIL,
+            _ => EMPTY,
+        };
+        self.grid.set_state(row, col, next);
+    }
+
+    fn save(&self) -> Option<Snapshot> {
+        Some(Snapshot {
+            generation: self.generation,
+            grid: self.grid.clone(),
+        })
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        self.grid = snapshot.grid.clone();
+        self.generation = snapshot.generation;
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::of("grid", self.grid.cells())]
+    }
+}
@@ -0,0 +1,280 @@
+use rayon::prelude::*;
+
+use crate::boundary::{BoundaryCondition, Edge};
+use crate::{Anchor, Grid, Memory, Rule, Simulator, Snapshot};
+
+const WORD: usize = u64::BITS as usize;
+
+// Two-state Moore-neighborhood Life with each row packed into u64 words,
+// bit i of word w holding column w * 64 + i. A step sums the eight
+// neighbors of 64 cells at once in four bit planes and applies the rule to
+// those planes, instead of visiting cells one by one.
+//
+// The rule's state count and neighborhood are ignored. Each axis either
+// wraps or is dead past both edges: a pair of edges behaves as wrapping
+// only when both wrap, and every other condition reads as dead.
+pub struct BitLife {
+    rows: usize,
+    cols: usize,
+    words: usize,
+    cells: Vec<u64>,
+    rule: Rule,
+    boundary: BoundaryCondition,
+    wrap_rows: bool,
+    wrap_cols: bool,
+    parallel: bool,
+    changed: Vec<(usize, usize)>,
+    // The previous generation's words, reused for the next one.
+    scratch: Vec<u64>,
+    generation: u64,
+}
+
+// Adds one bit per lane into a 4-bit counter spread over `planes`.
+fn add(planes: &mut [u64; 4], mut bits: u64) {
+    for plane in planes.iter_mut() {
+        let carry = *plane & bits;
+        *plane ^= bits;
+        bits = carry;
+    }
+}
+
+impl BitLife {
+    pub fn new(rows: usize, cols: usize, rule: Rule) -> Self {
+        let words = cols.div_ceil(WORD);
+        Self {
+            rows,
+            cols,
+            words,
+            cells: vec![0; rows * words],
+            rule,
+            boundary: BoundaryCondition::default(),
+            wrap_rows: false,
+            wrap_cols: false,
+            parallel: true,
+            changed: Vec::new(),
+            scratch: Vec::new(),
+            generation: 0,
+        }
+    }
+
+    pub fn from_grid(grid: &Grid, rule: Rule) -> Self {
+        let mut life = Self::new(grid.rows(), grid.cols(), rule);
+        for (row, col) in grid.live_cells() {
+            life.set_alive(row, col, true);
+        }
+        life
+    }
+
+    pub fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.set_boundary(boundary);
+        self
+    }
+
+    // Steps rows on rayon's thread pool unless turned off.
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn is_alive(&self, row: usize, col: usize) -> bool {
+        self.cells[row * self.words + col / WORD] >> (col % WORD) & 1 != 0
+    }
+
+    pub fn set_alive(&mut self, row: usize, col: usize, alive: bool) {
+        let word = &mut self.cells[row * self.words + col / WORD];
+        let bit = 1 << (col % WORD);
+        if alive { *word |= bit } else { *word &= !bit }
+        self.changed.push((row, col));
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    // The same settings on another board, every cell of it marked changed.
+    fn rebuilt(&self, grid: &Grid, generation: u64) -> Self {
+        let (rows, cols) = (grid.rows(), grid.cols());
+        let life = Self::from_grid(grid, self.rule)
+            .with_boundary(self.boundary)
+            .with_parallel(self.parallel);
+        Self {
+            changed: (0..rows).flat_map(|row| (0..cols).map(move |col| (row, col))).collect(),
+            generation,
+            ..life
+        }
+    }
+
+    pub fn to_grid(&self) -> Grid {
+        let mut grid = Grid::new(self.rows, self.cols);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                grid.set(row, col, self.is_alive(row, col));
+            }
+        }
+        grid
+    }
+
+    fn row(&self, row: isize) -> Option<&[u64]> {
+        let n = self.rows as isize;
+        let row = if self.wrap_rows { row.rem_euclid(n) } else { row };
+        (0..n)
+            .contains(&row)
+            .then(|| &self.cells[row as usize * self.words..(row as usize + 1) * self.words])
+    }
+
+    // Bits past the last column are kept clear.
+    fn last_word_mask(&self) -> u64 {
+        match self.cols % WORD {
+            0 => u64::MAX,
+            used => (1 << used) - 1,
+        }
+    }
+
+    // Word `w` of `row` shifted so each lane sees its west and east
+    // neighbor, carrying bits across word boundaries and around the board
+    // when columns wrap.
+    fn shifted(&self, row: &[u64], w: usize) -> (u64, u64) {
+        let last = self.words - 1;
+        let edge_bit = (self.cols - 1) % WORD;
+        let from_west = if w > 0 {
+            row[w - 1] >> 63
+        } else if self.wrap_cols {
+            row[last] >> edge_bit & 1
+        } else {
+            0
+        };
+        let from_east = if w < last {
+            row[w + 1] << 63
+        } else if self.wrap_cols {
+            (row[0] & 1) << edge_bit
+        } else {
+            0
+        };
+        (row[w] << 1 | from_west, row[w] >> 1 | from_east)
+    }
+
+    // Lanes whose neighbor count, spread over `planes`, equals `n`.
+    fn count_is(planes: &[u64; 4], n: u8) -> u64 {
+        planes
+            .iter()
+            .enumerate()
+            .fold(u64::MAX, |lanes, (bit, &plane)| lanes & if n >> bit & 1 != 0 { plane } else { !plane })
+    }
+}
+
+impl Simulator for BitLife {
+    fn step(&mut self) {
+        let mut next = std::mem::take(&mut self.scratch);
+        next.resize(self.cells.len(), 0);
+        let births: [bool; 9] = std::array::from_fn(|n| self.rule.births(n as u8));
+        let survivals: [bool; 9] = std::array::from_fn(|n| self.rule.survives(n as u8));
+        let matching = |planes: &[u64; 4], counts: &[bool; 9]| {
+            (0..9u8).filter(|&n| counts[n as usize]).fold(0, |lanes, n| lanes | Self::count_is(planes, n))
+        };
+        let update = |(row, out): (usize, &mut [u64])| {
+            let above = self.row(row as isize - 1);
+            let here = self.row(row as isize).expect("row is on the board");
+            let below = self.row(row as isize + 1);
+            for w in 0..self.words {
+                let mut planes = [0; 4];
+                for neighbor in [above, below].into_iter().flatten() {
+                    let (west, east) = self.shifted(neighbor, w);
+                    add(&mut planes, neighbor[w]);
+                    add(&mut planes, west);
+                    add(&mut planes, east);
+                }
+                let (west, east) = self.shifted(here, w);
+                add(&mut planes, west);
+                add(&mut planes, east);
+                let alive = here[w];
+                let born = matching(&planes, &births);
+                let kept = matching(&planes, &survivals);
+                out[w] = !alive & born | alive & kept;
+            }
+            out[self.words - 1] &= self.last_word_mask();
+        };
+        if self.parallel {
+            next.par_chunks_mut(self.words).enumerate().for_each(update);
+        } else {
+            next.chunks_mut(self.words).enumerate().for_each(update);
+        }
+        self.changed.clear();
+        for (i, (old, new)) in self.cells.iter().zip(&next).enumerate() {
+            let mut flipped = old ^ new;
+            while flipped != 0 {
+                let bit = flipped.trailing_zeros() as usize;
+                self.changed.push((i / self.words, i % self.words * WORD + bit));
+                flipped &= flipped - 1;
+            }
+        }
+        self.scratch = std::mem::replace(&mut self.cells, next);
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn population(&self) -> usize {
+        BitLife::population(self)
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        let grid = self.to_grid().resized(rows, cols, anchor);
+        *self = self.rebuilt(&grid, self.generation);
+    }
+
+    fn changes(&self) -> Option<&[(usize, usize)]> {
+        Some(&self.changed)
+    }
+
+    fn boundary(&self) -> Option<BoundaryCondition> {
+        Some(self.boundary)
+    }
+
+    fn set_boundary(&mut self, boundary: BoundaryCondition) {
+        let edges = boundary.edges().unwrap_or_default();
+        self.boundary = boundary;
+        self.wrap_rows = edges.top == Edge::Wrap && edges.bottom == Edge::Wrap;
+        self.wrap_cols = edges.left == Edge::Wrap && edges.right == Edge::Wrap;
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.is_alive(row, col)
+    }
+
+    fn set_cell(&mut self, row: usize, col: usize, alive: bool) {
+        self.set_alive(row, col, alive);
+    }
+
+    fn save(&self) -> Option<Snapshot> {
+        Some(Snapshot {
+            generation: self.generation,
+            grid: self.to_grid(),
+        })
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        *self = self.rebuilt(&snapshot.grid, snapshot.generation);
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![
+            Memory::of("cells", &self.cells),
+            Memory::of("scratch", &self.scratch),
+            Memory::of("changes", &self.changed),
+        ]
+    }
+}
@@ -0,0 +1,255 @@
+use crate::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    Rows,
+    Cols,
+}
+
+// What one edge of the board does with neighbors that fall past it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Edge {
+    #[default]
+    Dead,
+    Alive,
+    // Continues from the opposite edge.
+    Wrap,
+    Mirror,
+}
+
+impl Edge {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "dead" => Some(Edge::Dead),
+            "alive" => Some(Edge::Alive),
+            "wrap" => Some(Edge::Wrap),
+            "mirror" | "reflect" => Some(Edge::Mirror),
+            _ => None,
+        }
+    }
+
+    // Where an index past this edge lands, or the state it reads there.
+    fn fold(self, index: isize, len: usize) -> Result<usize, bool> {
+        let n = len as isize;
+        match self {
+            Edge::Wrap => Ok(index.rem_euclid(n) as usize),
+            Edge::Mirror => {
+                let m = index.rem_euclid(2 * n);
+                Ok(if m < n { m } else { 2 * n - 1 - m } as usize)
+            }
+            Edge::Dead => Err(false),
+            Edge::Alive => Err(true),
+        }
+    }
+}
+
+// Each edge of the board on its own. An edge that wraps continues from the
+// opposite one whatever that one does, so only `left` wrapping means cells
+// past the left edge see the right-hand column while cells past the right
+// edge still see `right`'s condition.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Edges {
+    pub top: Edge,
+    pub bottom: Edge,
+    pub left: Edge,
+    pub right: Edge,
+}
+
+impl Edges {
+    pub fn all(edge: Edge) -> Self {
+        Self {
+            top: edge,
+            bottom: edge,
+            left: edge,
+            right: edge,
+        }
+    }
+
+    pub fn with_top(mut self, edge: Edge) -> Self {
+        self.top = edge;
+        self
+    }
+
+    pub fn with_bottom(mut self, edge: Edge) -> Self {
+        self.bottom = edge;
+        self
+    }
+
+    pub fn with_left(mut self, edge: Edge) -> Self {
+        self.left = edge;
+        self
+    }
+
+    pub fn with_right(mut self, edge: Edge) -> Self {
+        self.right = edge;
+        self
+    }
+
+    // Comma-separated EDGE=CONDITION pairs such as "left=wrap,right=wrap";
+    // "rows" sets top and bottom, "cols" left and right, and unnamed edges
+    // are dead.
+    pub fn parse(spec: &str) -> Option<Self> {
+        spec.split(',').try_fold(Edges::default(), |edges, pair| {
+            let (name, condition) = pair.split_once('=')?;
+            let edge = Edge::parse(condition.trim())?;
+            Some(match name.trim().to_ascii_lowercase().as_str() {
+                "top" => edges.with_top(edge),
+                "bottom" => edges.with_bottom(edge),
+                "left" => edges.with_left(edge),
+                "right" => edges.with_right(edge),
+                "rows" => edges.with_top(edge).with_bottom(edge),
+                "cols" => edges.with_left(edge).with_right(edge),
+                _ => return None,
+            })
+        })
+    }
+
+    fn fold(before: Edge, after: Edge, index: isize, len: usize) -> Result<usize, bool> {
+        match index {
+            i if i < 0 => before.fold(i, len),
+            i if i >= len as isize => after.fold(i, len),
+            i => Ok(i as usize),
+        }
+    }
+
+    pub fn resolve(self, row: isize, col: isize, rows: usize, cols: usize) -> Resolved {
+        let row = Self::fold(self.top, self.bottom, row, rows);
+        let col = Self::fold(self.left, self.right, col, cols);
+        match (row, col) {
+            (Ok(r), Ok(c)) => Resolved::Cell(r, c),
+            (Err(alive), _) | (_, Err(alive)) => Resolved::Fixed(alive),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BoundaryCondition {
+    #[default]
+    Dead,
+    Wrap,
+    Mirror,
+    Alive,
+    // Wraps along one axis only; the other pair of edges is dead.
+    Cylinder(Axis),
+    // Wraps along both axes, mirroring the crossing cell's position along the
+    // other axis when it wraps along this one: Klein(Rows) joins the top edge
+    // to the bottom with a left-right flip.
+    Klein(Axis),
+    // Both pairs of edges are joined with a flip, making a projective plane.
+    CrossSurface,
+    PerEdge(Edges),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolved {
+    Cell(usize, usize),
+    Fixed(bool),
+}
+
+impl BoundaryCondition {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "dead" => Some(BoundaryCondition::Dead),
+            "wrap" | "torus" | "toroidal" => Some(BoundaryCondition::Wrap),
+            "mirror" | "reflect" => Some(BoundaryCondition::Mirror),
+            "alive" => Some(BoundaryCondition::Alive),
+            "cylinder" => Some(BoundaryCondition::Cylinder(Axis::Cols)),
+            "klein" | "kleinbottle" => Some(BoundaryCondition::Klein(Axis::Rows)),
+            "cross" | "crosssurface" | "cross-surface" => Some(BoundaryCondition::CrossSurface),
+            spec if spec.contains('=') => Edges::parse(spec).map(BoundaryCondition::PerEdge),
+            _ => None,
+        }
+    }
+
+    // The same condition spelled edge by edge, for all but the twisted
+    // topologies.
+    pub fn edges(self) -> Option<Edges> {
+        let (rows, cols) = match self {
+            BoundaryCondition::Dead => (Edge::Dead, Edge::Dead),
+            BoundaryCondition::Wrap => (Edge::Wrap, Edge::Wrap),
+            BoundaryCondition::Mirror => (Edge::Mirror, Edge::Mirror),
+            BoundaryCondition::Alive => (Edge::Alive, Edge::Alive),
+            BoundaryCondition::Cylinder(Axis::Rows) => (Edge::Wrap, Edge::Dead),
+            BoundaryCondition::Cylinder(Axis::Cols) => (Edge::Dead, Edge::Wrap),
+            BoundaryCondition::PerEdge(edges) => return Some(edges),
+            BoundaryCondition::Klein(_) | BoundaryCondition::CrossSurface => return None,
+        };
+        Some(Edges::all(rows).with_left(cols).with_right(cols))
+    }
+
+    // Golly's bounded-grid suffix without the colon: a topology letter and
+    // WIDTH,HEIGHT, such as "T100,80", "P64,64", "C50,50", or "K100*,80"
+    // where the asterisk marks the dimension whose edges are twisted (here
+    // the 100-cell top and bottom). A zero dimension is unbounded, which is
+    // how Golly spells a cylinder ("T100,0"); since boards here are always
+    // finite the size comes back only when both dimensions are given, as
+    // (rows, cols). Sphere topologies and shifted edges aren't supported.
+    pub fn parse_bounded_grid(spec: &str) -> Result<(Self, Option<(usize, usize)>), Error> {
+        let bad = |why: &str| Error::Config(format!("bounded grid {spec:?}: {why}"));
+        let mut chars = spec.trim().chars();
+        let topology = chars.next().map(|ch| ch.to_ascii_uppercase()).ok_or_else(|| bad("empty"))?;
+        let (width, height) = chars
+            .as_str()
+            .split_once(',')
+            .ok_or_else(|| bad("expected a letter and WIDTH,HEIGHT"))?;
+        let dimension = |text: &str| -> Result<(usize, bool), Error> {
+            let text = text.trim();
+            if text.contains(['+', '-']) {
+                return Err(bad("shifted edges aren't supported"));
+            }
+            let (digits, twisted) = match text.strip_suffix('*') {
+                Some(digits) => (digits, true),
+                None => (text, false),
+            };
+            let size = digits.parse().map_err(|_| bad("dimensions must be whole numbers"))?;
+            Ok((size, twisted))
+        };
+        let ((width, twist_width), (height, twist_height)) = (dimension(width)?, dimension(height)?);
+        let size = (width > 0 && height > 0).then_some((height, width));
+        if (twist_width || twist_height) && topology != 'K' {
+            return Err(bad("only Klein bottles take an asterisk"));
+        }
+        let boundary = match (topology, width, height) {
+            ('P', _, _) => BoundaryCondition::Dead,
+            ('T', 0, 0) => BoundaryCondition::Dead,
+            ('T', 0, _) => BoundaryCondition::Cylinder(Axis::Rows),
+            ('T', _, 0) => BoundaryCondition::Cylinder(Axis::Cols),
+            ('T', _, _) => BoundaryCondition::Wrap,
+            ('K' | 'C', 0, _) | ('K' | 'C', _, 0) => return Err(bad("Klein bottles and cross-surfaces must be bounded")),
+            ('K', _, _) => match (twist_width, twist_height) {
+                (true, false) => BoundaryCondition::Klein(Axis::Rows),
+                (false, true) => BoundaryCondition::Klein(Axis::Cols),
+                _ => return Err(bad("mark exactly one twisted dimension with an asterisk")),
+            },
+            ('C', _, _) => BoundaryCondition::CrossSurface,
+            ('S', _, _) => return Err(bad("spheres aren't supported")),
+            _ => return Err(bad("topology must be P, T, K, or C")),
+        };
+        Ok((boundary, size))
+    }
+
+    // Where `index` lands along a single row of `len` cells, for automata
+    // with only one dimension; topologies that join columns wrap.
+    pub fn fold(self, index: isize, len: usize) -> Option<usize> {
+        match self.edges() {
+            Some(edges) => Edges::fold(edges.left, edges.right, index, len).ok(),
+            None => Some(index.rem_euclid(len as isize) as usize),
+        }
+    }
+
+    pub fn resolve(self, row: isize, col: isize, rows: usize, cols: usize) -> Resolved {
+        if let Some(edges) = self.edges() {
+            return edges.resolve(row, col, rows, cols);
+        }
+        let (n_rows, n_cols) = (rows as isize, cols as isize);
+        let (mut r, mut c) = (row.rem_euclid(n_rows), col.rem_euclid(n_cols));
+        let twisted = |axis| self == BoundaryCondition::CrossSurface || self == BoundaryCondition::Klein(axis);
+        if twisted(Axis::Rows) && row.div_euclid(n_rows) % 2 != 0 {
+            c = n_cols - 1 - c;
+        }
+        if twisted(Axis::Cols) && col.div_euclid(n_cols) % 2 != 0 {
+            r = n_rows - 1 - r;
+        }
+        Resolved::Cell(r as usize, c as usize)
+    }
+}
@@ -0,0 +1,38 @@
+// Draws the board from a texture holding one texel per cell, over the frame
+// pixels already scaled to the window. The frame's board area is left
+// transparent apart from overlays, which cover the cells beneath them as far
+// as their alpha says.
+
+struct Locals {
+    // Share of the frame's height taken by the board, from the top.
+    board: f32,
+}
+@group(0) @binding(0) var cells: texture_2d<f32>;
+@group(0) @binding(1) var frame: texture_2d<f32>;
+@group(0) @binding(2) var nearest: sampler;
+@group(0) @binding(3) var<uniform> locals: Locals;
+
+struct VertexOutput {
+    @location(0) uv: vec2<f32>,
+    @builtin(position) position: vec4<f32>,
+}
+
+// One triangle covering the viewport.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let position = vec2<f32>(f32(index & 1u) * 4.0 - 1.0, f32(index >> 1u) * 4.0 - 1.0);
+    var out: VertexOutput;
+    out.uv = fma(position, vec2<f32>(0.5, -0.5), vec2<f32>(0.5, 0.5));
+    out.position = vec4<f32>(position, 0.0, 1.0);
+    return out;
+}
+
+@fragment
+fn fs_main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let drawn = textureSample(frame, nearest, uv);
+    let cell = textureSample(cells, nearest, vec2<f32>(uv.x, uv.y / locals.board));
+    if uv.y >= locals.board {
+        return drawn;
+    }
+    return vec4<f32>(mix(cell.rgb, drawn.rgb, drawn.a), 1.0);
+}
@@ -0,0 +1,61 @@
+use std::time::{Duration, Instant};
+
+// Turns elapsed wall-clock time into a whole number of fixed-length
+// simulation ticks, carrying the remainder over to the next frame. When
+// frames come late it catches up, but never by more than `max_steps` ticks
+// in one go; anything beyond that is dropped rather than piling up.
+pub struct Clock {
+    tick: Duration,
+    lag: Duration,
+    last: Instant,
+    max_steps: usize,
+}
+
+impl Clock {
+    pub fn new(tick: Duration) -> Self {
+        Self {
+            tick,
+            lag: Duration::ZERO,
+            last: Instant::now(),
+            max_steps: 32,
+        }
+    }
+
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn tick(&self) -> Duration {
+        self.tick
+    }
+
+    pub fn set_tick(&mut self, tick: Duration) {
+        self.tick = tick;
+    }
+
+    // Forgets any time owed, so ticks count from `now` again, as after a
+    // pause.
+    pub fn reset(&mut self, now: Instant) {
+        self.lag = Duration::ZERO;
+        self.last = now;
+    }
+
+    // Ticks due since the last call.
+    pub fn advance(&mut self, now: Instant) -> usize {
+        self.lag += now.saturating_duration_since(self.last);
+        self.last = now;
+        if self.tick.is_zero() {
+            return self.max_steps;
+        }
+        let due = (self.lag.as_nanos() / self.tick.as_nanos()) as usize;
+        let steps = due.min(self.max_steps);
+        self.lag = if due > steps { Duration::ZERO } else { self.lag - self.tick * steps as u32 };
+        steps
+    }
+
+    // When the next tick falls due.
+    pub fn next_tick(&self) -> Instant {
+        self.last + self.tick.saturating_sub(self.lag)
+    }
+}
@@ -0,0 +1,500 @@
+use std::path::Path;
+
+use crate::automata::ant::Ant;
+use crate::automata::golly::RuleFile;
+use crate::automata::isotropic::IsotropicRule;
+use crate::automata::lenia::LeniaParams;
+use crate::automata::life3d::Rule3D;
+use crate::automata::ltl::LtlRule;
+use crate::automata::turmite::TurmiteTable;
+use crate::boundary::BoundaryCondition;
+use crate::render::{self, CellShape, Colormap, Theme};
+use crate::{formats, Anchor, Error, Grid, Rule, RuleNeighborhood, StochasticRule};
+
+// Fewest cells down and across a board holding a --pattern or --apgcode.
+const PATTERN_ROOM: usize = 64;
+// Pixels across the largest board the window fits without panning.
+const WINDOW: usize = 760;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Automaton {
+    #[default]
+    Life,
+    Wireworld,
+    BriansBrain,
+    Turmite,
+    Elementary,
+    Cyclic,
+    Lenia,
+    SmoothLife,
+    Sandpile,
+    ForestFire,
+    WaTor,
+    Immigration,
+    QuadLife,
+    Critters,
+    BilliardBall,
+    RuleFile,
+    Life3D,
+    Sparse,
+    BitPacked,
+    LargerThanLife,
+}
+
+impl Automaton {
+    pub fn parse(name: &str) -> Result<Self, Error> {
+        let key: String = name
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|ch| ch.to_ascii_lowercase())
+            .collect();
+        match key.as_str() {
+            "life" => Ok(Automaton::Life),
+            "wireworld" => Ok(Automaton::Wireworld),
+            "briansbrain" => Ok(Automaton::BriansBrain),
+            "ant" | "langtonsant" | "turmite" => Ok(Automaton::Turmite),
+            "elementary" | "wolfram" => Ok(Automaton::Elementary),
+            "cyclic" => Ok(Automaton::Cyclic),
+            "lenia" => Ok(Automaton::Lenia),
+            "smoothlife" => Ok(Automaton::SmoothLife),
+            "sandpile" | "abeliansandpile" => Ok(Automaton::Sandpile),
+            "forestfire" => Ok(Automaton::ForestFire),
+            "wator" => Ok(Automaton::WaTor),
+            "immigration" => Ok(Automaton::Immigration),
+            "quadlife" => Ok(Automaton::QuadLife),
+            "critters" => Ok(Automaton::Critters),
+            "billiardball" | "bbm" => Ok(Automaton::BilliardBall),
+            "life3d" | "3d" => Ok(Automaton::Life3D),
+            "sparse" | "unbounded" => Ok(Automaton::Sparse),
+            "bitpacked" | "bitlife" | "packed" => Ok(Automaton::BitPacked),
+            "ltl" | "largerthanlife" => Ok(Automaton::LargerThanLife),
+            _ => Err(Error::Config(format!("unknown automaton {name:?}"))),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub automaton: Automaton,
+    pub rule: Rule,
+    pub stochastic: Option<StochasticRule>,
+    // A rule with Hensel letters, for the life automaton to run in place of
+    // `rule`.
+    pub isotropic: Option<IsotropicRule>,
+    // A B/S rule or its name from --rule or the pattern, till `read_rule`.
+    notation: Option<String>,
+    pub neighborhood: Option<RuleNeighborhood>,
+    pub ltl: Option<LtlRule>,
+    pub rule3d: Rule3D,
+    pub ants: Vec<Ant>,
+    pub turmite: Option<TurmiteTable>,
+    pub rule_file: Option<RuleFile>,
+    pub wolfram: u8,
+    pub states: u8,
+    pub threshold: usize,
+    pub seed: u64,
+    // Share of cells alive in a random fill.
+    pub density: f64,
+    pub boundary: Option<BoundaryCondition>,
+    pub grains: u32,
+    pub growth: f64,
+    pub lightning: f64,
+    // Lenia's kernel and growth, from the --lenia-* arguments.
+    pub lenia: LeniaParams,
+    pub size: Option<(usize, usize)>,
+    // Read from --pattern or decoded from --apgcode to start with in place
+    // of the built-in one.
+    pub pattern: Option<Grid>,
+    // Where Ctrl+S writes the board or selection, in the format its
+    // extension names.
+    pub export: String,
+    pub cell_size: Option<usize>,
+    pub colormap: Option<Colormap>,
+    // A built-in theme from --theme, with any of its colors replaced by the
+    // --*-color arguments after it and its shape by --cell-shape. A config
+    // file writes colors without the '#', which starts a comment there.
+    pub theme: Theme,
+    // How many cells a resize key adds or removes, and what stays in place.
+    pub resize_step: usize,
+    pub anchor: Anchor,
+    // Worker threads for parallel stepping; 0 lets rayon pick one per core.
+    pub threads: usize,
+    // Milliseconds of real time per tick.
+    pub tick_ms: u64,
+    // Generations run per tick, and how many the jump key runs at once.
+    pub warp: usize,
+    pub jump: usize,
+    // Draws square boards with a shader; --no-gpu paints them on the CPU.
+    pub gpu: bool,
+    // Starts with lines between the cells, as H toggles.
+    pub grid_lines: bool,
+    // Starts with dead cells fading out, as J toggles.
+    pub trails: bool,
+    // Draws a soft glow around live cells, on the CPU.
+    pub glow: bool,
+    // Changes Ctrl+Z can undo; 0 keeps no history.
+    pub history: usize,
+    // MiB of past generations kept for stepping backwards.
+    pub rewind_mib: usize,
+    // Generations between the timeline's checkpoints, 0 for none, the MiB
+    // they can take up, and how far , and . scrub through it.
+    pub checkpoint_every: u64,
+    pub timeline_mib: usize,
+    pub scrub: usize,
+    // Generations to time without opening a window.
+    pub bench: Option<u64>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            automaton: Automaton::default(),
+            rule: Rule::default(),
+            stochastic: None,
+            isotropic: None,
+            notation: None,
+            neighborhood: None,
+            ltl: None,
+            rule3d: Rule3D::default(),
+            ants: Vec::new(),
+            turmite: None,
+            rule_file: None,
+            wolfram: 30,
+            states: 14,
+            threshold: 1,
+            seed: 0,
+            density: 0.35,
+            boundary: None,
+            grains: 10_000,
+            growth: 0.01,
+            lightning: 0.00005,
+            lenia: LeniaParams::default(),
+            size: None,
+            pattern: None,
+            export: "pattern.rle".to_string(),
+            cell_size: None,
+            colormap: None,
+            theme: Theme::default(),
+            resize_step: 8,
+            anchor: Anchor::default(),
+            threads: 0,
+            tick_ms: 200,
+            warp: 1,
+            jump: 1000,
+            gpu: true,
+            grid_lines: false,
+            trails: false,
+            glow: false,
+            history: 100,
+            rewind_mib: 64,
+            checkpoint_every: 50,
+            timeline_mib: 64,
+            scrub: 10,
+            bench: None,
+        }
+    }
+}
+
+fn number<T: std::str::FromStr>(arg: &str, value: &str) -> Result<T, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::Config(format!("{arg} expects a number, got {value:?}")))
+}
+
+fn color(arg: &str, value: &str) -> Result<[u8; 4], Error> {
+    render::parse_color(value).ok_or_else(|| Error::Config(format!("{arg} expects a color like #RRGGBB, got {value:?}")))
+}
+
+impl Config {
+    // Boards that need room to develop default to something bigger than the
+    // built-in 19x19 starting pattern, and a pattern from --pattern or
+    // --apgcode gets room to spread out into.
+    pub fn board_size(&self, pattern: (usize, usize)) -> (usize, usize) {
+        self.size.unwrap_or(match self.automaton {
+            Automaton::Lenia
+            | Automaton::SmoothLife
+            | Automaton::Sandpile
+            | Automaton::ForestFire
+            | Automaton::WaTor
+            | Automaton::Critters
+            | Automaton::BilliardBall => (128, 128),
+            Automaton::Life3D => (32, 32),
+            Automaton::Sparse => (96, 96),
+            Automaton::BitPacked => (256, 256),
+            _ if self.pattern.is_some() => ((pattern.0 * 3).max(PATTERN_ROOM), (pattern.1 * 3).max(PATTERN_ROOM)),
+            _ => pattern,
+        })
+    }
+
+    // Golly's "B3/S23:T100,100" carries a bounded grid after the colon;
+    // stochastic rules have a digit there. A Larger-than-Life rule such as
+    // "R5,C0,M1,S34..58,B34..45,NM" only runs on its own automaton, so it
+    // also picks that. A B/S rule with Hensel letters, like "B2-a/S12", is
+    // isotropic.
+    fn set_rule(&mut self, rule: &str) -> Result<(), Error> {
+        let mut rule = rule;
+        if let Some((notation, grid)) = rule.split_once(':')
+            && grid.starts_with(|ch: char| ch.is_ascii_alphabetic())
+        {
+            let (boundary, size) = BoundaryCondition::parse_bounded_grid(grid)?;
+            self.boundary = Some(boundary);
+            self.size = size.or(self.size);
+            rule = notation;
+        }
+        if rule.contains(':') {
+            self.stochastic = Some(rule.parse()?);
+        } else if rule.starts_with(['R', 'r']) && rule[1..].starts_with(|ch: char| ch.is_ascii_digit()) {
+            self.ltl = Some(rule.parse()?);
+            self.automaton = Automaton::LargerThanLife;
+        } else {
+            self.notation = Some(rule.to_string());
+        }
+        Ok(())
+    }
+
+    // B/S rules are read once the arguments are all in, so their counts are
+    // checked against whatever --neighborhood comes after them.
+    fn read_rule(&mut self) -> Result<(), Error> {
+        let Some(rule) = self.notation.take() else {
+            if let Some(neighborhood) = self.neighborhood {
+                self.rule = self.rule.with_neighborhood(neighborhood)?;
+            }
+            return Ok(());
+        };
+        match Rule::from_name_or_notation_in(&rule, self.neighborhood) {
+            Ok(parsed) => {
+                self.rule = parsed;
+                self.isotropic = None;
+            }
+            // Isotropic rules only run on the eight nearest cells.
+            Err(err) if self.neighborhood.is_none_or(|neighborhood| neighborhood == RuleNeighborhood::default()) => {
+                self.isotropic = Some(IsotropicRule::parse(&rule).map_err(|_| err)?);
+            }
+            Err(err) => return Err(err),
+        }
+        Ok(())
+    }
+
+    // The rule in B/S notation, for saving patterns with, where the
+    // automaton runs one.
+    pub fn rulestring(&self) -> Option<String> {
+        match self.automaton {
+            Automaton::Life if self.stochastic.is_none() => {
+                Some(self.isotropic.as_ref().map_or_else(|| self.rule.to_string(), IsotropicRule::to_string))
+            }
+            Automaton::Sparse | Automaton::BitPacked => Some(self.rule.to_string()),
+            Automaton::LargerThanLife => self.ltl.as_ref().map(LtlRule::to_string),
+            _ => None,
+        }
+    }
+
+    // An explicit --neighborhood wins over the rulestring's suffix.
+    pub fn neighborhood(&self) -> RuleNeighborhood {
+        self.neighborhood.unwrap_or(self.rule.neighborhood())
+    }
+
+    pub fn cell_size(&self, rows: usize, cols: usize) -> usize {
+        self.cell_size.unwrap_or_else(|| (WINDOW / rows.max(cols)).clamp(1, 19))
+    }
+
+    // Most cells the window shows down and across at `cell_size`; bigger
+    // boards are panned around.
+    pub fn view(&self, cell_size: usize) -> (usize, usize) {
+        let cells = WINDOW / cell_size.max(1);
+        (cells, cells)
+    }
+
+    // A config file holds one `name = value` per line, or a bare `name` for
+    // switches like `wrap`, each standing for the matching --name argument.
+    // Blank lines and anything after a '#' are ignored.
+    pub fn file_args(text: &str) -> Vec<String> {
+        let mut args = Vec::new();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            match line.split_once('=') {
+                Some((name, value)) => args.extend([format!("--{}", name.trim()), value.trim().to_string()]),
+                None => args.push(format!("--{line}")),
+            }
+        }
+        args
+    }
+
+    // `--config PATH` reads the file's settings in its place, so arguments
+    // after it override the file and ones before it are overridden.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, Error> {
+        let mut config = Self::default();
+        let mut expanded = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--config" {
+                let path = args.next().ok_or_else(|| Error::Config("--config expects a value".to_string()))?;
+                expanded.extend(Self::file_args(&std::fs::read_to_string(path)?));
+            } else {
+                expanded.push(arg);
+            }
+        }
+        let mut args = expanded.into_iter();
+        let (mut rule_given, mut pattern_rule) = (false, None);
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| Error::Config(format!("{arg} expects a value")))
+            };
+            match arg.as_str() {
+                "--automaton" => config.automaton = Automaton::parse(&value()?)?,
+                "--rule" => {
+                    config.set_rule(&value()?)?;
+                    rule_given = true;
+                }
+                // The rule in an RLE file's header counts for less than a
+                // --rule argument, wherever that is.
+                "--pattern" => {
+                    let (pattern, rule) = formats::read(value()?)?;
+                    config.pattern = Some(pattern);
+                    pattern_rule = rule;
+                }
+                // Such as xq4_153, straight from apgsearch or Catagolue.
+                "--apgcode" => config.pattern = Some(formats::parse_apgcode(&value()?)?),
+                "--rule3d" => config.rule3d = value()?.parse()?,
+                "--neighborhood" => {
+                    let name = value()?;
+                    let neighborhood = RuleNeighborhood::parse(&name)
+                        .ok_or_else(|| Error::Config(format!("unknown neighborhood {name:?}")))?;
+                    config.neighborhood = Some(neighborhood);
+                }
+                "--ant" => config.ants.push(value()?.parse()?),
+                "--turmite" => {
+                    let spec = value()?;
+                    let spec = if Path::new(&spec).is_file() { std::fs::read_to_string(&spec)? } else { spec };
+                    config.turmite = Some(spec.parse()?);
+                }
+                // A rule file only means one thing, so it also picks the automaton.
+                "--rule-file" => {
+                    config.rule_file = Some(RuleFile::load(value()?)?);
+                    config.automaton = Automaton::RuleFile;
+                }
+                "--wolfram" => config.wolfram = number(&arg, &value()?)?,
+                "--states" => config.states = number(&arg, &value()?)?,
+                "--threshold" => config.threshold = number(&arg, &value()?)?,
+                "--seed" => config.seed = number(&arg, &value()?)?,
+                // A fraction, or a percentage with a trailing %.
+                "--density" => {
+                    let text = value()?;
+                    let density: f64 = match text.strip_suffix('%') {
+                        Some(percent) => number::<f64>(&arg, percent)? / 100.0,
+                        None => number(&arg, &text)?,
+                    };
+                    if !(0.0..=1.0).contains(&density) {
+                        return Err(Error::Config(format!("--density must be between 0 and 1, got {text:?}")));
+                    }
+                    config.density = density;
+                }
+                "--grains" => config.grains = number(&arg, &value()?)?,
+                "--growth" => config.growth = number(&arg, &value()?)?,
+                "--lightning" => config.lightning = number(&arg, &value()?)?,
+                "--lenia-radius" => config.lenia.radius = number(&arg, &value()?)?,
+                "--lenia-mu" => config.lenia.mu = number(&arg, &value()?)?,
+                "--lenia-sigma" => config.lenia.sigma = number(&arg, &value()?)?,
+                "--lenia-dt" => config.lenia.dt = number(&arg, &value()?)?,
+                // The height of each ring of the kernel, inside out, as in 1,0.5.
+                "--lenia-peaks" => {
+                    let peaks = value()?;
+                    let peaks = peaks.split(',').map(|peak| number(&arg, peak.trim()));
+                    config.lenia.peaks = peaks.collect::<Result<_, _>>()?;
+                }
+                "--boundary" => {
+                    let name = value()?;
+                    let boundary = match BoundaryCondition::parse(&name) {
+                        Some(boundary) => boundary,
+                        None if name.contains(',') => {
+                            let (boundary, size) = BoundaryCondition::parse_bounded_grid(&name)?;
+                            config.size = size.or(config.size);
+                            boundary
+                        }
+                        None => return Err(Error::Config(format!("unknown boundary {name:?}"))),
+                    };
+                    config.boundary = Some(boundary);
+                }
+                "--wrap" => config.boundary = Some(BoundaryCondition::Wrap),
+                "--size" => {
+                    let size = value()?;
+                    let (rows, cols) = size
+                        .split_once(['x', 'X'])
+                        .ok_or_else(|| Error::Config(format!("--size expects ROWSxCOLS, got {size:?}")))?;
+                    let (rows, cols) = (number(&arg, rows)?, number(&arg, cols)?);
+                    if rows == 0 || cols == 0 {
+                        return Err(Error::Config(format!("--size needs at least one row and column, got {size:?}")));
+                    }
+                    config.size = Some((rows, cols));
+                }
+                "--cell-size" => config.cell_size = Some(number(&arg, &value()?)?),
+                "--colormap" => {
+                    let name = value()?;
+                    let colormap = Colormap::parse(&name)
+                        .ok_or_else(|| Error::Config(format!("unknown colormap {name:?}")))?;
+                    config.colormap = Some(colormap);
+                }
+                "--theme" => {
+                    let name = value()?;
+                    config.theme = Theme::parse(&name).ok_or_else(|| Error::Config(format!("unknown theme {name:?}")))?;
+                }
+                "--background-color" => config.theme.background = color(&arg, &value()?)?,
+                "--alive-color" => config.theme.alive = color(&arg, &value()?)?,
+                "--dead-color" => config.theme.dead = color(&arg, &value()?)?,
+                "--dying-color" => config.theme.dying.0 = color(&arg, &value()?)?,
+                "--dying-end-color" => config.theme.dying.1 = color(&arg, &value()?)?,
+                "--grid-color" => config.theme.grid_line = color(&arg, &value()?)?,
+                "--cell-shape" => {
+                    let name = value()?;
+                    config.theme.shape =
+                        CellShape::parse(&name).ok_or_else(|| Error::Config(format!("unknown cell shape {name:?}")))?;
+                }
+                "--export" => config.export = value()?,
+                "--tick-ms" => config.tick_ms = number(&arg, &value()?)?,
+                "--warp" => config.warp = number::<usize>(&arg, &value()?)?.max(1),
+                "--jump" => config.jump = number(&arg, &value()?)?,
+                "--no-gpu" => config.gpu = false,
+                "--grid-lines" => config.grid_lines = true,
+                "--trails" => config.trails = true,
+                "--glow" => config.glow = true,
+                "--history" => config.history = number(&arg, &value()?)?,
+                "--rewind-mib" => config.rewind_mib = number(&arg, &value()?)?,
+                "--checkpoint-every" => config.checkpoint_every = number(&arg, &value()?)?,
+                "--timeline-mib" => config.timeline_mib = number(&arg, &value()?)?,
+                "--scrub" => config.scrub = number(&arg, &value()?)?,
+                "--bench" => config.bench = Some(number(&arg, &value()?)?),
+                "--threads" => config.threads = number(&arg, &value()?)?,
+                "--resize-step" => config.resize_step = number(&arg, &value()?)?,
+                "--anchor" => {
+                    let name = value()?;
+                    let anchor =
+                        Anchor::parse(&name).ok_or_else(|| Error::Config(format!("unknown anchor {name:?}")))?;
+                    config.anchor = anchor;
+                }
+                _ => return Err(Error::Config(format!("unknown argument {arg:?}"))),
+            }
+        }
+        // Golly saves Wireworld circuits under the rule "WireWorld", which
+        // names the automaton rather than a rule for Life.
+        if let Some(rule) = pattern_rule
+            && !rule_given
+        {
+            match Automaton::parse(&rule) {
+                Ok(Automaton::Wireworld) => config.automaton = Automaton::Wireworld,
+                _ => config.set_rule(&rule)?,
+            }
+        }
+        config.read_rule()?;
+        config.lenia.check()?;
+        if config.automaton == Automaton::RuleFile && config.rule_file.is_none() {
+            return Err(Error::Config("the rule-file automaton needs --rule-file PATH".to_string()));
+        }
+        if config.automaton == Automaton::LargerThanLife && config.ltl.is_none() {
+            return Err(Error::Config("the larger-than-life automaton needs an R..,B.. --rule".to_string()));
+        }
+        Ok(config)
+    }
+}
@@ -0,0 +1,64 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    Window(winit::error::OsError),
+    Surface(pixels::Error),
+    Frame(pixels::TextureError),
+    Pattern(String),
+    Rule(String),
+    Config(String),
+    Gamepad(String),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Window(err) => write!(f, "failed to create window: {err}"),
+            Error::Surface(err) => write!(f, "rendering surface error: {err}"),
+            Error::Frame(err) => write!(f, "failed to resize frame: {err}"),
+            Error::Pattern(msg) => write!(f, "invalid pattern: {msg}"),
+            Error::Rule(msg) => write!(f, "invalid rule: {msg}"),
+            Error::Config(msg) => write!(f, "invalid configuration: {msg}"),
+            Error::Gamepad(msg) => write!(f, "gamepad error: {msg}"),
+            Error::Io(err) => write!(f, "i/o error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Window(err) => Some(err),
+            Error::Surface(err) => Some(err),
+            Error::Frame(err) => Some(err),
+            Error::Io(err) => Some(err),
+            Error::Pattern(_) | Error::Rule(_) | Error::Config(_) | Error::Gamepad(_) => None,
+        }
+    }
+}
+
+impl From<winit::error::OsError> for Error {
+    fn from(err: winit::error::OsError) -> Self {
+        Error::Window(err)
+    }
+}
+
+impl From<pixels::Error> for Error {
+    fn from(err: pixels::Error) -> Self {
+        Error::Surface(err)
+    }
+}
+
+impl From<pixels::TextureError> for Error {
+    fn from(err: pixels::TextureError) -> Self {
+        Error::Frame(err)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
@@ -0,0 +1,105 @@
+// A 3x5 pixel font for overlay text. Each glyph is five rows, top first,
+// with the leftmost pixel in bit 2. Letters are drawn in upper case and
+// anything without a glyph is left blank.
+const fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        '=' => [0b000, 0b111, 0b000, 0b111, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        '(' => [0b010, 0b100, 0b100, 0b100, 0b010],
+        ')' => [0b010, 0b001, 0b001, 0b001, 0b010],
+        '[' => [0b110, 0b100, 0b100, 0b100, 0b110],
+        ']' => [0b011, 0b001, 0b001, 0b001, 0b011],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        '>' => [0b100, 0b010, 0b001, 0b010, 0b100],
+        '<' => [0b001, 0b010, 0b100, 0b010, 0b001],
+        '?' => [0b111, 0b001, 0b010, 0b000, 0b010],
+        '!' => [0b010, 0b010, 0b010, 0b000, 0b010],
+        '#' => [0b101, 0b111, 0b101, 0b111, 0b101],
+        '*' => [0b000, 0b101, 0b010, 0b101, 0b000],
+        _ => [0; 5],
+    }
+}
+
+pub const GLYPH_WIDTH: usize = 3;
+pub const GLYPH_HEIGHT: usize = 5;
+
+// Pixel size of `text` drawn at `scale`, one blank column between glyphs
+// and one blank row between lines.
+pub fn text_size(text: &str, scale: usize) -> (usize, usize) {
+    let lines = text.lines().count().max(1);
+    let longest = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+    (
+        (longest * (GLYPH_WIDTH + 1)).saturating_sub(1) * scale,
+        (lines * (GLYPH_HEIGHT + 1) - 1) * scale,
+    )
+}
+
+// Draws `text` with its top-left corner at (x, y) in a frame `width` pixels
+// wide, clipping whatever falls outside it.
+pub fn draw_text(frame: &mut [u8], width: usize, x: usize, y: usize, text: &str, scale: usize, color: [u8; 4]) {
+    for (line_index, line) in text.lines().enumerate() {
+        let top = y + line_index * (GLYPH_HEIGHT + 1) * scale;
+        for (i, c) in line.chars().enumerate() {
+            let left = x + i * (GLYPH_WIDTH + 1) * scale;
+            for (row, bits) in glyph(c).into_iter().enumerate() {
+                for col in (0..GLYPH_WIDTH).filter(|col| bits >> (GLYPH_WIDTH - 1 - col) & 1 != 0) {
+                    fill_rect(frame, width, (left + col * scale, top + row * scale), (scale, scale), color);
+                }
+            }
+        }
+    }
+}
+
+// Fills a `w` by `h` rectangle at (x, y), clipped to a frame `width`
+// pixels wide.
+pub fn fill_rect(frame: &mut [u8], width: usize, (x, y): (usize, usize), (w, h): (usize, usize), color: [u8; 4]) {
+    let height = frame.len() / 4 / width.max(1);
+    for row in y..(y + h).min(height) {
+        for col in x..(x + w).min(width) {
+            let idx = (row * width + col) * 4;
+            frame[idx..idx + 4].copy_from_slice(&color);
+        }
+    }
+}
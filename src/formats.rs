@@ -0,0 +1,780 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::{Error, Grid};
+
+fn bad(msg: impl Into<String>) -> Error {
+    Error::Pattern(msg.into())
+}
+
+// Reads a pattern file, going by its extension: .rle, plaintext .cells,
+// Life 1.05 or 1.06 .lif or .life, or Golly's macrocell .mc.
+pub fn load(path: impl AsRef<Path>) -> Result<Grid, Error> {
+    read(path).map(|(grid, _)| grid)
+}
+
+// The same, with the rule the file names, if its format has room for one.
+pub fn read(path: impl AsRef<Path>) -> Result<(Grid, Option<String>), Error> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)?;
+    match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("rle") => Ok((parse_rle(&text)?, parse_rle_header(&text)?.and_then(|header| header.rule))),
+        Some("cells") => Ok((parse_cells(&text)?, None)),
+        Some("lif" | "life") => parse_life(&text),
+        Some("mc") => parse_macrocell(&text),
+        _ => Err(bad(format!("{} isn't an .rle, .cells, .lif, .life or .mc file", path.display()))),
+    }
+}
+
+// Writes `pattern` to a pattern file, going by its extension as `load`
+// does, under `rule` where the format has room for one: Life 1.05 for .lif
+// and 1.06 for .life.
+pub fn save(path: impl AsRef<Path>, pattern: &Grid, rule: Option<&str>) -> Result<(), Error> {
+    let path = path.as_ref();
+    let text = match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("rle") => write_rle(pattern, rule),
+        Some("cells") => write_cells(pattern),
+        Some("lif") => write_life_105(pattern, rule)?,
+        Some("life") => write_life_106(pattern),
+        Some("mc") => write_macrocell(pattern, rule),
+        _ => return Err(bad(format!("{} isn't an .rle, .cells, .lif, .life or .mc file", path.display()))),
+    };
+    std::fs::write(path, text)?;
+    Ok(())
+}
+
+// The smallest part of `pattern` holding all its live cells.
+fn trim(pattern: &Grid) -> Grid {
+    let cells: Vec<_> = pattern.live_cells().collect();
+    let top = cells.iter().map(|&(row, _)| row).min().unwrap_or(0);
+    let left = cells.iter().map(|&(_, col)| col).min().unwrap_or(0);
+    let moved: Vec<_> = cells.iter().map(|&(row, col)| (row - top, col - left)).collect();
+    fit(&moved, (0, 0))
+}
+
+// A grid just big enough for `cells` at coordinates that may be negative,
+// moved to start from the top-left corner, unless it would be more than
+// `area` cells.
+fn fit_signed(cells: &[(i64, i64)], area: u64) -> Result<Grid, Error> {
+    let span = |axis: fn(&(i64, i64)) -> i64| {
+        let (low, high) = (cells.iter().map(axis).min().unwrap_or(0), cells.iter().map(axis).max().unwrap_or(0));
+        (low, high.abs_diff(low).saturating_add(1))
+    };
+    let ((top, rows), (left, cols)) = (span(|cell| cell.0), span(|cell| cell.1));
+    if rows.saturating_mul(cols) > area {
+        return Err(bad("pattern spreads out too far to fit in a grid"));
+    }
+    let moved: Vec<_> = cells.iter().map(|&(row, col)| (row.abs_diff(top) as usize, col.abs_diff(left) as usize)).collect();
+    Ok(fit(&moved, (0, 0)))
+}
+
+// A grid big enough for `cells` and at least `size`.
+fn fit(cells: &[(usize, usize)], size: (usize, usize)) -> Grid {
+    let rows = cells.iter().map(|&(row, _)| row + 1).max().unwrap_or(0).max(size.0);
+    let cols = cells.iter().map(|&(_, col)| col + 1).max().unwrap_or(0).max(size.1);
+    let mut grid = Grid::new(rows, cols);
+    for &(row, col) in cells {
+        grid.set(row, col, true);
+    }
+    grid
+}
+
+// What an RLE file's header line says, as in `x = 3, y = 3, rule = B3/S23`:
+// the pattern's width and height, and the rule it runs under if given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RleHeader {
+    pub cols: usize,
+    pub rows: usize,
+    pub rule: Option<String>,
+}
+
+// Most cells an RLE pattern can cover, by its header or its runs.
+const RLE_AREA: u64 = 1 << 28;
+
+// The first line of an RLE file that isn't a comment, if it's an `x = ...`
+// header; any other line is the start of the cells.
+fn rle_header_line(text: &str) -> Option<&str> {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.starts_with('#'))
+        .filter(|line| line.strip_prefix('x').is_some_and(|rest| rest.trim_start().starts_with('=')))
+}
+
+// The header of an RLE file, if it has one. Settings other than these are
+// skipped.
+pub fn parse_rle_header(text: &str) -> Result<Option<RleHeader>, Error> {
+    let Some(line) = rle_header_line(text) else {
+        return Ok(None);
+    };
+    let mut header = RleHeader { cols: 0, rows: 0, rule: None };
+    for setting in line.split(',') {
+        let (name, value) = setting.split_once('=').ok_or_else(|| bad(format!("expected name = value in {setting:?}")))?;
+        let size = || value.trim().parse().map_err(|_| bad(format!("bad size {:?} in RLE header", value.trim())));
+        match name.trim() {
+            "x" => header.cols = size()?,
+            "y" => header.rows = size()?,
+            "rule" => header.rule = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    if (header.cols as u64).saturating_mul(header.rows as u64) > RLE_AREA {
+        return Err(bad(format!("RLE pattern of {} by {} cells is too large", header.cols, header.rows)));
+    }
+    Ok(Some(header))
+}
+
+// Run Length Encoded cells: runs of `b` for dead cells and `o` for live
+// ones, or `.` and `A` to `X` for states 0 to 24 as Golly writes patterns
+// with more states, `$` ending rows and `!` the pattern, each optionally
+// preceded by a count. Comments are skipped, and the grid is at least the size the header
+// gives.
+pub fn parse_rle(text: &str) -> Result<Grid, Error> {
+    let size = parse_rle_header(text)?.map_or((0, 0), |header| (header.rows, header.cols));
+    // Live runs as they're read, a row, the columns it covers and their
+    // state, and the columns the widest of them reaches.
+    let (mut runs, mut width) = (Vec::new(), 0);
+    let (mut row, mut col) = (0usize, 0usize);
+    // Digits read so far of the next count; 0 if there are none.
+    let mut count = 0usize;
+    let header = rle_header_line(text).is_some();
+    let body = text.lines().map(str::trim).filter(|line| !line.starts_with('#')).skip(header as usize);
+    let too_large = || bad("RLE pattern is too large");
+    'lines: for line in body {
+        for ch in line.chars().filter(|ch| !ch.is_whitespace()) {
+            if let Some(digit) = ch.to_digit(10) {
+                count = count
+                    .checked_mul(10)
+                    .and_then(|count| count.checked_add(digit as usize))
+                    .ok_or_else(|| bad("run count is too large"))?;
+                continue;
+            }
+            let run = std::mem::take(&mut count).max(1);
+            match ch {
+                'b' | '.' => col = col.checked_add(run).ok_or_else(too_large)?,
+                '$' => (row, col) = (row.checked_add(run).ok_or_else(too_large)?, 0),
+                '!' => break 'lines,
+                ch if ch.is_ascii_alphabetic() => {
+                    let state = if ('A'..='X').contains(&ch) { ch as u8 - b'A' + 1 } else { 1 };
+                    let end = col.checked_add(run).ok_or_else(too_large)?;
+                    width = width.max(end);
+                    if (row as u64).saturating_add(1).saturating_mul(width as u64) > RLE_AREA {
+                        return Err(too_large());
+                    }
+                    runs.push((row, col..end, state));
+                    col = end;
+                }
+                ch => return Err(bad(format!("unexpected {ch:?} in RLE"))),
+            }
+        }
+    }
+    let rows = runs.last().map_or(0, |(row, _, _)| row + 1).max(size.0);
+    let mut grid = Grid::new(rows, width.max(size.1));
+    for (row, cols, state) in runs {
+        for col in cols {
+            grid.set_state(row, col, state);
+        }
+    }
+    Ok(grid)
+}
+
+// Plaintext rows of `.` for dead cells and `O` or `*` for live ones, with
+// `!` comment lines. The grid is as wide as the widest row.
+pub fn parse_cells(text: &str) -> Result<Grid, Error> {
+    let (mut cells, mut size) = (Vec::new(), (0, 0));
+    for (row, line) in text.lines().filter(|line| !line.starts_with('!')).enumerate() {
+        let line = line.trim_end();
+        if !line.is_empty() {
+            size = (row + 1, size.1.max(line.chars().count()));
+        }
+        for (col, ch) in line.chars().enumerate() {
+            match ch {
+                'O' | '*' => cells.push((row, col)),
+                '.' => {}
+                ch => return Err(bad(format!("unexpected {ch:?} in .cells row {}", row + 1))),
+            }
+        }
+    }
+    Ok(fit(&cells, size))
+}
+
+// `pattern` trimmed to its live cells as plaintext, a full row of `.` and
+// `O` to a line.
+pub fn write_cells(pattern: &Grid) -> String {
+    let pattern = trim(pattern);
+    let mut text = String::new();
+    for row in 0..pattern.rows() {
+        text.extend((0..pattern.cols()).map(|col| if pattern.get(row, col) { 'O' } else { '.' }));
+        text.push('\n');
+    }
+    text
+}
+
+// Most characters on a line of RLE, as Golly writes it.
+const RLE_LINE: usize = 70;
+
+// `pattern` trimmed to its live cells as RLE, with a header giving its size
+// and `rule` if there is one.
+pub fn write_rle(pattern: &Grid, rule: Option<&str>) -> String {
+    let pattern = trim(pattern);
+    let mut text = format!("x = {}, y = {}", pattern.cols(), pattern.rows());
+    if let Some(rule) = rule {
+        text += &format!(", rule = {rule}");
+    }
+    // Runs of dead cells at the ends of rows are left off, and runs of
+    // ended rows run together.
+    let mut runs: Vec<(usize, char)> = Vec::new();
+    let mut push = |count: usize, tag: char| match runs.last_mut() {
+        Some((run, last)) if *last == tag => *run += count,
+        _ => runs.push((count, tag)),
+    };
+    for row in 0..pattern.rows() {
+        if row > 0 {
+            push(1, '$');
+        }
+        let mut col = 0;
+        while col < pattern.cols() {
+            let alive = pattern.get(row, col);
+            let run = (col..pattern.cols()).take_while(|&c| pattern.get(row, c) == alive).count();
+            if alive || col + run < pattern.cols() {
+                push(run, if alive { 'o' } else { 'b' });
+            }
+            col += run;
+        }
+    }
+    push(1, '!');
+    let mut line = String::new();
+    for (count, tag) in runs {
+        let run = if count > 1 { format!("{count}{tag}") } else { tag.to_string() };
+        if line.len() + run.len() > RLE_LINE {
+            text += &format!("\n{line}");
+            line.clear();
+        }
+        line += &run;
+    }
+    text + &format!("\n{line}\n")
+}
+
+// Most cells a Life 1.05 or 1.06 pattern can spread out into a grid.
+const LIFE_AREA: u64 = 1 << 28;
+
+// The rule a Life 1.05 `#R` line gives, survival counts first, as the B/S
+// notation the rest of the program uses.
+fn life_105_rule(rule: &str) -> String {
+    match rule.split_once('/') {
+        Some((survival, birth)) if !rule.contains(['B', 'b', 'S', 's']) => format!("B{birth}/S{survival}"),
+        _ => rule.to_string(),
+    }
+}
+
+// Life 1.05 or 1.06, going by the `#Life` line the file starts with, and the
+// rule it names if it's 1.05 and does.
+pub fn parse_life(text: &str) -> Result<(Grid, Option<String>), Error> {
+    match text.lines().next().map(str::trim) {
+        Some("#Life 1.05") => parse_life_105(text),
+        Some("#Life 1.06") => Ok((parse_life_106(text)?, None)),
+        _ => Err(bad("expected a #Life 1.05 or #Life 1.06 line first")),
+    }
+}
+
+// Blocks of `.` for dead cells and `*` for live ones, each after a
+// `#P x y` line giving where its top-left corner goes, with `#N` for
+// Conway's rule or `#R survival/birth` for another. Other `#` lines are
+// skipped.
+pub fn parse_life_105(text: &str) -> Result<(Grid, Option<String>), Error> {
+    let (mut cells, mut rule) = (Vec::new(), None);
+    let (mut corner, mut row) = ((0i64, 0i64), 0i64);
+    for line in text.lines().map(str::trim_end) {
+        if let Some(at) = line.strip_prefix("#P") {
+            let mut numbers = at.split_whitespace().map(|number| number.parse::<i64>());
+            match (numbers.next(), numbers.next()) {
+                (Some(Ok(x)), Some(Ok(y))) => (corner, row) = ((y, x), 0),
+                _ => return Err(bad(format!("expected #P x y, got {line:?}"))),
+            }
+        } else if line.starts_with("#N") {
+            rule = Some("B3/S23".to_string());
+        } else if let Some(given) = line.strip_prefix("#R") {
+            rule = Some(life_105_rule(given.trim()));
+        } else if !line.starts_with('#') {
+            for (col, ch) in line.chars().enumerate() {
+                match ch {
+                    '*' => {
+                        let cell = corner.0.checked_add(row).zip(corner.1.checked_add(col as i64));
+                        cells.push(cell.ok_or_else(|| bad(format!("Life 1.05 block at {corner:?} goes too far")))?);
+                    }
+                    '.' => {}
+                    ch => return Err(bad(format!("unexpected {ch:?} in Life 1.05"))),
+                }
+            }
+            row += 1;
+        }
+    }
+    Ok((fit_signed(&cells, LIFE_AREA)?, rule))
+}
+
+// A live cell's `x y` to a line, either of them maybe negative.
+pub fn parse_life_106(text: &str) -> Result<Grid, Error> {
+    let mut cells = Vec::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let mut numbers = line.split_whitespace().map(|number| number.parse::<i64>());
+        match (numbers.next(), numbers.next(), numbers.next()) {
+            (Some(Ok(x)), Some(Ok(y)), None) => cells.push((y, x)),
+            _ => return Err(bad(format!("expected x y, got {line:?}"))),
+        }
+    }
+    fit_signed(&cells, LIFE_AREA)
+}
+
+// Widest a row of a Life 1.05 block goes; wider patterns are cut into
+// blocks side by side.
+const LIFE_105_LINE: usize = 80;
+
+// `pattern` trimmed to its live cells as Life 1.05, centered on the origin,
+// with `rule`, which has to be plain B/S notation.
+pub fn write_life_105(pattern: &Grid, rule: Option<&str>) -> Result<String, Error> {
+    let pattern = trim(pattern);
+    let mut text = "#Life 1.05\n".to_string();
+    let counts = |part: &str, tag| part.strip_prefix(tag).is_some_and(|counts| counts.chars().all(|ch| ch.is_ascii_digit()));
+    match rule.map(|rule| rule.split('/').collect::<Vec<_>>()).as_deref() {
+        Some(["B3", "S23"]) | None => text += "#N\n",
+        Some([birth, survival]) if counts(birth, 'B') && counts(survival, 'S') => {
+            text += &format!("#R {}/{}\n", &survival[1..], &birth[1..]);
+        }
+        // Only two-state rules on the eight nearest neighbors fit.
+        Some(_) => return Err(bad(format!("Life 1.05 can't hold the rule {:?}; save as .rle or .mc", rule.unwrap_or_default()))),
+    }
+    let (top, left) = (-((pattern.rows() / 2) as i64), -((pattern.cols() / 2) as i64));
+    for from in (0..pattern.cols()).step_by(LIFE_105_LINE) {
+        let to = (from + LIFE_105_LINE).min(pattern.cols());
+        text += &format!("#P {} {}\n", left + from as i64, top);
+        for row in 0..pattern.rows() {
+            let line: String = (from..to).map(|col| if pattern.get(row, col) { '*' } else { '.' }).collect();
+            let line = line.trim_end_matches('.');
+            text += if line.is_empty() { "." } else { line };
+            text += "\n";
+        }
+    }
+    Ok(text)
+}
+
+// `pattern`'s live cells as Life 1.06, centered on the origin.
+pub fn write_life_106(pattern: &Grid) -> String {
+    let pattern = trim(pattern);
+    let (top, left) = ((pattern.rows() / 2) as i64, (pattern.cols() / 2) as i64);
+    let mut text = "#Life 1.06\n".to_string();
+    for (row, col) in pattern.live_cells() {
+        text += &format!("{} {}\n", col as i64 - left, row as i64 - top);
+    }
+    text
+}
+
+// Most live cells, and most cells in all, a macrocell file can spread out
+// into a grid; bigger patterns wait on an engine that keeps the quadtree.
+const MACROCELL_LIVE: u64 = 1 << 26;
+const MACROCELL_AREA: u64 = 1 << 28;
+
+// A node of a macrocell quadtree: an 8x8 leaf, a byte to a row with the
+// leftmost cell in the top bit, or a square 2^level cells across of four
+// nodes a level down, top-left, top-right, bottom-left then bottom-right,
+// by their numbers counting from 1, with 0 for an empty one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Macrocell {
+    Leaf([u8; 8]),
+    Branch(u32, [usize; 4]),
+}
+
+impl Macrocell {
+    fn level(self) -> u32 {
+        match self {
+            Macrocell::Leaf(_) => 3,
+            Macrocell::Branch(level, _) => level,
+        }
+    }
+}
+
+// Golly's macrocell format: a `[M2]` line, `#R` giving the rule and other
+// `#` lines skipped, then a node to a line, each after the ones it's made
+// of and the last the whole pattern. Leaves are rows of `.` and `*` each
+// ended by `$`, and bigger nodes their level and four node numbers.
+pub fn parse_macrocell(text: &str) -> Result<(Grid, Option<String>), Error> {
+    let mut lines = text.lines().map(str::trim);
+    if !lines.next().is_some_and(|line| line.starts_with("[M2]")) {
+        return Err(bad("expected an [M2] line first"));
+    }
+    let (mut nodes, mut rule) = (Vec::new(), None);
+    // Live cells under each node, to check the pattern fits before
+    // spreading it out and to skip empty nodes doing it.
+    let mut populations: Vec<u64> = Vec::new();
+    let population = |populations: &[u64], child: usize| child.checked_sub(1).map_or(0, |i| populations[i]);
+    for line in lines.filter(|line| !line.is_empty()) {
+        if let Some(given) = line.strip_prefix("#R") {
+            rule = Some(given.trim().to_string());
+        } else if line.starts_with('#') {
+            continue;
+        } else if line.starts_with(['.', '*', '$']) {
+            let (mut rows, mut row, mut col) = ([0u8; 8], 0, 0);
+            for ch in line.chars() {
+                match ch {
+                    '.' => col += 1,
+                    '*' if row < 8 && col < 8 => {
+                        rows[row] |= 0x80 >> col;
+                        col += 1;
+                    }
+                    '$' => (row, col) = (row + 1, 0),
+                    _ => return Err(bad(format!("bad macrocell leaf {line:?}"))),
+                }
+            }
+            nodes.push(Macrocell::Leaf(rows));
+            populations.push(rows.iter().map(|bits| bits.count_ones() as u64).sum());
+        } else {
+            let numbers: Vec<usize> = line
+                .split_whitespace()
+                .map(|number| number.parse().map_err(|_| bad(format!("bad macrocell node {line:?}"))))
+                .collect::<Result<_, _>>()?;
+            let &[level, nw, ne, sw, se] = numbers.as_slice() else {
+                return Err(bad(format!("expected a level and four nodes, got {line:?}")));
+            };
+            let children = [nw, ne, sw, se];
+            // Children come first, a level down; coordinates have to fit in an i64.
+            let fits = |child: usize| {
+                child == 0 || nodes.get(child - 1).is_some_and(|node: &Macrocell| node.level() + 1 == level as u32)
+            };
+            if !(4..=62).contains(&level) || !children.into_iter().all(fits) {
+                return Err(bad(format!("macrocell node {line:?} doesn't fit its children")));
+            }
+            nodes.push(Macrocell::Branch(level as u32, children));
+            populations.push(children.into_iter().fold(0, |sum, child| sum.saturating_add(population(&populations, child))));
+        }
+    }
+    if population(&populations, nodes.len()) > MACROCELL_LIVE {
+        return Err(bad("macrocell pattern has too many live cells to fit in a grid"));
+    }
+    let mut cells = Vec::new();
+    if !nodes.is_empty() {
+        spread(&nodes, &populations, nodes.len(), (0, 0), &mut cells);
+    }
+    Ok((fit_signed(&cells, MACROCELL_AREA)?, rule))
+}
+
+// The live cells of node `number`, with its top-left corner at `corner`.
+fn spread(nodes: &[Macrocell], populations: &[u64], number: usize, corner: (i64, i64), cells: &mut Vec<(i64, i64)>) {
+    match nodes[number - 1] {
+        Macrocell::Leaf(rows) => {
+            for (row, bits) in rows.into_iter().enumerate() {
+                let live = (0..8).filter(|col| bits & (0x80 >> col) != 0);
+                cells.extend(live.map(|col| (corner.0 + row as i64, corner.1 + col as i64)));
+            }
+        }
+        Macrocell::Branch(level, children) => {
+            let half = 1i64 << (level - 1);
+            let corners = [(0, 0), (0, half), (half, 0), (half, half)];
+            for (child, (down, right)) in children.into_iter().zip(corners) {
+                if child != 0 && populations[child - 1] > 0 {
+                    spread(nodes, populations, child, (corner.0 + down, corner.1 + right), cells);
+                }
+            }
+        }
+    }
+}
+
+// `pattern` as a macrocell quadtree under `rule`, with identical nodes
+// written once.
+pub fn write_macrocell(pattern: &Grid, rule: Option<&str>) -> String {
+    let pattern = trim(pattern);
+    let mut level = 3;
+    while 1 << level < pattern.rows().max(pattern.cols()) {
+        level += 1;
+    }
+    let mut writer = MacrocellWriter {
+        text: "[M2] (simple_program_1)\n".to_string(),
+        numbers: HashMap::new(),
+    };
+    if let Some(rule) = rule {
+        writer.text += &format!("#R {rule}\n");
+    }
+    // Even an empty pattern needs a node to be the whole of it.
+    if writer.node(&pattern, level, (0, 0)) == 0 {
+        writer.number(Macrocell::Leaf([0; 8]));
+    }
+    writer.text
+}
+
+// The file so far, and the number given each node written to it.
+struct MacrocellWriter {
+    text: String,
+    numbers: HashMap<Macrocell, usize>,
+}
+
+impl MacrocellWriter {
+    // Writes the node 2^level cells across from `corner` of `pattern`, and
+    // any under it not written yet, and returns its number.
+    fn node(&mut self, pattern: &Grid, level: u32, (top, left): (usize, usize)) -> usize {
+        if top >= pattern.rows() || left >= pattern.cols() {
+            return 0;
+        }
+        let node = if level == 3 {
+            let rows = std::array::from_fn(|row| {
+                (0..8).filter(|&col| top + row < pattern.rows() && left + col < pattern.cols()).fold(0u8, |bits, col| {
+                    if pattern.get(top + row, left + col) { bits | 0x80 >> col } else { bits }
+                })
+            });
+            Macrocell::Leaf(rows)
+        } else {
+            let half = 1 << (level - 1);
+            let corners = [(top, left), (top, left + half), (top + half, left), (top + half, left + half)];
+            Macrocell::Branch(level, corners.map(|corner| self.node(pattern, level - 1, corner)))
+        };
+        match node {
+            Macrocell::Leaf([0, 0, 0, 0, 0, 0, 0, 0]) | Macrocell::Branch(_, [0, 0, 0, 0]) => 0,
+            node => self.number(node),
+        }
+    }
+
+    fn number(&mut self, node: Macrocell) -> usize {
+        if let Some(&number) = self.numbers.get(&node) {
+            return number;
+        }
+        match node {
+            // Rows end at their last live cell, and the leaf at its last row
+            // with any.
+            Macrocell::Leaf(rows) => {
+                let last = rows.iter().rposition(|&bits| bits != 0).map_or(0, |row| row + 1);
+                let mut line = String::new();
+                for bits in &rows[..last] {
+                    line.extend((0..8 - bits.trailing_zeros() as usize).map(|col| {
+                        if bits & (0x80 >> col) != 0 { '*' } else { '.' }
+                    }));
+                    line.push('$');
+                }
+                self.text += &format!("{}\n", if line.is_empty() { "$" } else { &line });
+            }
+            Macrocell::Branch(level, [nw, ne, sw, se]) => self.text += &format!("{level} {nw} {ne} {sw} {se}\n"),
+        }
+        let number = self.numbers.len() + 1;
+        self.numbers.insert(node, number);
+        number
+    }
+}
+
+// Most cells an apgcode's object can cover.
+const APGCODE_AREA: u64 = 1 << 28;
+
+// The object an apgcode from apgsearch or Catagolue names, such as `xq4_153`
+// for the glider. Only still lifes, oscillators and spaceships (`xs`, `xp`
+// and `xq`) spell out their cells. After the `_` the cells come in strips
+// five rows deep, split by `z`, each a column to a character from `0` to
+// `v` with the top row in the lowest bit; `w` and `x` stand for two and
+// three empty columns, and `y` then a character from `0` to `z` for four
+// or more.
+pub fn parse_apgcode(code: &str) -> Result<Grid, Error> {
+    let (prefix, cells) = code.split_once('_').ok_or_else(|| bad(format!("{code:?} isn't an apgcode")))?;
+    if !["xs", "xp", "xq"].iter().any(|kind| prefix.starts_with(kind)) {
+        return Err(bad(format!("{code:?} doesn't spell out its cells; only xs, xp and xq apgcodes do")));
+    }
+    let mut live = Vec::new();
+    let (mut strip, mut col) = (0, 0);
+    let mut chars = cells.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            'w' => col += 2,
+            'x' => col += 3,
+            'y' => {
+                let count = chars.next().and_then(|ch| ch.to_digit(36)).ok_or_else(|| bad(format!("bad run in {code:?}")))?;
+                col += 4 + count as usize;
+            }
+            'z' => (strip, col) = (strip + 1, 0),
+            ch => {
+                let bits = ch.to_digit(32).ok_or_else(|| bad(format!("unexpected {ch:?} in {code:?}")))?;
+                live.extend((0..5).filter(|row| bits & 1 << row != 0).map(|row| (strip * 5 + row, col)));
+                col += 1;
+            }
+        }
+    }
+    let rows = live.iter().map(|&(row, _)| row as u64 + 1).max().unwrap_or(0);
+    let cols = live.iter().map(|&(_, col)| col as u64 + 1).max().unwrap_or(0);
+    if rows.saturating_mul(cols) > APGCODE_AREA {
+        return Err(bad(format!("{code:?} spreads out too far to fit in a grid")));
+    }
+    Ok(trim(&fit(&live, (0, 0))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Grid {
+        Grid::from(rows.iter().map(|row| row.chars().map(|ch| ch == 'O').collect()).collect::<Vec<Vec<bool>>>())
+    }
+
+    fn glider() -> Grid {
+        grid(&["OOO", "..O", ".O."])
+    }
+
+    // Wider than an RLE or Life 1.05 line, with an empty row and a leaf's
+    // worth of empty columns inside it.
+    fn wide() -> Grid {
+        let mut wide = Grid::new(5, 100);
+        for col in (0..40).chain(60..100).step_by(3) {
+            wide.set(0, col, true);
+            wide.set(2, col + 1, true);
+        }
+        wide.set(4, 99, true);
+        wide
+    }
+
+    fn is_bad<T>(result: Result<T, Error>) -> bool {
+        matches!(result, Err(Error::Pattern(_)))
+    }
+
+    // A macrocell file of `leaf` and then a node at each of `levels`, made
+    // of the node before it wherever `children` puts its number.
+    fn macrocell(leaf: &str, levels: std::ops::RangeInclusive<usize>, children: fn(usize) -> [usize; 4]) -> String {
+        let mut text = format!("[M2]\n{leaf}\n");
+        for (number, level) in levels.enumerate() {
+            let [nw, ne, sw, se] = children(number + 1);
+            text += &format!("{level} {nw} {ne} {sw} {se}\n");
+        }
+        text
+    }
+
+    #[test]
+    fn rle_round_trips() {
+        for pattern in [glider(), wide()] {
+            let text = write_rle(&pattern, Some("B36/S23"));
+            assert_eq!(parse_rle(&text).unwrap(), pattern);
+            assert_eq!(parse_rle_header(&text).unwrap().and_then(|header| header.rule).as_deref(), Some("B36/S23"));
+        }
+    }
+
+    #[test]
+    fn rle_reads_a_body_starting_with_x() {
+        assert_eq!(parse_rle("#C no header\nxo$ob!").unwrap(), grid(&["OO", "O."]));
+        assert_eq!(parse_rle_header("xo$ob!").unwrap(), None);
+    }
+
+    #[test]
+    fn rle_keeps_multistate_cells() {
+        let circuit = parse_rle("x = 4, y = 1, rule = WireWorld\n.CAB!").unwrap();
+        assert_eq!((0..4).map(|col| circuit.state(0, col)).collect::<Vec<_>>(), [0, 3, 1, 2]);
+    }
+
+    #[test]
+    fn rle_rejects_bad_input() {
+        assert!(is_bad(parse_rle("bo@!")));
+        assert!(is_bad(parse_rle("x = 3, y = z\n3o!")));
+        assert!(is_bad(parse_rle("x = 100000, y = 100000\no!")));
+        assert!(is_bad(parse_rle("99999999999999999999o!")));
+        assert!(is_bad(parse_rle("18446744073709551615bo!")));
+        assert!(is_bad(parse_rle("18446744073709551615$$o!")));
+        assert!(is_bad(parse_rle("o268435456$o!")));
+        assert!(is_bad(parse_rle("268435457o!")));
+    }
+
+    #[test]
+    fn cells_round_trip() {
+        for pattern in [glider(), wide()] {
+            assert_eq!(parse_cells(&write_cells(&pattern)).unwrap(), pattern);
+        }
+        assert!(is_bad(parse_cells("!Name: nothing\nO.X")));
+    }
+
+    #[test]
+    fn life_105_round_trips() {
+        for pattern in [glider(), wide()] {
+            let (read, rule) = parse_life(&write_life_105(&pattern, Some("B36/S23")).unwrap()).unwrap();
+            assert_eq!((read, rule.as_deref()), (pattern.clone(), Some("B36/S23")));
+            let (read, rule) = parse_life(&write_life_105(&pattern, None).unwrap()).unwrap();
+            assert_eq!((read, rule.as_deref()), (pattern, Some("B3/S23")));
+        }
+    }
+
+    #[test]
+    fn life_105_refuses_rules_it_cant_hold() {
+        for rule in ["B3/S23/C4", "B3/S23V", "B2-a/S12", "R5,C0,M1,S34..58,B34..45,NM"] {
+            assert!(is_bad(write_life_105(&glider(), Some(rule))));
+        }
+    }
+
+    #[test]
+    fn life_106_round_trips() {
+        for pattern in [glider(), wide()] {
+            assert_eq!(parse_life(&write_life_106(&pattern)).unwrap(), (pattern, None));
+        }
+    }
+
+    #[test]
+    fn life_rejects_bad_input() {
+        assert!(is_bad(parse_life("**\n")));
+        assert!(is_bad(parse_life("#Life 1.05\n#P a b\n*")));
+        assert!(is_bad(parse_life("#Life 1.05\n#P 0 0\n*o*")));
+        assert!(is_bad(parse_life("#Life 1.06\n1 2 3")));
+        assert!(is_bad(parse_life("#Life 1.06\n1")));
+        assert!(is_bad(parse_life("#Life 1.05\n#P 9223372036854775807 0\n**")));
+        assert!(is_bad(parse_life("#Life 1.06\n-9223372036854775808 0\n9223372036854775807 0")));
+        assert!(is_bad(parse_life("#Life 1.06\n0 0\n1000000000 1000000000")));
+    }
+
+    #[test]
+    fn macrocell_round_trips() {
+        for pattern in [glider(), wide()] {
+            let (read, rule) = parse_macrocell(&write_macrocell(&pattern, Some("B36/S23"))).unwrap();
+            assert_eq!((read, rule.as_deref()), (pattern, Some("B36/S23")));
+        }
+        let (empty, _) = parse_macrocell(&write_macrocell(&Grid::new(4, 4), None)).unwrap();
+        assert_eq!(empty.population(), 0);
+    }
+
+    #[test]
+    fn macrocell_rejects_bad_input() {
+        assert!(is_bad(parse_macrocell("*$\n")));
+        assert!(is_bad(parse_macrocell("[M2]\n*q$\n")));
+        assert!(is_bad(parse_macrocell("[M2]\n$$$$$$$$*$\n")));
+        assert!(is_bad(parse_macrocell("[M2]\n.........*$\n")));
+        assert!(is_bad(parse_macrocell("[M2]\n*$\n4 1 0 0\n")));
+        assert!(is_bad(parse_macrocell("[M2]\n*$\n4 1 0 0 x\n")));
+        assert!(is_bad(parse_macrocell("[M2]\n4 1 0 0 0\n")));
+        assert!(is_bad(parse_macrocell("[M2]\n*$\n5 1 0 0 0\n")));
+        assert!(is_bad(parse_macrocell("[M2]\n*$\n4 2 0 0 0\n")));
+        assert!(is_bad(parse_macrocell(&macrocell("*$", 4..=63, |child| [child, 0, 0, 0]))));
+    }
+
+    #[test]
+    fn macrocell_refuses_patterns_too_big_for_a_grid() {
+        let refused = |text: &str, limit: u64, why: &str| match parse_macrocell(text) {
+            Err(Error::Pattern(msg)) => assert!(msg.contains(why), "expected {why:?} past {limit}, got {msg:?}"),
+            other => panic!("expected {why:?} past {limit}, got {other:?}"),
+        };
+        // Every level of full leaves has four times the live cells of the
+        // one below, so level 14 has 2^28.
+        let full = "********$".repeat(8);
+        refused(&macrocell(&full, 4..=14, |child| [child; 4]), MACROCELL_LIVE, "too many live cells");
+        // Cells only ever in opposite corners stay few but far apart: level
+        // 16 spans some 2^16 cells each way.
+        refused(&macrocell("*$", 4..=16, |child| [child, 0, 0, child]), MACROCELL_AREA, "too far");
+        let (near, _) = parse_macrocell(&macrocell("*$", 4..=12, |child| [child, 0, 0, child])).unwrap();
+        assert_eq!(near.population(), 1 << 9);
+    }
+
+    #[test]
+    fn apgcodes_decode() {
+        assert_eq!(parse_apgcode("xq4_153").unwrap(), glider());
+        assert_eq!(parse_apgcode("xs4_33").unwrap(), grid(&["OO", "OO"]));
+        assert_eq!(parse_apgcode("xp2_7").unwrap(), grid(&["O", "O", "O"]));
+        assert_eq!(parse_apgcode("xs2_1w1").unwrap(), grid(&["O..O"]));
+        assert_eq!(parse_apgcode("xs2_1y01").unwrap(), grid(&["O....O"]));
+        assert_eq!(parse_apgcode("xp2_7z7").unwrap(), grid(&["O", "O", "O", ".", ".", "O", "O", "O"]));
+    }
+
+    #[test]
+    fn apgcodes_reject_bad_input() {
+        assert!(is_bad(parse_apgcode("xq4")));
+        assert!(is_bad(parse_apgcode("yl144_1_16_afb5f3db909e60548f086e22ee3353ac")));
+        assert!(is_bad(parse_apgcode("xs4_!")));
+        assert!(is_bad(parse_apgcode("xs4_1y")));
+        // 100,001 rows by 2,731 columns is past APGCODE_AREA, though only two
+        // cells are live.
+        let far = format!("xs2_1{}{}1", "z".repeat(20_000), "yz".repeat(70));
+        assert!(is_bad(parse_apgcode(&far)));
+        let near = format!("xs2_1{}{}1", "z".repeat(2_000), "yz".repeat(70));
+        assert_eq!(parse_apgcode(&near).unwrap().population(), 2);
+    }
+}
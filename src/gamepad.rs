@@ -0,0 +1,55 @@
+use gilrs::{Button, EventType, Gilrs};
+
+use crate::Error;
+
+// What a gamepad button asks for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    // Moving the view one notch down and right; negative goes back.
+    Pan(isize, isize),
+    Faster,
+    Slower,
+    Pause,
+    Step,
+}
+
+impl Action {
+    // The d-pad pans, the triggers change speed, A pauses and B steps.
+    fn of(button: Button) -> Option<Self> {
+        match button {
+            Button::DPadUp => Some(Action::Pan(-1, 0)),
+            Button::DPadDown => Some(Action::Pan(1, 0)),
+            Button::DPadLeft => Some(Action::Pan(0, -1)),
+            Button::DPadRight => Some(Action::Pan(0, 1)),
+            Button::RightTrigger | Button::RightTrigger2 => Some(Action::Faster),
+            Button::LeftTrigger | Button::LeftTrigger2 => Some(Action::Slower),
+            Button::South => Some(Action::Pause),
+            Button::East => Some(Action::Step),
+            _ => None,
+        }
+    }
+}
+
+// Every connected gamepad. Nothing wakes the event loop for them, so they
+// have to be polled.
+pub struct Gamepad {
+    gilrs: Gilrs,
+}
+
+impl Gamepad {
+    pub fn new() -> Result<Self, Error> {
+        let gilrs = Gilrs::new().map_err(|err| Error::Gamepad(err.to_string()))?;
+        Ok(Self { gilrs })
+    }
+
+    // What the buttons pressed since the last poll ask for, in order.
+    pub fn poll(&mut self) -> Vec<Action> {
+        let mut actions = Vec::new();
+        while let Some(event) = self.gilrs.next_event() {
+            if let EventType::ButtonPressed(button, _) = event.event {
+                actions.extend(Action::of(button));
+            }
+        }
+        actions
+    }
+}
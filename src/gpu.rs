@@ -0,0 +1,201 @@
+use pixels::wgpu;
+
+// Draws square-lattice boards on the GPU: each cell's color is one texel of
+// a texture the size of the board, scaled up by a fragment shader, so
+// drawing costs one write per cell however large the window is.
+pub struct CellPass {
+    rows: usize,
+    cols: usize,
+    colors: Vec<u8>,
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl CellPass {
+    // For a board of `rows` by `cols` cells filling the top `board_height`
+    // rows of `frame`, which is `height` rows tall and drawn into targets of
+    // `target_format`. Build a new one whenever any of those change.
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        frame: &wgpu::Texture,
+        target_format: wgpu::TextureFormat,
+        (rows, cols): (usize, usize),
+        (board_height, height): (usize, usize),
+    ) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("cell_texture"),
+            size: extent(rows, cols),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: frame.format(),
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("cell_sampler"),
+            ..Default::default()
+        });
+        let locals = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("cell_locals"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let board = board_height as f32 / height.max(1) as f32;
+        let mut bytes = [0; 16];
+        bytes[..4].copy_from_slice(&board.to_ne_bytes());
+        queue.write_buffer(&locals, 0, &bytes);
+
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("cell_bind_group_layout"),
+            entries: &[
+                texture_entry(0),
+                texture_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let cell_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let frame_view = frame.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("cell_bind_group"),
+            layout: &layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&cell_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&frame_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: locals.as_entire_binding(),
+                },
+            ],
+        });
+        let module = device.create_shader_module(wgpu::include_wgsl!("cells.wgsl"));
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("cell_pipeline_layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("cell_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+        Self {
+            rows,
+            cols,
+            colors: vec![0; rows * cols * 4],
+            texture,
+            bind_group,
+            pipeline,
+        }
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, color: [u8; 4]) {
+        let idx = (row * self.cols + col) * 4;
+        self.colors[idx..idx + 4].copy_from_slice(&color);
+    }
+
+    // Uploads the cell colors and draws them over the frame, which is
+    // already scaled into `clip_rect` of `target`.
+    pub fn render(
+        &self,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        (x, y, width, height): (u32, u32, u32, u32),
+    ) {
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &self.colors,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(self.cols as u32 * 4),
+                rows_per_image: Some(self.rows as u32),
+            },
+            extent(self.rows, self.cols),
+        );
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("cell_render_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.set_viewport(x as f32, y as f32, width as f32, height as f32, 0.0, 1.0);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+fn extent(rows: usize, cols: usize) -> wgpu::Extent3d {
+    wgpu::Extent3d {
+        width: cols.max(1) as u32,
+        height: rows.max(1) as u32,
+        depth_or_array_layers: 1,
+    }
+}
@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+
+// Which part of the board stays put when it is resized.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    #[default]
+    Center,
+}
+
+impl Anchor {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "topleft" | "top-left" | "corner" => Some(Anchor::TopLeft),
+            "center" | "centre" => Some(Anchor::Center),
+            _ => None,
+        }
+    }
+
+    // Where the old board's top-left cell lands on the new one.
+    pub fn offset(self, from: (usize, usize), to: (usize, usize)) -> (isize, isize) {
+        match self {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Center => (
+                (to.0 as isize - from.0 as isize) / 2,
+                (to.1 as isize - from.1 as isize) / 2,
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Grid {
+    rows: usize,
+    cols: usize,
+    cells: Vec<u8>,
+}
+
+impl Grid {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            cells: vec![0; rows * cols],
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn index(&self, row: usize, col: usize) -> usize {
+        row * self.cols + col
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        self.cells[self.index(row, col)] != 0
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, alive: bool) {
+        let idx = self.index(row, col);
+        self.cells[idx] = alive as u8;
+    }
+
+    pub fn state(&self, row: usize, col: usize) -> u8 {
+        self.cells[self.index(row, col)]
+    }
+
+    pub fn set_state(&mut self, row: usize, col: usize, state: u8) {
+        let idx = self.index(row, col);
+        self.cells[idx] = state;
+    }
+
+    pub fn row(&self, row: usize) -> &[u8] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [u8] {
+        &mut self.cells
+    }
+
+    pub fn live_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, state)| **state != 0)
+            .map(|(idx, _)| (idx / self.cols, idx % self.cols))
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|state| **state != 0).count()
+    }
+
+    // Copies `other` with its top-left corner at (row, col), clipping
+    // whatever falls outside this grid.
+    pub fn paste(&mut self, other: &Grid, row: isize, col: isize) {
+        for r in 0..other.rows {
+            for c in 0..other.cols {
+                let (tr, tc) = (row + r as isize, col + c as isize);
+                if (0..self.rows as isize).contains(&tr) && (0..self.cols as isize).contains(&tc) {
+                    self.set_state(tr as usize, tc as usize, other.state(r, c));
+                }
+            }
+        }
+    }
+
+    // A copy at the new size, keeping the anchored part of its contents.
+    pub fn resized(&self, rows: usize, cols: usize, anchor: Anchor) -> Grid {
+        let (dr, dc) = anchor.offset((self.rows, self.cols), (rows, cols));
+        let mut grid = Grid::new(rows, cols);
+        grid.paste(self, dr, dc);
+        grid
+    }
+
+    // Turned a quarter turn clockwise, so the rows become the columns.
+    pub fn rotated(&self) -> Grid {
+        self.remapped(self.cols, self.rows, |row, col| (self.rows - 1 - col, row))
+    }
+
+    // Mirrored left to right.
+    pub fn flipped_horizontally(&self) -> Grid {
+        self.remapped(self.rows, self.cols, |row, col| (row, self.cols - 1 - col))
+    }
+
+    // Mirrored top to bottom.
+    pub fn flipped_vertically(&self) -> Grid {
+        self.remapped(self.rows, self.cols, |row, col| (self.rows - 1 - row, col))
+    }
+
+    // A `rows` by `cols` grid whose every cell is copied from the cell of
+    // this one that `source` names.
+    fn remapped(&self, rows: usize, cols: usize, source: impl Fn(usize, usize) -> (usize, usize)) -> Grid {
+        let mut grid = Grid::new(rows, cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let (r, c) = source(row, col);
+                grid.set_state(row, col, self.state(r, c));
+            }
+        }
+        grid
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FloatGrid {
+    rows: usize,
+    cols: usize,
+    cells: Vec<f32>,
+}
+
+impl FloatGrid {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            cells: vec![0.0; rows * cols],
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> f32 {
+        self.cells[row * self.cols + col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: f32) {
+        self.cells[row * self.cols + col] = value;
+    }
+
+    pub fn cells(&self) -> &[f32] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [f32] {
+        &mut self.cells
+    }
+
+    pub fn resized(&self, rows: usize, cols: usize, anchor: Anchor) -> FloatGrid {
+        let (dr, dc) = anchor.offset((self.rows, self.cols), (rows, cols));
+        let mut grid = FloatGrid::new(rows, cols);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                let (tr, tc) = (dr + r as isize, dc + c as isize);
+                if (0..rows as isize).contains(&tr) && (0..cols as isize).contains(&tc) {
+                    grid.set(tr as usize, tc as usize, self.get(r, c));
+                }
+            }
+        }
+        grid
+    }
+}
+
+impl From<Vec<Vec<bool>>> for Grid {
+    fn from(rows: Vec<Vec<bool>>) -> Self {
+        let cols = rows.first().map_or(0, Vec::len);
+        let cells = rows
+            .iter()
+            .flat_map(|row| row.iter().map(|&alive| alive as u8))
+            .collect();
+        Self {
+            rows: rows.len(),
+            cols,
+            cells,
+        }
+    }
+}
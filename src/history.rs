@@ -0,0 +1,153 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::Snapshot;
+
+// Boards to go back to, newest last, keeping at most `limit` of them, and
+// the boards undone since the last change, to redo.
+pub struct History {
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>,
+    limit: usize,
+}
+
+impl History {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    // Notes the board as it was before a change, forgetting anything undone
+    // before it.
+    pub fn record(&mut self, snapshot: Snapshot) {
+        if self.limit == 0 {
+            return;
+        }
+        if self.undo.len() == self.limit {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(snapshot);
+        self.redo.clear();
+    }
+
+    // The board before the last change, given the board now.
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    // The board the last undo went back from.
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current);
+        Some(next)
+    }
+}
+
+// The boards of recent generations, newest last, dropping the oldest once
+// they take up more than `budget` bytes between them.
+pub struct Rewind {
+    generations: VecDeque<Snapshot>,
+    bytes: usize,
+    budget: usize,
+}
+
+impl Rewind {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            generations: VecDeque::new(),
+            bytes: 0,
+            budget,
+        }
+    }
+
+    pub fn record(&mut self, snapshot: Snapshot) {
+        self.bytes += snapshot.grid.cells().len();
+        self.generations.push_back(snapshot);
+        while self.bytes > self.budget
+            && let Some(oldest) = self.generations.pop_front()
+        {
+            self.bytes -= oldest.grid.cells().len();
+        }
+    }
+
+    // The generation before the one last recorded.
+    pub fn back(&mut self) -> Option<Snapshot> {
+        let snapshot = self.generations.pop_back()?;
+        self.bytes -= snapshot.grid.cells().len();
+        Some(snapshot)
+    }
+
+    pub fn clear(&mut self) {
+        self.generations.clear();
+        self.bytes = 0;
+    }
+}
+
+// Boards from every `interval` generations of the run, up to the latest
+// generation reached, so any generation in between can be got back to by
+// stepping on from the checkpoint before it. The oldest go once they take up
+// more than `budget` bytes.
+pub struct Timeline {
+    checkpoints: BTreeMap<u64, Snapshot>,
+    interval: u64,
+    latest: u64,
+    bytes: usize,
+    budget: usize,
+}
+
+impl Timeline {
+    pub fn new(interval: u64, budget: usize) -> Self {
+        Self {
+            checkpoints: BTreeMap::new(),
+            interval,
+            latest: 0,
+            bytes: 0,
+            budget,
+        }
+    }
+
+    // Notes a board that's about to be stepped.
+    pub fn record(&mut self, snapshot: &Snapshot) {
+        self.latest = self.latest.max(snapshot.generation + 1);
+        if self.interval == 0
+            || !snapshot.generation.is_multiple_of(self.interval)
+            || self.checkpoints.contains_key(&snapshot.generation)
+        {
+            return;
+        }
+        self.bytes += snapshot.grid.cells().len();
+        self.checkpoints.insert(snapshot.generation, snapshot.clone());
+        while self.bytes > self.budget
+            && let Some((_, oldest)) = self.checkpoints.pop_first()
+        {
+            self.bytes -= oldest.grid.cells().len();
+        }
+    }
+
+    // Forgets what came after `generation`, which has been edited since.
+    pub fn truncate(&mut self, generation: u64) {
+        for (_, later) in self.checkpoints.split_off(&(generation + 1)) {
+            self.bytes -= later.grid.cells().len();
+        }
+        self.latest = generation;
+    }
+
+    pub fn clear(&mut self) {
+        self.checkpoints.clear();
+        self.bytes = 0;
+        self.latest = 0;
+    }
+
+    // The checkpoint to start from for `generation`, or as near to it as
+    // the timeline goes, and how many generations to step on from it.
+    pub fn seek(&self, generation: u64) -> Option<(&Snapshot, u64)> {
+        let generation = generation.min(self.latest);
+        let (_, checkpoint) =
+            self.checkpoints.range(..=generation).next_back().or_else(|| self.checkpoints.first_key_value())?;
+        Some((checkpoint, generation.saturating_sub(checkpoint.generation)))
+    }
+}
@@ -0,0 +1,39 @@
+pub mod automata;
+mod bitlife;
+mod clock;
+pub mod boundary;
+mod config;
+mod error;
+pub mod font;
+pub mod formats;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+pub mod gpu;
+mod grid;
+mod history;
+mod life;
+pub mod neighborhood;
+pub mod paint;
+pub mod palette;
+pub mod patterns;
+pub mod random;
+pub mod render;
+mod rule;
+mod simulator;
+mod sparse;
+mod stochastic;
+pub mod view;
+mod worker;
+
+pub use bitlife::BitLife;
+pub use clock::Clock;
+pub use config::{Automaton, Config};
+pub use error::Error;
+pub use grid::{Anchor, FloatGrid, Grid};
+pub use history::{History, Rewind, Timeline};
+pub use life::{CellBehavior, GameOfLife, Snapshot, StandardCell};
+pub use rule::{Rule, RuleNeighborhood};
+pub use simulator::{Lattice, Memory, Parameter, Population, Shade, Simulator, Step};
+pub use sparse::SparseLife;
+pub use stochastic::StochasticRule;
+pub use worker::{Report, Worker};
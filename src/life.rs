@@ -0,0 +1,448 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::boundary::{BoundaryCondition, Edge, Resolved};
+use crate::neighborhood::{Moore, Neighborhood};
+use crate::{Anchor, Grid, Lattice, Memory, Shade, Simulator};
+
+pub(crate) const DEAD: u8 = 0;
+pub(crate) const ALIVE: u8 = 1;
+
+pub trait CellBehavior: Send + Sync {
+    fn next_state(&self, state: u8, alive_neighbors: u8) -> u8;
+
+    fn states(&self) -> u8 {
+        2
+    }
+
+    // Whether cells may be updated concurrently and in any order. Rules
+    // drawing on a single random stream need the same order every run.
+    fn parallel(&self) -> bool {
+        true
+    }
+}
+
+pub struct StandardCell;
+
+impl CellBehavior for StandardCell {
+    fn next_state(&self, state: u8, alive_neighbors: u8) -> u8 {
+        match (state, alive_neighbors) {
+            (ALIVE, 2 | 3) | (DEAD, 3) => ALIVE,
+            _ => DEAD,
+        }
+    }
+}
+
+// Notes every cell of `new` that differs from `old`, or all of them if the
+// size changed.
+fn record_changes(old: &Grid, new: &Grid, changed: &mut Vec<(usize, usize)>) {
+    let cols = new.cols().max(1);
+    let resized = (old.rows(), old.cols()) != (new.rows(), new.cols());
+    let differs = new.cells().iter().enumerate().map(|(i, state)| resized || old.cells()[i] != *state);
+    changed.extend(differs.enumerate().filter(|&(_, differs)| differs).map(|(i, _)| (i / cols, i % cols)));
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub generation: u64,
+    pub grid: Grid,
+}
+
+pub struct GameOfLife {
+    grid: Grid,
+    generation: u64,
+    cell: Box<dyn CellBehavior>,
+    neighborhood: Box<dyn Neighborhood>,
+    boundary: BoundaryCondition,
+    palette: Option<Vec<[u8; 4]>>,
+    parallel: bool,
+    // Cells set since the last step began, including by that step.
+    changed: Vec<(usize, usize)>,
+    // The previous generation, kept to be overwritten by the next one.
+    scratch: Grid,
+    active_cells: bool,
+    // Set when the next step can't rely on `changed`, such as after the
+    // neighborhood or boundary changes.
+    stale: bool,
+    marks: Vec<bool>,
+    neighbor_counts: bool,
+    // Live neighbors of every cell, kept up to date as cells are born and
+    // die while `counted` is set.
+    counts: Vec<u8>,
+    counted: bool,
+}
+
+impl GameOfLife {
+    pub fn new(initial: Grid) -> Self {
+        Self::with_behavior(initial, Box::new(StandardCell))
+    }
+
+    pub fn with_behavior(initial: Grid, cell: Box<dyn CellBehavior>) -> Self {
+        Self {
+            grid: initial,
+            generation: 0,
+            cell,
+            neighborhood: Box::new(Moore::new()),
+            boundary: BoundaryCondition::default(),
+            palette: None,
+            parallel: true,
+            changed: Vec::new(),
+            scratch: Grid::new(0, 0),
+            active_cells: true,
+            stale: true,
+            marks: Vec::new(),
+            neighbor_counts: true,
+            counts: Vec::new(),
+            counted: false,
+        }
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: Box<dyn Neighborhood>) -> Self {
+        self.neighborhood = neighborhood;
+        self.stale = true;
+        self.counted = false;
+        self
+    }
+
+    pub fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.set_boundary(boundary);
+        self
+    }
+
+    pub fn with_palette(mut self, palette: Vec<[u8; 4]>) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+
+    // After the first step, only revisits cells next to the last step's
+    // changes: a cell whose whole neighborhood stayed put can't change
+    // either. Falls back to visiting every cell when most of the board is
+    // changing, for random rules, and on boundaries where cells don't read
+    // each other both ways.
+    pub fn with_active_cells(mut self, active_cells: bool) -> Self {
+        self.active_cells = active_cells;
+        self
+    }
+
+    // Keeps every cell's live-neighbor count between steps, adjusting the
+    // counts around each birth and death instead of recounting the whole
+    // neighborhood of every cell it visits. Only used on the same
+    // boundaries as active cells.
+    pub fn with_neighbor_counts(mut self, neighbor_counts: bool) -> Self {
+        self.neighbor_counts = neighbor_counts;
+        self.counted = false;
+        self
+    }
+
+    // Steps rows on rayon's thread pool unless turned off or the cell
+    // behavior needs a fixed order.
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    pub fn boundary(&self) -> BoundaryCondition {
+        self.boundary
+    }
+
+    pub fn set_boundary(&mut self, boundary: BoundaryCondition) {
+        self.boundary = boundary;
+        self.stale = true;
+        self.counted = false;
+    }
+
+    pub fn rows(&self) -> usize {
+        self.grid.rows()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.grid.cols()
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            generation: self.generation,
+            grid: self.grid.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        let old = std::mem::replace(&mut self.grid, snapshot.grid.clone());
+        self.generation = snapshot.generation;
+        record_changes(&old, &self.grid, &mut self.changed);
+        self.counted = false;
+    }
+
+    pub fn states(&self) -> u8 {
+        self.cell.states()
+    }
+
+    pub fn state(&self, row: usize, col: usize) -> u8 {
+        self.grid.state(row, col)
+    }
+
+    pub fn set_state(&mut self, row: usize, col: usize, state: u8) {
+        let was_alive = self.is_alive(row, col);
+        self.grid.set_state(row, col, state);
+        self.changed.push((row, col));
+        self.adjust_counts(row, col, was_alive);
+    }
+
+    pub fn is_alive(&self, row: usize, col: usize) -> bool {
+        self.grid.state(row, col) == ALIVE
+    }
+
+    pub fn set_alive(&mut self, row: usize, col: usize, alive: bool) {
+        let was_alive = self.is_alive(row, col);
+        self.grid.set(row, col, alive);
+        self.changed.push((row, col));
+        self.adjust_counts(row, col, was_alive);
+    }
+
+    pub fn live_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.grid.live_cells().filter(|&(r, c)| self.is_alive(r, c))
+    }
+
+    pub fn population(&self) -> usize {
+        self.grid.cells().iter().filter(|&&state| state == ALIVE).count()
+    }
+
+    // Whether every cell a cell reads reads it back, once per time it's
+    // read, which both the active-cell step and `counts` rely on. Wrapping
+    // only one of a pair of edges breaks that, and so do twisted
+    // topologies, reflecting lattices that aren't mirror-symmetric, and
+    // wrapping triangles across an odd length.
+    fn symmetric(&self) -> bool {
+        let Some(edges) = self.boundary.edges() else {
+            return false;
+        };
+        let paired = |a: Edge, b: Edge| (a == Edge::Wrap) == (b == Edge::Wrap);
+        let mirrored = [edges.top, edges.bottom, edges.left, edges.right].contains(&Edge::Mirror);
+        let odd_wrap = (edges.top == Edge::Wrap && !self.rows().is_multiple_of(2))
+            || (edges.left == Edge::Wrap && !self.cols().is_multiple_of(2));
+        let lattice = self.neighborhood.lattice();
+        paired(edges.top, edges.bottom)
+            && paired(edges.left, edges.right)
+            && (lattice == Lattice::Square || !mirrored)
+            && (lattice != Lattice::Triangular || !odd_wrap)
+    }
+
+    // Counts every cell's live neighbors from scratch.
+    fn count_all(&mut self) {
+        let cols = self.cols().max(1);
+        let mut counts = std::mem::take(&mut self.counts);
+        counts.resize(self.rows() * self.cols(), 0);
+        let count = |(r, row): (usize, &mut [u8])| {
+            for (c, count) in row.iter_mut().enumerate() {
+                *count = self.alive_neighbors(r, c);
+            }
+        };
+        if self.parallel {
+            counts.par_chunks_mut(cols).enumerate().for_each(count);
+        } else {
+            counts.chunks_mut(cols).enumerate().for_each(count);
+        }
+        self.counts = counts;
+        self.counted = true;
+    }
+
+    // Tells the neighbors of (row, col) it was born or died, if it did.
+    fn adjust_counts(&mut self, row: usize, col: usize, was_alive: bool) {
+        let alive = self.is_alive(row, col);
+        if !self.counted || alive == was_alive {
+            return;
+        }
+        let (rows, cols) = (self.rows(), self.cols());
+        for &(dr, dc) in self.neighborhood.offsets_at(row, col) {
+            if let Resolved::Cell(r, c) = self.boundary.resolve(row as isize + dr, col as isize + dc, rows, cols) {
+                let count = &mut self.counts[r * cols + c];
+                *count = if alive { *count + 1 } else { *count - 1 };
+            }
+        }
+    }
+
+    // Live neighbors of (row, col), from `counts` when they're kept.
+    fn neighbors(&self, row: usize, col: usize) -> u8 {
+        if self.counted { self.counts[row * self.cols() + col] } else { self.alive_neighbors(row, col) }
+    }
+
+    // Recomputes just the changed cells and everything whose neighborhood
+    // holds one. Relies on neighborhoods being symmetric, so the cells that
+    // read a cell are the ones it reads.
+    fn step_active(&mut self) {
+        let (rows, cols) = (self.rows(), self.cols());
+        let mut marks = std::mem::take(&mut self.marks);
+        marks.resize(rows * cols, false);
+        let mut candidates = Vec::with_capacity(self.changed.len() * 9);
+        for &(row, col) in &self.changed {
+            let around = self.neighborhood.offsets_at(row, col).iter();
+            for &(dr, dc) in std::iter::once(&(0, 0)).chain(around) {
+                if let Resolved::Cell(r, c) = self.boundary.resolve(row as isize + dr, col as isize + dc, rows, cols)
+                    && !marks[r * cols + c]
+                {
+                    marks[r * cols + c] = true;
+                    candidates.push((r, c));
+                }
+            }
+        }
+        let updates: Vec<(usize, usize, u8)> = candidates
+            .iter()
+            .filter_map(|&(r, c)| {
+                let state = self.grid.state(r, c);
+                let next = self.cell.next_state(state, self.neighbors(r, c));
+                (next != state).then_some((r, c, next))
+            })
+            .collect();
+        for &(r, c) in &candidates {
+            marks[r * cols + c] = false;
+        }
+        self.marks = marks;
+        self.changed.clear();
+        for (r, c, state) in updates {
+            let was_alive = self.is_alive(r, c);
+            self.grid.set_state(r, c, state);
+            self.changed.push((r, c));
+            self.adjust_counts(r, c, was_alive);
+        }
+    }
+
+    pub fn alive_neighbors(&self, row: usize, col: usize) -> u8 {
+        let (rows, cols) = (self.rows(), self.cols());
+        let mut count = 0;
+        for (dr, dc) in self.neighborhood.offsets_at(row, col) {
+            let alive = match self.boundary.resolve(row as isize + dr, col as isize + dc, rows, cols) {
+                Resolved::Cell(r, c) => self.is_alive(r, c),
+                Resolved::Fixed(alive) => alive,
+            };
+            if alive {
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+impl Simulator for GameOfLife {
+    fn step(&mut self) {
+        if self.neighbor_counts && !self.counted && self.symmetric() {
+            self.count_all();
+        }
+        let busy = self.changed.len() > self.rows() * self.cols() / 16;
+        if self.active_cells && !self.stale && !busy && self.symmetric() && self.cell.parallel() {
+            self.step_active();
+            self.generation += 1;
+            return;
+        }
+        self.stale = false;
+        let mut next = std::mem::replace(&mut self.scratch, Grid::new(0, 0));
+        if (next.rows(), next.cols()) != (self.rows(), self.cols()) {
+            next = Grid::new(self.rows(), self.cols());
+        }
+        let update = |(r, row): (usize, &mut [u8])| {
+            for (c, state) in row.iter_mut().enumerate() {
+                *state = self.cell.next_state(self.grid.state(r, c), self.neighbors(r, c));
+            }
+        };
+        let cols = self.cols().max(1);
+        if self.parallel && self.cell.parallel() {
+            next.cells_mut().par_chunks_mut(cols).enumerate().for_each(update);
+        } else {
+            next.cells_mut().chunks_mut(cols).enumerate().for_each(update);
+        }
+        self.scratch = std::mem::replace(&mut self.grid, next);
+        self.changed.clear();
+        record_changes(&self.scratch, &self.grid, &mut self.changed);
+        for i in 0..self.changed.len() {
+            let (r, c) = self.changed[i];
+            self.adjust_counts(r, c, self.scratch.state(r, c) == ALIVE);
+        }
+        self.generation += 1;
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.rows(), self.cols())
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn population(&self) -> usize {
+        GameOfLife::population(self)
+    }
+
+    fn changes(&self) -> Option<&[(usize, usize)]> {
+        Some(&self.changed)
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        let resized = self.grid.resized(rows, cols, anchor);
+        let old = std::mem::replace(&mut self.grid, resized);
+        self.changed.clear();
+        record_changes(&old, &self.grid, &mut self.changed);
+        self.stale = true;
+        self.counted = false;
+    }
+
+    fn boundary(&self) -> Option<BoundaryCondition> {
+        Some(self.boundary)
+    }
+
+    fn set_boundary(&mut self, boundary: BoundaryCondition) {
+        GameOfLife::set_boundary(self, boundary);
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.is_alive(row, col)
+    }
+
+    fn lattice(&self) -> Lattice {
+        self.neighborhood.lattice()
+    }
+
+    fn neighbors(&self, row: usize, col: usize) -> Option<u8> {
+        Some(self.alive_neighbors(row, col))
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        let state = self.state(row, col);
+        if let Some(color) = self.palette.as_ref().and_then(|palette| palette.get(state as usize)) {
+            return Shade::Rgba(*color);
+        }
+        match state {
+            DEAD => Shade::Dead,
+            ALIVE => Shade::Alive,
+            dying => Shade::Dying((dying - 1) as f32 / (self.states() - 1) as f32),
+        }
+    }
+
+    fn set_cell(&mut self, row: usize, col: usize, alive: bool) {
+        self.set_alive(row, col, alive);
+    }
+
+    fn save(&self) -> Option<Snapshot> {
+        Some(self.snapshot())
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        self.restore(snapshot);
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![
+            Memory::of("grid", self.grid.cells()),
+            Memory::of("scratch", self.scratch.cells()),
+            Memory::of("counts", &self.counts),
+            Memory::of("marks", &self.marks),
+            Memory::of("changes", &self.changed),
+        ]
+    }
+}
@@ -1,158 +1,1199 @@
-use pixels::{Error, Pixels, SurfaceTexture};
-use winit::dpi::LogicalSize;
-use winit::event::{Event, VirtualKeyCode, WindowEvent, KeyboardInput, ElementState};
-use winit::event_loop::{ControlFlow, EventLoop};
-use winit::window::WindowBuilder;
+use pixels::{Pixels, SurfaceTexture};
+use simple_program_1::automata::ant::{Ant, Direction};
+use simple_program_1::automata::brians_brain::brians_brain;
+use simple_program_1::automata::colored::ColoredLife;
+use simple_program_1::automata::cyclic::Cyclic;
+use simple_program_1::automata::elementary::Elementary;
+use simple_program_1::automata::forest_fire::ForestFire;
+use simple_program_1::automata::golly::RuleFileAutomaton;
+use simple_program_1::automata::isotropic::IsotropicLife;
+use simple_program_1::automata::lenia::Lenia;
+use simple_program_1::automata::life3d::Life3D;
+use simple_program_1::automata::ltl::LargerThanLife;
+use simple_program_1::automata::margolus::{BlockRule, Margolus};
+use simple_program_1::automata::sandpile::Sandpile;
+use simple_program_1::automata::smoothlife::{SmoothLife, SmoothLifeParams};
+use simple_program_1::automata::turmite::{TurmiteTable, Turmites};
+use simple_program_1::automata::wator::{WaTor, WaTorParams};
+use simple_program_1::automata::wireworld::Wireworld;
+use simple_program_1::boundary::BoundaryCondition;
+use simple_program_1::formats;
+#[cfg(feature = "gamepad")]
+use simple_program_1::gamepad::{Action, Gamepad};
+use simple_program_1::paint::{self, Symmetry, Tool};
+use simple_program_1::palette::Palette;
+use simple_program_1::patterns;
+use simple_program_1::random::Rng;
+use simple_program_1::render::{self, Heatmap, PixelsRenderer, PopulationGraph, Renderer};
+use simple_program_1::view::Viewport;
+use simple_program_1::{
+    Anchor, Automaton, BitLife, Clock, Config, Error, GameOfLife, Grid, History, Parameter, Report, Rewind,
+    Simulator, Snapshot, SparseLife, Timeline, Worker,
+};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use winit::dpi::{LogicalSize, PhysicalSize};
+use winit::event::{
+    ElementState, Event, KeyboardInput, ModifiersState, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent,
+};
+use winit::event_loop::{ControlFlow, EventLoopBuilder};
+use winit::window::WindowBuilder;
 
-trait CellBehavior {
-    fn next_state(&self, alive_neighbors: u8) -> bool;
-}
+const GRAPH_HEIGHT: usize = 96;
+// What the board starts with when --pattern doesn't say.
+const INITIAL: &str = "x = 19, y = 19
+$2bo$2bo4b2o$2bo5bo$7bo$7b2o6b2o$7bo7b2o$5bobo5bo2bo$5b3o6b2o$13bobo$11bob2o$11b4o$14bo$11bo2bo$11b4o!";
+// Most logical pixels the zoom draws a cell across.
+const MAX_ZOOM: usize = 64;
+// How often gamepads are checked on while nothing else wakes the loop.
+#[cfg(feature = "gamepad")]
+const GAMEPAD_POLL: Duration = Duration::from_millis(16);
 
-struct StandardCell;
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
 
-impl CellBehavior for StandardCell {
-    fn next_state(&self, alive_neighbors: u8) -> bool {
-        match alive_neighbors {
-            2 => true,
-            3 => true,
-            _ => false,
+fn build_simulator(config: &Config, initial: Grid) -> Box<dyn Simulator> {
+    let neighborhood = || config.neighborhood().build().expect("from_args checks the neighborhood");
+    match config.automaton {
+        Automaton::Life => match (&config.stochastic, &config.isotropic) {
+            (Some(rule), _) => Box::new(GameOfLife::with_behavior(initial, Box::new(rule.clone().with_seed(config.seed)))),
+            (None, Some(rule)) => Box::new(IsotropicLife::new(initial, rule.clone())),
+            (None, None) => {
+                Box::new(GameOfLife::with_behavior(initial, Box::new(config.rule)).with_neighborhood(neighborhood()))
+            }
+        },
+        // Without a pattern of its own, Wireworld starts from a sample circuit.
+        Automaton::Wireworld if config.pattern.is_none() => Box::new(Wireworld::sample(initial.rows(), initial.cols())),
+        Automaton::Wireworld => Box::new(Wireworld::new(initial)),
+        Automaton::BriansBrain => Box::new(brians_brain(initial)),
+        Automaton::Turmite => {
+            let (rows, cols) = (initial.rows(), initial.cols());
+            let ants = if config.ants.is_empty() {
+                vec![Ant::new(rows / 2, cols / 2, Direction::North)]
+            } else {
+                config.ants.clone()
+            };
+            let table = config.turmite.clone().unwrap_or_else(TurmiteTable::langtons_ant);
+            Box::new(Turmites::new(Grid::new(rows, cols), table, ants))
+        }
+        Automaton::Elementary => Box::new(Elementary::single_seed(config.wolfram, initial.rows(), initial.cols())),
+        Automaton::Cyclic => Box::new(Cyclic::random(
+            initial.rows(),
+            initial.cols(),
+            config.states,
+            config.threshold,
+            config.seed,
+        )),
+        Automaton::Lenia => {
+            let lenia = Lenia::random(initial.rows(), initial.cols(), config.lenia.clone(), config.seed);
+            Box::new(match config.colormap {
+                Some(colormap) => lenia.with_colormap(colormap),
+                None => lenia,
+            })
+        }
+        Automaton::SmoothLife => {
+            let smooth = SmoothLife::random(initial.rows(), initial.cols(), SmoothLifeParams::default(), config.seed);
+            Box::new(match config.colormap {
+                Some(colormap) => smooth.with_colormap(colormap),
+                None => smooth,
+            })
+        }
+        Automaton::Sandpile => Box::new(Sandpile::pile(initial.rows(), initial.cols(), config.grains)),
+        Automaton::ForestFire => Box::new(ForestFire::empty(
+            initial.rows(),
+            initial.cols(),
+            config.growth,
+            config.lightning,
+            config.seed,
+        )),
+        Automaton::WaTor => Box::new(WaTor::random(
+            initial.rows(),
+            initial.cols(),
+            WaTorParams::default(),
+            0.3,
+            0.05,
+            config.seed,
+        )),
+        Automaton::Immigration => Box::new(ColoredLife::new(initial, 2, config.rule).paint(config.seed)),
+        Automaton::QuadLife => Box::new(ColoredLife::new(initial, 4, config.rule).paint(config.seed)),
+        Automaton::Critters => Box::new(Margolus::random(
+            initial.rows(),
+            initial.cols(),
+            BlockRule::critters(),
+            0.5,
+            config.seed,
+        )),
+        Automaton::BilliardBall => Box::new(Margolus::random(
+            initial.rows(),
+            initial.cols(),
+            BlockRule::billiard_ball(),
+            0.1,
+            config.seed,
+        )),
+        Automaton::Sparse => {
+            Box::new(SparseLife::from_grid(&initial, config.rule).with_neighborhood(neighborhood()))
+        }
+        Automaton::BitPacked => Box::new(BitLife::from_grid(&initial, config.rule)),
+        Automaton::LargerThanLife => {
+            let rule = config.ltl.clone().expect("from_args requires an R.. --rule");
+            Box::new(LargerThanLife::new(initial, rule))
+        }
+        // The volume is as deep as the board is tall.
+        Automaton::Life3D => Box::new(Life3D::random(
+            initial.rows(),
+            initial.rows(),
+            initial.cols(),
+            config.rule3d,
+            0.3,
+            config.seed,
+        )),
+        Automaton::RuleFile => {
+            let rule = config.rule_file.clone().expect("from_args requires --rule-file");
+            Box::new(RuleFileAutomaton::new(initial, rule))
         }
     }
 }
 
-struct GameOfLife {
-    grid: Vec<Vec<bool>>,
-    cell: Box<dyn CellBehavior>,
+// A simulator on `board` set up as the config asks, as at startup.
+fn start(config: &Config, board: &Grid) -> Box<dyn Simulator> {
+    let mut game = build_simulator(config, board.clone());
+    if let Some(boundary) = config.boundary {
+        game.set_boundary(boundary);
+    }
+    game
+}
+
+// What the window title shows besides the parameters.
+struct Status {
+    // The tunable parameter Tab has selected.
+    selected: usize,
+    // Generations run per tick.
+    warp: usize,
+    paused: bool,
+    // Edit mode keeps the board paused and takes clicks as edits, which run
+    // mode ignores.
+    editing: bool,
+    // The library pattern being stamped, which stays picked up after each
+    // click, unlike a paste.
+    stamp: Option<usize>,
+    tool: Tool,
+    symmetry: Symmetry,
+    heatmap: Option<Heatmap>,
+    // The board cell under the cursor and what's in it.
+    hover: Option<((usize, usize), String)>,
 }
 
-impl GameOfLife {
-    fn new(initial: Vec<Vec<bool>>) -> Self {
+impl Status {
+    fn new(warp: usize) -> Self {
         Self {
-            grid: initial,
-            cell: Box::new(StandardCell),
+            selected: 0,
+            warp,
+            paused: false,
+            editing: false,
+            stamp: None,
+            tool: Tool::default(),
+            symmetry: Symmetry::default(),
+            heatmap: None,
+            hover: None,
         }
     }
 
-    fn step(&mut self) {
-        let rows = self.grid.len();
-        let cols = self.grid[0].len();
-        let mut next = vec![vec![false; cols]; rows];
-
-        for r in 0..rows {
-            for c in 0..cols {
-                let alive = self.grid[r][c];
-                let neighbors = self.alive_neighbors(r, c);
-                next[r][c] = if alive {
-                    self.cell.next_state(neighbors)
-                } else {
-                    neighbors == 3
-                };
+    // Entering edit mode pauses the board, and leaving it runs it again.
+    fn set_editing(&mut self, editing: bool) {
+        self.editing = editing;
+        self.paused = editing;
+    }
+
+    // Shows the parameter selected, if any, the warp factor once it's past
+    // 1, the mode and whether the simulation is paused, and how a click
+    // edits the board and any heatmap, then the cell under the cursor.
+    fn title(&self, parameters: &[Parameter]) -> String {
+        let mut title = "Game of Life".to_string();
+        if let Some(parameter) = parameters.get(self.selected) {
+            title += &format!(" - {} = {}", parameter.name, parameter.value);
+        }
+        if self.warp > 1 {
+            title += &format!(" - x{}", self.warp);
+        }
+        if self.editing {
+            title += " - edit mode";
+        } else {
+            title += " - run mode";
+            if self.paused {
+                title += " - paused";
             }
         }
-        self.grid = next;
+        if let Some((name, _)) = self.stamp.and_then(|stamp| patterns::LIBRARY.get(stamp)) {
+            title += &format!(" - stamp {name}");
+        }
+        if self.tool != Tool::Brush {
+            title += &format!(" - {}", self.tool.name());
+        }
+        if self.symmetry != Symmetry::None {
+            title += &format!(" - {}", self.symmetry.name());
+        }
+        if let Some(heatmap) = self.heatmap {
+            title += &format!(" - {} heatmap", heatmap.name());
+        }
+        if let Some(((row, col), state)) = &self.hover {
+            title += &format!(" - ({row}, {col}) {state}");
+        }
+        title
     }
+}
 
-    fn alive_neighbors(&self, row: usize, col: usize) -> u8 {
-        let dirs = [
-            (-1, -1), (-1, 0), (-1, 1),
-            (0, -1),          (0, 1),
-            (1, -1), (1, 0), (1, 1),
-        ];
-        let rows = self.grid.len() as isize;
-        let cols = self.grid[0].len() as isize;
-        let mut count = 0;
-        for (dr, dc) in dirs.iter() {
-            let nr = row as isize + dr;
-            let nc = col as isize + dc;
-            if nr >= 0 && nr < rows && nc >= 0 && nc < cols {
-                if self.grid[nr as usize][nc as usize] {
-                    count += 1;
-                }
-            }
-        }
-        count
-    }
+// What picking an entry in the palette does: press a command's keys, or go
+// back to a bookmark.
+#[derive(Clone, Copy)]
+enum Pick {
+    Keys(VirtualKeyCode, ModifiersState),
+    Bookmark(usize),
+}
 
-    fn draw(&self, frame: &mut [u8], cell_size: usize) {
-        let rows = self.grid.len();
-        let cols = self.grid[0].len();
-        let width = cols * cell_size;
-        let height = rows * cell_size;
-        for y in 0..height {
-            for x in 0..width {
-                let cell_x = x / cell_size;
-                let cell_y = y / cell_size;
-                let idx = (y * width + x) * 4;
-                let alive = self.grid[cell_y][cell_x];
-                let color = if alive { [0, 0, 0, 255] } else { [255, 255, 255, 255] };
-                frame[idx..idx + 4].copy_from_slice(&color);
-            }
-        }
+// Everything the command palette lists, with the keys it presses for each.
+const COMMANDS: [(&str, (VirtualKeyCode, ModifiersState)); 45] = {
+    use VirtualKeyCode::*;
+    const NONE: ModifiersState = ModifiersState::empty();
+    const CTRL: ModifiersState = ModifiersState::CTRL;
+    [
+        ("pause or resume", (Space, NONE)),
+        ("toggle edit mode", (E, NONE)),
+        ("step one generation", (N, NONE)),
+        ("step back a generation", (B, NONE)),
+        ("scrub back", (Comma, NONE)),
+        ("scrub forward", (Period, NONE)),
+        ("jump ahead", (G, NONE)),
+        ("speed up", (RBracket, NONE)),
+        ("slow down", (LBracket, NONE)),
+        ("reset board", (R, NONE)),
+        ("clear board", (C, NONE)),
+        ("random fill", (F, NONE)),
+        ("poke the middle cell", (D, NONE)),
+        ("toggle wrap", (W, NONE)),
+        ("next tool", (T, NONE)),
+        ("next symmetry", (M, NONE)),
+        ("stamp next library pattern", (P, NONE)),
+        ("copy selection", (C, CTRL)),
+        ("paste", (V, CTRL)),
+        ("undo", (Z, CTRL)),
+        ("redo", (Y, CTRL)),
+        ("next parameter", (Tab, NONE)),
+        ("raise parameter", (PageUp, NONE)),
+        ("lower parameter", (PageDown, NONE)),
+        ("grow board down", (Down, CTRL)),
+        ("shrink board up", (Up, CTRL)),
+        ("grow board right", (Right, CTRL)),
+        ("shrink board left", (Left, CTRL)),
+        ("toggle grid lines", (H, NONE)),
+        ("toggle age colors", (A, NONE)),
+        ("toggle trails", (J, NONE)),
+        ("toggle timing overlay", (F3, NONE)),
+        ("toggle info overlay", (I, NONE)),
+        ("toggle population chart", (O, NONE)),
+        ("next heatmap", (S, NONE)),
+        ("toggle births and deaths", (U, NONE)),
+        ("zoom in", (Equals, NONE)),
+        ("zoom out", (Minus, NONE)),
+        ("fit pattern to view", (Home, NONE)),
+        ("toggle split view", (V, NONE)),
+        ("bookmark generation", (K, NONE)),
+        ("list bookmarks", (L, NONE)),
+        ("export pattern", (S, CTRL)),
+        ("save to slot 1", (Key1, CTRL)),
+        ("load slot 1", (Key1, NONE)),
+    ]
+};
+
+// Logical pixels as physical ones at `scale`, never less than one.
+fn scaled(size: usize, scale: f64) -> usize {
+    ((size as f64 * scale).round() as usize).max(1)
+}
+
+// The quick-save slot a number key picks, counting from 0 for 1.
+fn slot(key: VirtualKeyCode) -> Option<usize> {
+    use VirtualKeyCode::*;
+    [Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9].iter().position(|&slot| slot == key)
+}
+
+// Peak resident memory in bytes, where the platform reports it.
+fn peak_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find_map(|line| line.strip_prefix("VmHWM:"))?;
+    let kib: u64 = line.trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+// Runs `generations` steps as fast as they go and reports the rate.
+fn bench(game: &mut dyn Simulator, generations: u64) {
+    let started = Instant::now();
+    for _ in 0..generations {
+        game.step();
+    }
+    let elapsed = started.elapsed().as_secs_f64();
+    let (rows, cols) = game.dimensions();
+    println!(
+        "{generations} generations of {rows}x{cols} in {elapsed:.3} s: {:.1} generations/sec",
+        generations as f64 / elapsed.max(f64::MIN_POSITIVE)
+    );
+    match peak_memory() {
+        Some(bytes) => println!("peak memory: {:.1} MiB", render::mebibytes(bytes as usize)),
+        None => println!("peak memory: unknown"),
+    }
+    for part in game.memory_footprint() {
+        println!("  {}: {} bytes", part.name, part.bytes);
     }
 }
 
-fn main() -> Result<(), Error> {
-    let initial = vec![
-        vec![false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false],
-        vec![false, false, true,  false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false],
-        vec![false, false, true,  false, false, false, false, true, true, false, false, false, false, false, false, false, false, false, false],
-        vec![false, false, true,  false, false, false, false, false, true, false, false, false, false, false, false, false, false, false, false],
-        vec![false, false, false, false, false, false, false, true, false, false, false, false, false, false, false, false, false, false, false],
-        vec![false, false, false, false, false, false, false, true, true, false, false, false, false, false, false, true, true, false, false],
-        vec![false, false, false, false, false, false, false, true, false, false, false, false, false, false, false, true, true, false, false],
-        vec![false, false, false, false, false, true, false, true, false, false, false, false, false, true, false, false, true, false, false],
-        vec![false, false, false, false, false, true, true, true, false, false, false, false, false, false, true, true, false, false, false],
-        vec![false, false, false, false, false, false, false, false, false, false, false, false, false, true, false, true, false, false, false],
-        vec![false, false, false, false, false, false, false, false, false, false, false, true, false, true, true, false, false, false, false],
-        vec![false, false, false, false, false, false, false, false, false, false, false, true, true, true, true, false, false, false, false],
-        vec![false, false, false, false, false, false, false, false, false, false, false, false, false, false, true, false, false, false, false],
-        vec![false, false, false, false, false, false, false, false, false, false, false, true, false, false, true, false, false, false, false],
-        vec![false, false, false, false, false, false, false, false, false, false, false, true, true, true, true, false, false, false, false],
-        vec![false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false],
-        vec![false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false],
-        vec![false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false],
-        vec![false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false, false],
-    ];
-    let mut game = GameOfLife::new(initial);
-    let cell_size = 19; // smaller cell size for larger boards
-    let rows = game.grid.len();
-    let cols = game.grid[0].len();
-    let width = cols * cell_size;
-    let height = rows * cell_size;
-
-    let event_loop = EventLoop::new();
+fn run() -> Result<(), Error> {
+    let config = Config::from_args(std::env::args().skip(1))?;
+    if config.threads > 0 {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(config.threads)
+            .build_global()
+            .map_err(|err| Error::Config(format!("--threads: {err}")))?;
+    }
+    let pattern = match &config.pattern {
+        Some(pattern) => pattern.clone(),
+        None => formats::parse_rle(INITIAL)?,
+    };
+    let (rows, cols) = config.board_size((pattern.rows(), pattern.cols()));
+    let mut board = Grid::new(rows, cols);
+    board.paste(
+        &pattern,
+        (rows as isize - pattern.rows() as isize) / 2,
+        (cols as isize - pattern.cols() as isize) / 2,
+    );
+    let mut game = start(&config, &board);
+    if let Some(generations) = config.bench {
+        bench(game.as_mut(), generations);
+        return Ok(());
+    }
+    let cell_size = config.cell_size(rows, cols);
+    let viewport = Viewport::new(config.view(cell_size));
+    let (width, board_height) = viewport.frame_size(game.as_ref(), cell_size);
+    let graph_height = if game.populations().is_empty() { 0 } else { GRAPH_HEIGHT };
+    let height = board_height + graph_height;
+
+    let event_loop = EventLoopBuilder::with_user_event().build();
+    let mut parameters = game.parameters();
     let window = WindowBuilder::new()
-        .with_title("Game of Life")
+        .with_title(Status::new(config.warp).title(&parameters))
         .with_inner_size(LogicalSize::new(width as f64, height as f64))
-        .build(&event_loop)
-        .unwrap();
-    let mut pixels = Pixels::new(width as u32, height as u32, SurfaceTexture::new(width as u32, height as u32, &window))?;
+        .build(&event_loop)?;
+    // The frame has a pixel for every physical one, with cells and graph
+    // scaled up to match, so they're neither blurred nor shrunk on HiDPI
+    // displays.
+    let scale = window.scale_factor();
+    let (width, board_height) = viewport.frame_size(game.as_ref(), scaled(cell_size, scale));
+    let graph_height = if graph_height > 0 { scaled(graph_height, scale) } else { 0 };
+    let height = board_height + graph_height;
+    window.set_inner_size(PhysicalSize::new(width as u32, height as u32));
+    let pixels = Pixels::new(width as u32, height as u32, SurfaceTexture::new(width as u32, height as u32, &window))?;
+    let mut renderer = PixelsRenderer::new(pixels, scaled(cell_size, scale))
+        .with_gpu(config.gpu)
+        .with_viewport(viewport)
+        .with_theme(config.theme)
+        .with_grid_lines(config.grid_lines)
+        .with_trails(config.trails)
+        .with_glow(config.glow);
+    if graph_height > 0 {
+        renderer = renderer.with_graph(PopulationGraph::new(width, graph_height));
+        renderer.record(game.as_ref());
+    }
 
-    let mut last_update = Instant::now();
+    // Steps run on the worker so a slow one never holds up the window; it
+    // wakes the event loop with a report as each generation or edit lands.
+    let mut shown = game.dimensions();
+    let proxy = event_loop.create_proxy();
+    let history = History::new(config.history);
+    let rewind = Rewind::new(config.rewind_mib << 20);
+    let timeline = Timeline::new(config.checkpoint_every, config.timeline_mib << 20);
+    let worker = Worker::spawn(game, history, rewind, timeline, move |report| {
+        let _ = proxy.send_event(report);
+    });
+    // Generations asked of the worker and not yet reported.
+    let mut pending = 0;
+    let mut clock = Clock::new(Duration::from_millis(config.tick_ms));
+    let mut cursor = None;
+    // The window's size in frame pixels since it was last resized, until the
+    // board's been fitted to it.
+    let mut fitting: Option<(usize, usize)> = None;
+    // Logical pixels across a cell, which the zoom keys and wheel double and
+    // halve. The other half of a split screen has its own.
+    let (mut zoom, mut split_zoom) = (cell_size, cell_size);
+    // Whether the view's to be fitted to the live cells on the next redraw.
+    let mut framing = false;
+    // Whether the drag under way paints live or dead cells, and the frame
+    // pixel it last painted at.
+    let mut painting: Option<(bool, (usize, usize))> = None;
+    // The frame pixel the middle-button drag has dragged the board to.
+    let mut panning: Option<(usize, usize)> = None;
+    // Corners of the shift-dragged selection, whether the drag is still
+    // going, what was last copied, and the copy being placed, if any.
+    let mut selection: Option<((usize, usize), (usize, usize))> = None;
+    let mut selecting = false;
+    let clipboard: Arc<Mutex<Option<Grid>>> = Arc::default();
+    let mut pasting: Option<Grid> = None;
+    // Boards saved with Ctrl and a number key, to come back to with the
+    // number alone.
+    let slots: Arc<Mutex<[Option<Snapshot>; 9]>> = Arc::default();
+    // Where the shape being dragged out with a shape tool starts and the
+    // cell it reaches so far.
+    let mut shaping: Option<((usize, usize), (usize, usize))> = None;
+    let mut status = Status::new(config.warp);
+    // Seeds each random fill, so a run's soups come back with its --seed.
+    let mut soups = Rng::new(config.seed);
+    let mut modifiers = ModifiersState::empty();
+    let mut palette: Option<Palette<Pick>> = None;
+    // Boards marked with K, named by their generation, to go back to from
+    // the list L opens.
+    let bookmarks: Arc<Mutex<Vec<(String, Snapshot)>>> = Arc::default();
+    #[cfg(feature = "gamepad")]
+    let mut gamepad = Gamepad::new().map_err(|err| eprintln!("error: {err}")).ok();
     event_loop.run(move |event, _, control_flow| {
-        *control_flow = ControlFlow::Poll;
+        // The d-pad pans as the arrow keys do, the triggers act as ] and [,
+        // A as Space and B as N.
+        #[cfg(feature = "gamepad")]
+        if let (Event::MainEventsCleared, Some(gamepad)) = (&event, &mut gamepad) {
+            for action in gamepad.poll() {
+                match action {
+                    Action::Pan(down, right) => {
+                        let (rows, cols) = renderer.view_size();
+                        renderer.glide((down * (rows / 8).max(1) as isize, right * (cols / 8).max(1) as isize));
+                        window.request_redraw();
+                    }
+                    Action::Faster => status.warp = status.warp.saturating_mul(2),
+                    Action::Slower => status.warp = (status.warp / 2).max(1),
+                    Action::Pause => {
+                        if status.editing {
+                            status.set_editing(false);
+                            renderer.set_cursor(None);
+                        } else {
+                            status.paused = !status.paused;
+                        }
+                        clock.reset(Instant::now());
+                    }
+                    Action::Step if status.paused => {
+                        worker.step(1);
+                        pending += 1;
+                    }
+                    Action::Step => {}
+                }
+                window.set_title(&status.title(&parameters));
+            }
+        }
+        // While the palette is open it has the keyboard to itself, until
+        // Enter passes on the keys of the command picked as if they'd been
+        // pressed.
+        let mut event = event;
+        let mut held = None;
+        if let Some(open) = &mut palette {
+            let mut close = false;
+            let taken = match &mut event {
+                Event::WindowEvent { event: WindowEvent::ReceivedCharacter(ch), .. } => {
+                    if !ch.is_control() {
+                        open.push(*ch);
+                    }
+                    true
+                }
+                Event::WindowEvent { event: WindowEvent::KeyboardInput { input, .. }, .. } => {
+                    if input.state == ElementState::Pressed {
+                        match input.virtual_keycode {
+                            Some(VirtualKeyCode::Return) => {
+                                match open.chosen() {
+                                    Some(Pick::Keys(key, chord)) => {
+                                        input.virtual_keycode = Some(key);
+                                        held = Some(std::mem::replace(&mut modifiers, chord));
+                                    }
+                                    Some(Pick::Bookmark(index)) => {
+                                        let bookmarks = Arc::clone(&bookmarks);
+                                        worker.edit(move |game| {
+                                            if let Some((_, snapshot)) = bookmarks.lock().expect("bookmarks lock poisoned").get(index) {
+                                                game.load(snapshot);
+                                            }
+                                        });
+                                    }
+                                    None => {}
+                                }
+                                close = true;
+                            }
+                            Some(VirtualKeyCode::Escape) => close = true,
+                            Some(VirtualKeyCode::Back) => open.pop(),
+                            Some(VirtualKeyCode::Up) => open.select(-1),
+                            Some(VirtualKeyCode::Down) => open.select(1),
+                            _ => {}
+                        }
+                    }
+                    held.is_none()
+                }
+                _ => false,
+            };
+            let text = (!close).then(|| open.text());
+            if close {
+                palette = None;
+            }
+            if taken || close {
+                renderer.set_palette(text);
+                window.request_redraw();
+            }
+            if taken {
+                return;
+            }
+        }
         match event {
+            // Skipped while a step holds the board; the step's report asks
+            // for another redraw.
             Event::RedrawRequested(_) => {
-                game.draw(pixels.frame_mut(), cell_size);
-                if pixels.render().is_err() {
-                    *control_flow = ControlFlow::ExitWithCode(0);
+                if let Some(game) = worker.try_lock() {
+                    if game.dimensions() != shown {
+                        shown = game.dimensions();
+                        match renderer.resize(game.as_ref()) {
+                            Ok((width, height)) => window.set_inner_size(PhysicalSize::new(width as u32, height as u32)),
+                            Err(err) => {
+                                eprintln!("error: {err}");
+                                *control_flow = ControlFlow::ExitWithCode(1);
+                            }
+                        }
+                    }
+                    if let Some(size) = fitting.take()
+                        && let Err(err) = renderer.fit(game.as_ref(), size)
+                    {
+                        eprintln!("error: {err}");
+                        *control_flow = ControlFlow::ExitWithCode(1);
+                    }
+                    let (inner, scale) = (window.inner_size(), window.scale_factor());
+                    let size = (inner.width as usize, inner.height as usize);
+                    if std::mem::take(&mut framing)
+                        && let Some(cell_size) = renderer.frame_pattern(game.as_ref(), size, scaled(MAX_ZOOM, scale))
+                    {
+                        zoom = ((cell_size as f64 / scale).round() as usize).max(1);
+                    }
+                    // Zooms and pans ease in over several frames.
+                    match renderer.animate(game.as_ref(), size) {
+                        Ok(true) => window.request_redraw(),
+                        Ok(false) => {}
+                        Err(err) => {
+                            eprintln!("error: {err}");
+                            *control_flow = ControlFlow::ExitWithCode(1);
+                        }
+                    }
+                    if let Err(err) = renderer.render(game.as_ref()) {
+                        eprintln!("error: {err}");
+                        *control_flow = ControlFlow::ExitWithCode(1);
+                    }
+                    let hover = cursor
+                        .and_then(|position| renderer.board_cell_at(position))
+                        .map(|(row, col)| ((row, col), game.describe(row, col)));
+                    if hover != status.hover {
+                        status.hover = hover;
+                        window.set_title(&status.title(&parameters));
+                    }
+                }
+            }
+            Event::UserEvent(Report::Stepped { took, changes, populations, live }) => {
+                pending -= 1;
+                renderer.note(shown, changes.as_deref(), &populations, live);
+                renderer.record_step(took, 1);
+                window.request_redraw();
+            }
+            Event::UserEvent(Report::Edited { changes, parameters: current }) => {
+                renderer.mark_changes(shown, changes.as_deref());
+                parameters = current;
+                window.set_title(&status.title(&parameters));
+                window.request_redraw();
+            }
+            // Ticks that fall due while the worker is still busy are dropped
+            // rather than queued behind it.
+            Event::MainEventsCleared if status.paused => {
+                *control_flow = ControlFlow::Wait;
+                #[cfg(feature = "gamepad")]
+                if gamepad.is_some() {
+                    *control_flow = ControlFlow::WaitUntil(Instant::now() + GAMEPAD_POLL);
                 }
             }
             Event::MainEventsCleared => {
-                if last_update.elapsed() >= Duration::from_millis(200) {
-                    game.step();
-                    window.request_redraw();
-                    last_update = Instant::now();
+                let steps = clock.advance(Instant::now());
+                if steps > 0 && pending == 0 {
+                    worker.step(steps * status.warp);
+                    pending = steps * status.warp;
+                }
+                // Sleep until the next generation is due; input wakes the
+                // loop earlier.
+                *control_flow = ControlFlow::WaitUntil(clock.next_tick());
+                #[cfg(feature = "gamepad")]
+                if gamepad.is_some() {
+                    *control_flow = ControlFlow::WaitUntil(clock.next_tick().min(Instant::now() + GAMEPAD_POLL));
                 }
             }
             Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
                 *control_flow = ControlFlow::ExitWithCode(0);
             }
+            // Escape drops a paste or selection first, and quits once there's
+            // neither.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::Escape), state: ElementState::Pressed, .. }, .. }, .. } if pasting.is_some() || selection.is_some() => {
+                if pasting.take().is_some() {
+                    renderer.set_ghost(None);
+                    if status.stamp.take().is_some() {
+                        window.set_title(&status.title(&parameters));
+                    }
+                } else {
+                    selection = None;
+                    renderer.set_selection(None);
+                }
+                window.request_redraw();
+            }
             Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::Escape), state: ElementState::Pressed, .. }, .. }, .. } => {
                 *control_flow = ControlFlow::ExitWithCode(0);
             }
+            // Ctrl+Z undoes the last edit or batch of steps, and Ctrl+Y or
+            // Ctrl+Shift+Z redoes it.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(key @ (VirtualKeyCode::Z | VirtualKeyCode::Y)), state: ElementState::Pressed, .. }, .. }, .. } if modifiers.ctrl() => {
+                if key == VirtualKeyCode::Z && !modifiers.shift() {
+                    worker.undo();
+                } else {
+                    worker.redo();
+                }
+            }
+            // Ctrl+C copies the selection; Ctrl+V picks the copy up to be
+            // placed with a left click.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::C), state: ElementState::Pressed, .. }, .. }, .. } if modifiers.ctrl() => {
+                if let Some((a, b)) = selection {
+                    let clipboard = Arc::clone(&clipboard);
+                    worker.read(move |game| {
+                        *clipboard.lock().expect("clipboard lock poisoned") = Some(paint::copy(game, a, b));
+                    });
+                }
+            }
+            // Ctrl+S writes the selection, or failing that the board, to the
+            // --export file.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::S), state: ElementState::Pressed, .. }, .. }, .. } if modifiers.ctrl() => {
+                let (path, rule) = (config.export.clone(), config.rulestring());
+                worker.read(move |game| {
+                    let (rows, cols) = game.dimensions();
+                    let (a, b) = selection.unwrap_or(((0, 0), (rows.saturating_sub(1), cols.saturating_sub(1))));
+                    match formats::save(&path, &paint::copy(game, a, b), rule.as_deref()) {
+                        Ok(()) => println!("saved {path}"),
+                        Err(err) => eprintln!("error: {err}"),
+                    }
+                });
+            }
+            // Ctrl+1 to Ctrl+9 save the board in a slot, and 1 to 9 put it
+            // back.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(key), state: ElementState::Pressed, .. }, .. }, .. } if slot(key).is_some() => {
+                let (slot, slots) = (slot(key), Arc::clone(&slots));
+                if modifiers.ctrl() {
+                    worker.read(move |game| {
+                        let Some(slot) = slot else { return };
+                        let mut slots = slots.lock().expect("slots lock poisoned");
+                        slots[slot] = game.save();
+                        if slots[slot].is_none() {
+                            eprintln!("error: this automaton's boards can't be saved");
+                        }
+                    });
+                } else {
+                    worker.edit(move |game| {
+                        let Some(slot) = slot else { return };
+                        if let Some(snapshot) = &slots.lock().expect("slots lock poisoned")[slot] {
+                            game.load(snapshot);
+                        }
+                    });
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::V), state: ElementState::Pressed, .. }, .. }, .. } if modifiers.ctrl() => {
+                pasting = clipboard.lock().expect("clipboard lock poisoned").clone();
+                status.stamp = None;
+                status.set_editing(true);
+                window.set_title(&status.title(&parameters));
+                if let (Some(pattern), Some(at)) = (&pasting, cursor.and_then(|position| renderer.board_cell_at(position))) {
+                    renderer.set_ghost(Some((pattern.clone(), at)));
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::D), state: ElementState::Pressed, .. }, .. }, .. } => {
+                worker.edit(|game| {
+                    let (rows, cols) = game.dimensions();
+                    game.poke(rows / 2, cols / 2);
+                });
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::W), state: ElementState::Pressed, .. }, .. }, .. } => {
+                worker.edit(|game| {
+                    let wrapped = game.boundary() == Some(BoundaryCondition::Wrap);
+                    game.set_boundary(if wrapped { BoundaryCondition::Dead } else { BoundaryCondition::Wrap });
+                });
+            }
+            // Space stops and restarts the clock, leaving edit mode to run.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::Space), state: ElementState::Pressed, .. }, .. }, .. } => {
+                if status.editing {
+                    status.set_editing(false);
+                    renderer.set_cursor(None);
+                    window.request_redraw();
+                } else {
+                    status.paused = !status.paused;
+                }
+                clock.reset(Instant::now());
+                window.set_title(&status.title(&parameters));
+            }
+            // E switches between run mode and edit mode.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::E), state: ElementState::Pressed, .. }, .. }, .. } => {
+                status.set_editing(!status.editing);
+                renderer.set_cursor(cursor.filter(|_| status.editing).and_then(|position| renderer.board_cell_at(position)));
+                clock.reset(Instant::now());
+                window.set_title(&status.title(&parameters));
+                window.request_redraw();
+            }
+            // Ctrl+P opens the command palette.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::P), state: ElementState::Pressed, .. }, .. }, .. } if modifiers.ctrl() => {
+                let open = Palette::new(COMMANDS.iter().map(|&(name, (key, chord))| (name.to_string(), Pick::Keys(key, chord))));
+                renderer.set_palette(Some(open.text()));
+                palette = Some(open);
+                window.request_redraw();
+            }
+            // K bookmarks the board as it is, and L lists the bookmarks in
+            // the palette to go back to one.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::K), state: ElementState::Pressed, .. }, .. }, .. } => {
+                let bookmarks = Arc::clone(&bookmarks);
+                worker.read(move |game| match game.save() {
+                    Some(snapshot) => {
+                        let mut bookmarks = bookmarks.lock().expect("bookmarks lock poisoned");
+                        let name = format!("{}: generation {}", bookmarks.len() + 1, snapshot.generation);
+                        bookmarks.push((name, snapshot));
+                    }
+                    None => eprintln!("error: this automaton's boards can't be saved"),
+                });
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::L), state: ElementState::Pressed, .. }, .. }, .. } => {
+                let names: Vec<_> = bookmarks.lock().expect("bookmarks lock poisoned").iter().map(|(name, _)| name.clone()).collect();
+                let open = Palette::new(names.into_iter().enumerate().map(|(index, name)| (name, Pick::Bookmark(index))));
+                renderer.set_palette(Some(open.text()));
+                palette = Some(open);
+                window.request_redraw();
+            }
+            // P picks up the next pattern from the library to stamp.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::P), state: ElementState::Pressed, .. }, .. }, .. } => {
+                let next = status.stamp.map_or(0, |stamp| (stamp + 1) % patterns::LIBRARY.len());
+                status.stamp = Some(next);
+                status.set_editing(true);
+                let pattern = patterns::from_rows(patterns::LIBRARY[next].1);
+                if let Some(at) = cursor.and_then(|position| renderer.board_cell_at(position)) {
+                    renderer.set_ghost(Some((pattern.clone(), at)));
+                    window.request_redraw();
+                }
+                pasting = Some(pattern);
+                window.set_title(&status.title(&parameters));
+            }
+            // With a copy being placed or a selection made, R turns it a
+            // quarter turn clockwise, X mirrors it left to right and Y top
+            // to bottom.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(key @ (VirtualKeyCode::R | VirtualKeyCode::X | VirtualKeyCode::Y)), state: ElementState::Pressed, .. }, .. }, .. } if pasting.is_some() || selection.is_some() => {
+                let transform = match key {
+                    VirtualKeyCode::R => Grid::rotated,
+                    VirtualKeyCode::X => Grid::flipped_horizontally,
+                    _ => Grid::flipped_vertically,
+                };
+                if let Some(pattern) = &mut pasting {
+                    *pattern = transform(pattern);
+                    if let Some(at) = cursor.and_then(|position| renderer.board_cell_at(position)) {
+                        renderer.set_ghost(Some((pattern.clone(), at)));
+                    }
+                } else if let Some((a, b)) = selection {
+                    worker.edit(move |game| paint::transform(game, a, b, transform));
+                    let ((top, left), (bottom, right)) = paint::bounds(a, b);
+                    if key == VirtualKeyCode::R {
+                        selection = Some(((top, left), (top + right - left, left + bottom - top)));
+                        renderer.set_selection(selection);
+                    }
+                }
+                window.request_redraw();
+            }
+            // R starts over from the first generation.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::R), state: ElementState::Pressed, .. }, .. }, .. } => {
+                worker.replace(start(&config, &board));
+            }
+            // C wipes the board and goes into edit mode, ready to draw on.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::C), state: ElementState::Pressed, .. }, .. }, .. } => {
+                worker.edit(|game| game.clear());
+                status.set_editing(true);
+                window.set_title(&status.title(&parameters));
+            }
+            // F fills the board with a random soup at --density.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::F), state: ElementState::Pressed, .. }, .. }, .. } => {
+                let (density, seed) = (config.density, soups.next_u64());
+                worker.edit(move |game| game.randomize(density, seed));
+            }
+            // Dropping a pattern file on the window pauses and puts
+            // the pattern in the middle of an otherwise empty board.
+            Event::WindowEvent { event: WindowEvent::DroppedFile(path), .. } => match formats::load(&path) {
+                Ok(pattern) => {
+                    status.paused = true;
+                    window.set_title(&status.title(&parameters));
+                    worker.edit(move |game| {
+                        let (rows, cols) = game.dimensions();
+                        let mut board = Grid::new(rows, cols);
+                        let (down, right) = Anchor::Center.offset((pattern.rows(), pattern.cols()), (rows, cols));
+                        board.paste(&pattern, down, right);
+                        paint::paste(game, &board, (0, 0));
+                    });
+                }
+                Err(err) => eprintln!("error: {err}"),
+            },
+            // T switches to the next editing tool.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::T), state: ElementState::Pressed, .. }, .. }, .. } => {
+                status.tool = status.tool.next();
+                if shaping.take().is_some() {
+                    renderer.set_ghost(None);
+                    window.request_redraw();
+                }
+                window.set_title(&status.title(&parameters));
+            }
+            // M switches to the next symmetry for drawing.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::M), state: ElementState::Pressed, .. }, .. }, .. } => {
+                status.symmetry = status.symmetry.next();
+                window.set_title(&status.title(&parameters));
+            }
+            // B pauses and goes back a generation.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::B), state: ElementState::Pressed, .. }, .. }, .. } => {
+                worker.back();
+                if !status.paused {
+                    status.paused = true;
+                    window.set_title(&status.title(&parameters));
+                }
+            }
+            // , and . pause and scrub back and forth through the run by
+            // --scrub generations.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(key @ (VirtualKeyCode::Comma | VirtualKeyCode::Period)), state: ElementState::Pressed, .. }, .. }, .. } => {
+                let by = config.scrub as i64;
+                worker.seek(if key == VirtualKeyCode::Comma { -by } else { by });
+                if !status.paused {
+                    status.paused = true;
+                    window.set_title(&status.title(&parameters));
+                }
+            }
+            // N steps a paused board one generation at a time.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::N), state: ElementState::Pressed, .. }, .. }, .. } if status.paused => {
+                worker.step(1);
+                pending += 1;
+            }
+            // ] and [ double and halve the generations run per tick.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(key @ (VirtualKeyCode::RBracket | VirtualKeyCode::LBracket)), state: ElementState::Pressed, .. }, .. }, .. } => {
+                status.warp = if key == VirtualKeyCode::RBracket { status.warp.saturating_mul(2) } else { (status.warp / 2).max(1) };
+                window.set_title(&status.title(&parameters));
+            }
+            // G jumps ahead --jump generations as fast as they'll go.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::G), state: ElementState::Pressed, .. }, .. }, .. } => {
+                worker.step(config.jump);
+                pending += config.jump;
+            }
+            // H shows and hides the lines between cells.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::H), state: ElementState::Pressed, .. }, .. }, .. } => {
+                renderer.toggle_grid_lines();
+                window.request_redraw();
+            }
+            // A colors live cells by how long they've been alive.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::A), state: ElementState::Pressed, .. }, .. }, .. } => {
+                renderer.toggle_ages();
+                window.request_redraw();
+            }
+            // J fades dead cells out instead of snapping them to white.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::J), state: ElementState::Pressed, .. }, .. }, .. } => {
+                renderer.toggle_trails();
+                window.request_redraw();
+            }
+            // U shows the cells each generation brings to life and kills.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::U), state: ElementState::Pressed, .. }, .. }, .. } => {
+                renderer.toggle_births();
+                window.request_redraw();
+            }
+            // F3 shows where each frame's time goes.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::F3), state: ElementState::Pressed, .. }, .. }, .. } => {
+                renderer.toggle_profile();
+                window.request_redraw();
+            }
+            // I shows the generation, population and how fast it's all going.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::I), state: ElementState::Pressed, .. }, .. }, .. } => {
+                renderer.toggle_hud();
+                window.request_redraw();
+            }
+            // O charts the live cells over the last few hundred generations.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::O), state: ElementState::Pressed, .. }, .. }, .. } => {
+                renderer.toggle_chart();
+                window.request_redraw();
+            }
+            // S colors the board by each heatmap in turn, then by state again.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::S), state: ElementState::Pressed, .. }, .. }, .. } => {
+                status.heatmap = renderer.next_heatmap();
+                window.set_title(&status.title(&parameters));
+                window.request_redraw();
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::Tab), state: ElementState::Pressed, .. }, .. }, .. } => {
+                status.selected = (status.selected + 1) % parameters.len().max(1);
+                window.set_title(&status.title(&parameters));
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(key @ (VirtualKeyCode::PageUp | VirtualKeyCode::PageDown)), state: ElementState::Pressed, .. }, .. }, .. } => {
+                if let Some(&parameter) = parameters.get(status.selected) {
+                    let value = if key == VirtualKeyCode::PageUp { parameter.raised() } else { parameter.lowered() };
+                    worker.edit(move |game| game.set_parameter(parameter.name, value));
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::ModifiersChanged(state), .. } => modifiers = state,
+            // Ctrl+arrows grow and shrink the board by --resize-step cells.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(key @ (VirtualKeyCode::Up | VirtualKeyCode::Down | VirtualKeyCode::Left | VirtualKeyCode::Right)), state: ElementState::Pressed, .. }, .. }, .. } if modifiers.ctrl() => {
+                let (step, anchor) = (config.resize_step, config.anchor);
+                worker.edit(move |game| {
+                    let (rows, cols) = game.dimensions();
+                    let (rows, cols) = match key {
+                        VirtualKeyCode::Down => (rows + step, cols),
+                        VirtualKeyCode::Up => (rows.saturating_sub(step).max(1), cols),
+                        VirtualKeyCode::Right => (rows, cols + step),
+                        _ => (rows, cols.saturating_sub(step).max(1)),
+                    };
+                    game.resize(rows, cols, anchor);
+                });
+            }
+            // Arrows pan around boards too big for the window, an eighth of
+            // the view at a time.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(key @ (VirtualKeyCode::Up | VirtualKeyCode::Down | VirtualKeyCode::Left | VirtualKeyCode::Right)), state: ElementState::Pressed, .. }, .. }, .. } => {
+                let (rows, cols) = renderer.view_size();
+                let (down, right) = ((rows / 8).max(1) as isize, (cols / 8).max(1) as isize);
+                renderer.glide(match key {
+                    VirtualKeyCode::Up => (-down, 0),
+                    VirtualKeyCode::Down => (down, 0),
+                    VirtualKeyCode::Left => (0, -right),
+                    _ => (0, right),
+                });
+                window.request_redraw();
+            }
+            // The board is refitted to the new size on the next redraw, when
+            // the worker isn't holding it.
+            Event::WindowEvent { event: WindowEvent::Resized(size), .. } => {
+                if let Err(err) = renderer.pixels_mut().resize_surface(size.width, size.height) {
+                    eprintln!("error: {err}");
+                    *control_flow = ControlFlow::ExitWithCode(1);
+                }
+                fitting = Some((size.width as usize, size.height as usize));
+                window.request_redraw();
+            }
+            // Moving to a display of another density keeps cells the same
+            // size on screen.
+            Event::WindowEvent { event: WindowEvent::ScaleFactorChanged { scale_factor, new_inner_size }, .. } => {
+                renderer.set_cell_size(scaled(zoom, scale_factor));
+                renderer.set_split_cell_size(scaled(split_zoom, scale_factor));
+                if let Err(err) = renderer.pixels_mut().resize_surface(new_inner_size.width, new_inner_size.height) {
+                    eprintln!("error: {err}");
+                    *control_flow = ControlFlow::ExitWithCode(1);
+                }
+                fitting = Some((new_inner_size.width as usize, new_inner_size.height as usize));
+                window.request_redraw();
+            }
+            // V splits the window between two views of the board, or joins it
+            // back up.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::V), state: ElementState::Pressed, .. }, .. }, .. } => {
+                renderer.toggle_split();
+                split_zoom = zoom;
+                let size = window.inner_size();
+                fitting = Some((size.width as usize, size.height as usize));
+                window.request_redraw();
+            }
+            // Home zooms and pans to fit the live cells in the window.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(VirtualKeyCode::Home), state: ElementState::Pressed, .. }, .. }, .. } => {
+                framing = true;
+                window.request_redraw();
+            }
+            // = and - zoom in and out on the middle of the view, and the wheel
+            // on the cell under the cursor.
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(key @ (VirtualKeyCode::Equals | VirtualKeyCode::Minus)), state: ElementState::Pressed, .. }, .. }, .. } => {
+                zoom = if key == VirtualKeyCode::Equals { (zoom * 2).min(MAX_ZOOM) } else { (zoom / 2).max(1) };
+                renderer.zoom_to(scaled(zoom, window.scale_factor()), None);
+                window.request_redraw();
+            }
+            Event::WindowEvent { event: WindowEvent::MouseWheel { delta, .. }, .. } => {
+                let up = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y > 0.0,
+                    MouseScrollDelta::PixelDelta(position) => position.y > 0.0,
+                };
+                zoom = if up { (zoom * 2).min(MAX_ZOOM) } else { (zoom / 2).max(1) };
+                renderer.zoom_to(scaled(zoom, window.scale_factor()), cursor);
+                window.request_redraw();
+            }
+            Event::WindowEvent { event: WindowEvent::CursorMoved { position, .. }, .. } => {
+                cursor = Some((position.x as f32, position.y as f32));
+                // Whichever half of a split screen the cursor's over is the
+                // one panned and zoomed, bar partway through a drag.
+                if painting.is_none()
+                    && panning.is_none()
+                    && shaping.is_none()
+                    && let Some(position) = cursor
+                    && renderer.focus(position)
+                {
+                    std::mem::swap(&mut zoom, &mut split_zoom);
+                }
+                let cell = cursor.and_then(|position| renderer.board_cell_at(position));
+                // The readout catches up on the next redraw.
+                if cell != status.hover.as_ref().map(|&(cell, _)| cell) {
+                    window.request_redraw();
+                }
+                if status.editing {
+                    renderer.set_cursor(cell);
+                    window.request_redraw();
+                }
+                if let (true, Some((anchor, _)), Some(corner)) = (selecting, selection, cell) {
+                    selection = Some((anchor, corner));
+                    renderer.set_selection(selection);
+                    window.request_redraw();
+                }
+                if let (Some(pattern), Some(at)) = (&pasting, cell) {
+                    renderer.set_ghost(Some((pattern.clone(), at)));
+                    window.request_redraw();
+                }
+                if let (Some((from, _)), Some(to)) = (shaping, cell)
+                    && let Some(cells) = status.tool.shape(from, to)
+                {
+                    shaping = Some((from, to));
+                    renderer.set_ghost(Some(paint::stencil(&status.symmetry.apply(shown, &cells))));
+                    window.request_redraw();
+                }
+                // The board follows the cursor a whole cell at a time,
+                // keeping whatever's left over for the next move.
+                if let (Some((x, y)), Some((from_x, from_y))) = (cursor.and_then(|position| renderer.pixel_at(position)), panning) {
+                    let cell = renderer.cell_size() as isize;
+                    let (down, right) = ((from_y as isize - y as isize) / cell, (from_x as isize - x as isize) / cell);
+                    if (down, right) != (0, 0) {
+                        renderer.pan((down, right));
+                        panning = Some(((from_x as isize - right * cell) as usize, (from_y as isize - down * cell) as usize));
+                        window.request_redraw();
+                    }
+                }
+                if let (Some((alive, from)), Some(to)) = (painting, cursor.and_then(|position| renderer.pixel_at(position))) {
+                    painting = Some((alive, to));
+                    let (cell_size, viewport) = (renderer.cell_size(), renderer.viewport());
+                    worker.stroke(move |game| {
+                        let cell = |(x, y)| viewport.cell_at_pixel(game, cell_size, x, y);
+                        // Motion events come far apart on a fast drag, so
+                        // fill in the cells between them.
+                        let cells = match (cell(from), cell(to)) {
+                            (Some(from), Some(to)) => paint::line(from, to).split_off(1),
+                            (_, to) => to.into_iter().collect(),
+                        };
+                        for (row, col) in status.symmetry.apply(game.dimensions(), &cells) {
+                            game.set_cell(row, col, alive);
+                        }
+                    });
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::CursorLeft { .. }, .. } => {
+                cursor = None;
+                renderer.set_cursor(None);
+                window.request_redraw();
+            }
+            Event::WindowEvent { event: WindowEvent::MouseInput { button: MouseButton::Left | MouseButton::Right, state: ElementState::Released, .. }, .. } => {
+                painting = None;
+                selecting = false;
+                if let Some((from, to)) = shaping.take()
+                    && let Some(cells) = status.tool.shape(from, to)
+                {
+                    renderer.set_ghost(None);
+                    worker.edit(move |game| {
+                        for (row, col) in status.symmetry.apply(game.dimensions(), &cells) {
+                            game.set_cell(row, col, true);
+                        }
+                    });
+                }
+            }
+            // Clicking the minimap moves the view there.
+            Event::WindowEvent { event: WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. }, .. } if cursor.and_then(|position| renderer.minimap_cell_at(position)).is_some() => {
+                if let Some(cell) = cursor.and_then(|position| renderer.minimap_cell_at(position)) {
+                    renderer.glide_to(cell);
+                    window.request_redraw();
+                }
+            }
+            // Shift-dragging selects a rectangle of cells.
+            Event::WindowEvent { event: WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. }, .. } if modifiers.shift() => {
+                if let Some(cell) = cursor.and_then(|position| renderer.board_cell_at(position)) {
+                    selection = Some((cell, cell));
+                    selecting = true;
+                    renderer.set_selection(selection);
+                    window.request_redraw();
+                }
+            }
+            // Run mode leaves the board alone.
+            Event::WindowEvent { event: WindowEvent::MouseInput { button: MouseButton::Left | MouseButton::Right, state: ElementState::Pressed, .. }, .. } if !status.editing => {}
+            // While a copy is being placed, a click puts it down.
+            Event::WindowEvent { event: WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. }, .. } if pasting.is_some() => {
+                if let Some(at) = cursor.and_then(|position| renderer.board_cell_at(position))
+                    && let Some(pattern) = if status.stamp.is_some() { pasting.clone() } else { pasting.take() }
+                {
+                    if pasting.is_none() {
+                        renderer.set_ghost(None);
+                    }
+                    worker.edit(move |game| paint::paste(game, &pattern, at));
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::MouseInput { button: MouseButton::Middle, state, .. }, .. } => {
+                panning = match state {
+                    ElementState::Pressed => cursor.and_then(|position| renderer.pixel_at(position)),
+                    ElementState::Released => None,
+                };
+            }
+            // A right click clears a cell, and dragging on from it clears
+            // every cell the cursor crosses.
+            Event::WindowEvent { event: WindowEvent::MouseInput { button: MouseButton::Right, state: ElementState::Pressed, .. }, .. } => {
+                if let Some((x, y)) = cursor.and_then(|position| renderer.pixel_at(position)) {
+                    painting = Some((false, (x, y)));
+                    let (cell_size, viewport) = (renderer.cell_size(), renderer.viewport());
+                    worker.edit(move |game| {
+                        if let Some(cell) = viewport.cell_at_pixel(game, cell_size, x, y) {
+                            for (row, col) in status.symmetry.images(game.dimensions(), cell) {
+                                game.set_cell(row, col, false);
+                            }
+                        }
+                    });
+                }
+            }
+            // With the fill tool, a click fills or empties the region under it.
+            Event::WindowEvent { event: WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. }, .. } if status.tool == Tool::Fill => {
+                if let Some(at) = cursor.and_then(|position| renderer.board_cell_at(position)) {
+                    worker.edit(move |game| {
+                        // An image already in the region filled is
+                        // flipped with it.
+                        let state = game.cell(at.0, at.1);
+                        for image in status.symmetry.images(game.dimensions(), at) {
+                            if game.cell(image.0, image.1) == state {
+                                paint::fill(game, image);
+                            }
+                        }
+                    });
+                }
+            }
+            // With a shape tool, dragging draws the shape from where the
+            // button went down to where it comes up.
+            Event::WindowEvent { event: WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. }, .. } if matches!(status.tool, Tool::Line | Tool::Rectangle | Tool::FilledRectangle) => {
+                if let Some(at) = cursor.and_then(|position| renderer.board_cell_at(position)) {
+                    shaping = Some((at, at));
+                    renderer.set_ghost(status.tool.shape(at, at).map(|cells| paint::stencil(&status.symmetry.apply(shown, &cells))));
+                    window.request_redraw();
+                }
+            }
+            // A click toggles a cell; dragging on from it paints live cells.
+            Event::WindowEvent { event: WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. }, .. } => {
+                if let Some((x, y)) = cursor.and_then(|position| renderer.pixel_at(position)) {
+                    painting = Some((true, (x, y)));
+                    let (cell_size, viewport) = (renderer.cell_size(), renderer.viewport());
+                    worker.edit(move |game| {
+                        if let Some(cell) = viewport.cell_at_pixel(game, cell_size, x, y) {
+                            for (row, col) in status.symmetry.images(game.dimensions(), cell) {
+                                game.poke(row, col);
+                            }
+                        }
+                    });
+                }
+            }
             _ => {}
         }
+        if let Some(held) = held {
+            modifiers = held;
+        }
     });
 }
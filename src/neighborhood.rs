@@ -0,0 +1,143 @@
+use crate::{Error, Lattice};
+
+pub trait Neighborhood: Send + Sync {
+    fn offsets(&self) -> &[(isize, isize)];
+
+    // Lattices whose cells come in several orientations pick offsets per
+    // cell; everything else uses the same ones everywhere.
+    fn offsets_at(&self, _row: usize, _col: usize) -> &[(isize, isize)] {
+        self.offsets()
+    }
+
+    fn lattice(&self) -> Lattice {
+        Lattice::Square
+    }
+}
+
+// Neighbor counts are bytes, so no neighborhood can have more than 255
+// cells.
+fn offsets_within(range: usize, inside: impl Fn(isize, isize) -> bool) -> Result<Vec<(isize, isize)>, Error> {
+    let too_many = || Error::Rule(format!("a neighborhood of range {range} has too many cells to count"));
+    let r = isize::try_from(range).ok().filter(|&r| r < 16).ok_or_else(too_many)?;
+    let offsets: Vec<_> = (-r..=r)
+        .flat_map(|dr| (-r..=r).map(move |dc| (dr, dc)))
+        .filter(|&(dr, dc)| (dr, dc) != (0, 0) && inside(dr, dc))
+        .collect();
+    if offsets.len() > u8::MAX as usize {
+        return Err(too_many());
+    }
+    Ok(offsets)
+}
+
+pub struct Moore {
+    offsets: Vec<(isize, isize)>,
+}
+
+impl Moore {
+    pub fn new() -> Self {
+        Self::range(1).expect("range 1 has eight cells")
+    }
+
+    pub fn range(range: usize) -> Result<Self, Error> {
+        Ok(Self {
+            offsets: offsets_within(range, |_, _| true)?,
+        })
+    }
+}
+
+impl Default for Moore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Neighborhood for Moore {
+    fn offsets(&self) -> &[(isize, isize)] {
+        &self.offsets
+    }
+}
+
+pub struct VonNeumann {
+    offsets: Vec<(isize, isize)>,
+}
+
+impl VonNeumann {
+    pub fn new() -> Self {
+        Self::range(1).expect("range 1 has four cells")
+    }
+
+    pub fn range(range: usize) -> Result<Self, Error> {
+        Ok(Self {
+            offsets: offsets_within(range, |dr, dc| dr.unsigned_abs() + dc.unsigned_abs() <= range)?,
+        })
+    }
+}
+
+impl Default for VonNeumann {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Neighborhood for VonNeumann {
+    fn offsets(&self) -> &[(isize, isize)] {
+        &self.offsets
+    }
+}
+
+// The six cells touching a hexagon, in axial (row, col) coordinates.
+pub struct Hexagonal;
+
+impl Hexagonal {
+    const OFFSETS: [(isize, isize); 6] = [(-1, 0), (-1, 1), (0, 1), (1, 0), (1, -1), (0, -1)];
+}
+
+impl Neighborhood for Hexagonal {
+    fn offsets(&self) -> &[(isize, isize)] {
+        &Self::OFFSETS
+    }
+
+    fn lattice(&self) -> Lattice {
+        Lattice::Hexagonal
+    }
+}
+
+// Neighbors on a triangular lattice, mirrored vertically for down-pointing
+// cells: the 3 cells sharing an edge or the 12 sharing at least a corner.
+// Wrapping across an odd number of columns breaks the up/down alternation.
+pub struct Triangular {
+    up: Vec<(isize, isize)>,
+    down: Vec<(isize, isize)>,
+}
+
+impl Triangular {
+    fn mirrored(up: Vec<(isize, isize)>) -> Self {
+        let down = up.iter().map(|&(dr, dc)| (-dr, dc)).collect();
+        Self { up, down }
+    }
+
+    pub fn edges() -> Self {
+        Self::mirrored(vec![(0, -1), (0, 1), (1, 0)])
+    }
+
+    pub fn vertices() -> Self {
+        let above = (-1..=1).map(|dc| (-1, dc));
+        let beside = [-2, -1, 1, 2].into_iter().map(|dc| (0, dc));
+        let below = (-2..=2).map(|dc| (1, dc));
+        Self::mirrored(above.chain(beside).chain(below).collect())
+    }
+}
+
+impl Neighborhood for Triangular {
+    fn offsets(&self) -> &[(isize, isize)] {
+        &self.up
+    }
+
+    fn offsets_at(&self, row: usize, col: usize) -> &[(isize, isize)] {
+        if (row + col).is_multiple_of(2) { &self.up } else { &self.down }
+    }
+
+    fn lattice(&self) -> Lattice {
+        Lattice::Triangular
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{Grid, Simulator};
+
+// What a left click on the board does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tool {
+    // Toggles the cell, and paints live cells dragged over.
+    #[default]
+    Brush,
+    // Flips the whole region around the cell, as `fill` does.
+    Fill,
+    // Draw live cells from where the button goes down to where it comes
+    // up, as `shape` has them.
+    Line,
+    Rectangle,
+    FilledRectangle,
+}
+
+impl Tool {
+    pub fn next(self) -> Self {
+        match self {
+            Tool::Brush => Tool::Fill,
+            Tool::Fill => Tool::Line,
+            Tool::Line => Tool::Rectangle,
+            Tool::Rectangle => Tool::FilledRectangle,
+            Tool::FilledRectangle => Tool::Brush,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::Brush => "brush",
+            Tool::Fill => "fill",
+            Tool::Line => "line",
+            Tool::Rectangle => "rectangle",
+            Tool::FilledRectangle => "filled rectangle",
+        }
+    }
+
+    // The cells drawn between `from` and `to`, for the tools that draw
+    // shapes.
+    pub fn shape(self, from: (usize, usize), to: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        match self {
+            Tool::Brush | Tool::Fill => None,
+            Tool::Line => Some(line(from, to)),
+            Tool::Rectangle => Some(rectangle(from, to, false)),
+            Tool::FilledRectangle => Some(rectangle(from, to, true)),
+        }
+    }
+}
+
+// How each edit is repeated across the board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Symmetry {
+    #[default]
+    None,
+    // Mirrored left to right, about the middle column.
+    MirrorX,
+    // Mirrored top to bottom, about the middle row.
+    MirrorY,
+    // Mirrored both ways.
+    Fourfold,
+    // Turned by quarter turns about the middle of the board.
+    Rotational,
+}
+
+impl Symmetry {
+    pub fn next(self) -> Self {
+        match self {
+            Symmetry::None => Symmetry::MirrorX,
+            Symmetry::MirrorX => Symmetry::MirrorY,
+            Symmetry::MirrorY => Symmetry::Fourfold,
+            Symmetry::Fourfold => Symmetry::Rotational,
+            Symmetry::Rotational => Symmetry::None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Symmetry::None => "no symmetry",
+            Symmetry::MirrorX => "mirror x",
+            Symmetry::MirrorY => "mirror y",
+            Symmetry::Fourfold => "4-fold",
+            Symmetry::Rotational => "rotational",
+        }
+    }
+
+    // `cell` and its images on a board of `dimensions`, each once, leaving
+    // out quarter turns that land off a board that isn't square.
+    pub fn images(self, (rows, cols): (usize, usize), (row, col): (usize, usize)) -> Vec<(usize, usize)> {
+        let (mirror_row, mirror_col) = (rows - 1 - row, cols - 1 - col);
+        let mut images = match self {
+            Symmetry::None => vec![(row, col)],
+            Symmetry::MirrorX => vec![(row, col), (row, mirror_col)],
+            Symmetry::MirrorY => vec![(row, col), (mirror_row, col)],
+            Symmetry::Fourfold => vec![(row, col), (row, mirror_col), (mirror_row, col), (mirror_row, mirror_col)],
+            Symmetry::Rotational => {
+                // Offsets from the middle, doubled to stay whole on boards
+                // with an even side.
+                let (down, right) = (2 * row as isize - (rows as isize - 1), 2 * col as isize - (cols as isize - 1));
+                let at = |down: isize, right: isize| {
+                    let (row, col) = ((down + rows as isize - 1).div_euclid(2), (right + cols as isize - 1).div_euclid(2));
+                    ((0..rows as isize).contains(&row) && (0..cols as isize).contains(&col)).then_some((row as usize, col as usize))
+                };
+                [at(down, right), at(right, -down), at(-down, -right), at(-right, down)].into_iter().flatten().collect()
+            }
+        };
+        images.sort_unstable();
+        images.dedup();
+        images
+    }
+
+    // `cells` and all their images.
+    pub fn apply(self, dimensions: (usize, usize), cells: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        let mut images: Vec<_> = cells.iter().flat_map(|&cell| self.images(dimensions, cell)).collect();
+        images.sort_unstable();
+        images.dedup();
+        images
+    }
+}
+
+// The cells on a straight line from `from` to `to`, both ends included, each
+// one touching the last so a stroke drawn through them has no gaps.
+pub fn line(from: (usize, usize), to: (usize, usize)) -> Vec<(usize, usize)> {
+    let (mut row, mut col) = (from.0 as i64, from.1 as i64);
+    let (end_row, end_col) = (to.0 as i64, to.1 as i64);
+    let (drow, dcol) = ((end_row - row).abs(), -(end_col - col).abs());
+    let (step_row, step_col) = ((end_row - row).signum(), (end_col - col).signum());
+    let mut error = drow + dcol;
+    let mut cells = Vec::with_capacity((drow - dcol) as usize + 1);
+    loop {
+        cells.push((row as usize, col as usize));
+        if (row, col) == (end_row, end_col) {
+            return cells;
+        }
+        let doubled = 2 * error;
+        if doubled >= dcol {
+            error += dcol;
+            row += step_row;
+        }
+        if doubled <= drow {
+            error += drow;
+            col += step_col;
+        }
+    }
+}
+
+// The cells of the rectangle with corners `a` and `b`, or just its edges
+// unless `filled`.
+pub fn rectangle(a: (usize, usize), b: (usize, usize), filled: bool) -> Vec<(usize, usize)> {
+    let ((top, left), (bottom, right)) = bounds(a, b);
+    (top..=bottom)
+        .flat_map(|row| (left..=right).map(move |col| (row, col)))
+        .filter(|&(row, col)| filled || row == top || row == bottom || col == left || col == right)
+        .collect()
+}
+
+// `cells` as a pattern and the board cell its top-left corner goes on.
+pub fn stencil(cells: &[(usize, usize)]) -> (Grid, (usize, usize)) {
+    let top = cells.iter().map(|&(row, _)| row).min().unwrap_or(0);
+    let left = cells.iter().map(|&(_, col)| col).min().unwrap_or(0);
+    let rows = cells.iter().map(|&(row, _)| row + 1 - top).max().unwrap_or(0);
+    let cols = cells.iter().map(|&(_, col)| col + 1 - left).max().unwrap_or(0);
+    let mut pattern = Grid::new(rows, cols);
+    for &(row, col) in cells {
+        pattern.set(row - top, col - left, true);
+    }
+    (pattern, (top, left))
+}
+
+// Corners of the rectangle spanned by two cells, top-left first.
+pub fn bounds(a: (usize, usize), b: (usize, usize)) -> ((usize, usize), (usize, usize)) {
+    ((a.0.min(b.0), a.1.min(b.1)), (a.0.max(b.0), a.1.max(b.1)))
+}
+
+// The live cells of the rectangle between `a` and `b`, both included.
+pub fn copy(sim: &dyn Simulator, a: (usize, usize), b: (usize, usize)) -> Grid {
+    let (rows, cols) = sim.dimensions();
+    let ((top, left), (bottom, right)) = bounds(a, b);
+    let (bottom, right) = (bottom.min(rows.saturating_sub(1)), right.min(cols.saturating_sub(1)));
+    let mut pattern = Grid::new((bottom + 1).saturating_sub(top), (right + 1).saturating_sub(left));
+    for row in 0..pattern.rows() {
+        for col in 0..pattern.cols() {
+            pattern.set(row, col, sim.cell(top + row, left + col));
+        }
+    }
+    pattern
+}
+
+// Writes `pattern` over the board with its top-left corner at `at`, dead
+// cells and all, dropping whatever hangs off the edge.
+pub fn paste(sim: &mut dyn Simulator, pattern: &Grid, at: (usize, usize)) {
+    let (rows, cols) = sim.dimensions();
+    for row in 0..pattern.rows().min(rows.saturating_sub(at.0)) {
+        for col in 0..pattern.cols().min(cols.saturating_sub(at.1)) {
+            sim.set_cell(at.0 + row, at.1 + col, pattern.get(row, col));
+        }
+    }
+}
+
+// Replaces the rectangle between `a` and `b` with `transform` of what's in
+// it, kept at the same top-left corner.
+pub fn transform(sim: &mut dyn Simulator, a: (usize, usize), b: (usize, usize), transform: impl Fn(&Grid) -> Grid) {
+    let (top_left, _) = bounds(a, b);
+    let pattern = copy(sim, a, b);
+    paste(sim, &Grid::new(pattern.rows(), pattern.cols()), top_left);
+    paste(sim, &transform(&pattern), top_left);
+}
+
+// Flips the cell at `at` and every cell in the same state joined to it side
+// on, so a click fills a dead region with live cells or empties a live one.
+pub fn fill(sim: &mut dyn Simulator, at: (usize, usize)) {
+    let (rows, cols) = sim.dimensions();
+    if at.0 >= rows || at.1 >= cols {
+        return;
+    }
+    let state = sim.cell(at.0, at.1);
+    let mut seen = vec![false; rows * cols];
+    seen[at.0 * cols + at.1] = true;
+    let mut pending = vec![at];
+    while let Some((row, col)) = pending.pop() {
+        sim.set_cell(row, col, !state);
+        let sides = [
+            row.checked_sub(1).map(|up| (up, col)),
+            (row + 1 < rows).then_some((row + 1, col)),
+            col.checked_sub(1).map(|left| (row, left)),
+            (col + 1 < cols).then_some((row, col + 1)),
+        ];
+        for (row, col) in sides.into_iter().flatten() {
+            if !seen[row * cols + col] && sim.cell(row, col) == state {
+                seen[row * cols + col] = true;
+                pending.push((row, col));
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+// Entries looked up by name: the ones whose names hold the letters typed so
+// far, in order though not necessarily together, closest matches first.
+pub struct Palette<T> {
+    entries: Vec<(String, T)>,
+    query: String,
+    selected: usize,
+}
+
+// Most matches listed at once.
+const SHOWN: usize = 10;
+
+impl<T: Copy> Palette<T> {
+    pub fn new(entries: impl IntoIterator<Item = (String, T)>) -> Self {
+        Self {
+            entries: entries.into_iter().collect(),
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    pub fn push(&mut self, ch: char) {
+        self.query.push(ch);
+        self.selected = 0;
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.selected = 0;
+    }
+
+    // Moves the highlight `by` matches, wrapping around the list.
+    pub fn select(&mut self, by: isize) {
+        let count = self.matches().len().min(SHOWN);
+        if count > 0 {
+            self.selected = (self.selected as isize + by).rem_euclid(count as isize) as usize;
+        }
+    }
+
+    pub fn matches(&self) -> Vec<&(String, T)> {
+        let mut scored: Vec<_> =
+            self.entries.iter().filter_map(|entry| Some((score(&self.query, &entry.0)?, entry))).collect();
+        scored.sort_by_key(|&(score, _)| score);
+        scored.into_iter().map(|(_, entry)| entry).collect()
+    }
+
+    // The highlighted entry, if anything matches.
+    pub fn chosen(&self) -> Option<T> {
+        self.matches().get(self.selected).map(|&&(_, entry)| entry)
+    }
+
+    // The query, then a line per match with the highlighted one marked.
+    pub fn text(&self) -> String {
+        let mut text = format!("> {}_", self.query);
+        for (i, (name, _)) in self.matches().into_iter().take(SHOWN).enumerate() {
+            text += &format!("\n{} {name}", if i == self.selected { '>' } else { ' ' });
+        }
+        text
+    }
+}
+
+// How well `name` matches `query`, lower being better: first the letters
+// matched that neither start a word nor follow the last letter matched, then
+// the letters skipped over. None unless it holds all of them in order.
+pub fn score(query: &str, name: &str) -> Option<(usize, usize)> {
+    let name: Vec<char> = name.chars().map(|ch| ch.to_ascii_lowercase()).collect();
+    let starts_word = |i: usize| i == 0 || name[i - 1] == ' ';
+    let (mut next, mut stray, mut skipped) = (0, 0, 0);
+    for wanted in query.chars().map(|ch| ch.to_ascii_lowercase()).filter(|ch| !ch.is_whitespace()) {
+        let mut found = (next..name.len()).filter(|&i| name[i] == wanted);
+        let at = match name.get(next) {
+            Some(&ch) if ch == wanted => next,
+            _ => found.clone().find(|&i| starts_word(i)).or_else(|| found.next())?,
+        };
+        if at != next && !starts_word(at) {
+            stray += 1;
+        }
+        skipped += at - next;
+        next = at + 1;
+    }
+    Some((stray, skipped))
+}
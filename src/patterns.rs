@@ -0,0 +1,38 @@
+use crate::Grid;
+
+// Well-known Life patterns for the stamp tool, as rows of 'O' for live
+// cells and '.' for dead ones.
+pub const LIBRARY: [(&str, &[&str]); 7] = [
+    ("glider", &[".O.", "..O", "OOO"]),
+    ("lwss", &[".O..O", "O....", "O...O", "OOOO."]),
+    ("mwss", &["...O..", ".O...O", "O.....", "O....O", "OOOOO."]),
+    ("hwss", &["...OO..", ".O....O", "O......", "O.....O", "OOOOOO."]),
+    ("r-pentomino", &[".OO", "OO.", ".O."]),
+    ("acorn", &[".O.....", "...O...", "OO..OOO"]),
+    (
+        "gosper glider gun",
+        &[
+            "........................O...........",
+            "......................O.O...........",
+            "............OO......OO............OO",
+            "...........O...O....OO............OO",
+            "OO........O.....O...OO..............",
+            "OO........O...O.OO....O.O...........",
+            "..........O.....O.......O...........",
+            "...........O...O....................",
+            "............OO......................",
+        ],
+    ),
+];
+
+// A grid as wide as the longest row, with a live cell for every 'O'.
+pub fn from_rows(rows: &[&str]) -> Grid {
+    let cols = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut grid = Grid::new(rows.len(), cols);
+    for (r, row) in rows.iter().enumerate() {
+        for (c, _) in row.bytes().enumerate().filter(|&(_, cell)| cell == b'O') {
+            grid.set(r, c, true);
+        }
+    }
+    grid
+}
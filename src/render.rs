@@ -0,0 +1,1863 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use pixels::Pixels;
+
+use crate::font;
+use crate::paint;
+use crate::gpu::CellPass;
+use crate::view::Viewport;
+use crate::{Error, Grid, Lattice, Memory, Population, Shade, Simulator};
+
+// The outline around a selection, the live cells of a pattern about to be
+// pasted, and the outline of the cell under the cursor in edit mode.
+pub const SELECTION: [u8; 4] = [0, 140, 255, 255];
+pub const GHOST: [u8; 4] = [90, 170, 255, 160];
+pub const CURSOR: [u8; 4] = [255, 200, 0, 255];
+// Lines between cells are left off cells narrower than this many pixels.
+const GRID_MIN_CELL: usize = 4;
+
+pub trait Renderer {
+    fn render(&mut self, sim: &dyn Simulator) -> Result<(), Error>;
+}
+
+pub fn lerp(from: [u8; 4], to: [u8; 4], t: f32) -> [u8; 4] {
+    let t = t.clamp(0.0, 1.0);
+    let mut out = [0; 4];
+    for i in 0..4 {
+        out[i] = (from[i] as f32 + (to[i] as f32 - from[i] as f32) * t).round() as u8;
+    }
+    out
+}
+
+// Fully saturated color at `t` of the way around the color wheel.
+pub fn hue(t: f32) -> [u8; 4] {
+    let h = t.rem_euclid(1.0) * 6.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    let (r, g, b) = match h as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    [(r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8, 255]
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Colormap {
+    Grayscale,
+    #[default]
+    Viridis,
+}
+
+const VIRIDIS: [[u8; 4]; 5] = [
+    [68, 1, 84, 255],
+    [59, 82, 139, 255],
+    [33, 145, 140, 255],
+    [94, 201, 98, 255],
+    [253, 231, 37, 255],
+];
+
+impl Colormap {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "gray" | "grey" | "grayscale" | "greyscale" => Some(Colormap::Grayscale),
+            "viridis" => Some(Colormap::Viridis),
+            _ => None,
+        }
+    }
+
+    pub fn color(self, value: f32) -> [u8; 4] {
+        let v = value.clamp(0.0, 1.0);
+        match self {
+            Colormap::Grayscale => {
+                let level = (v * 255.0).round() as u8;
+                [level, level, level, 255]
+            }
+            Colormap::Viridis => {
+                let scaled = v * (VIRIDIS.len() - 1) as f32;
+                let i = (scaled as usize).min(VIRIDIS.len() - 2);
+                lerp(VIRIDIS[i], VIRIDIS[i + 1], scaled - i as f32)
+            }
+        }
+    }
+}
+
+// The colors boards are drawn in, and the shape of their cells. Dying
+// cells go from the first dying color to the second, and the background
+// shows through wherever the frame falls outside every cell, such as the
+// corners around a hexagonal board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub background: [u8; 4],
+    pub alive: [u8; 4],
+    pub dead: [u8; 4],
+    pub dying: ([u8; 4], [u8; 4]),
+    pub grid_line: [u8; 4],
+    pub shape: CellShape,
+}
+
+impl Theme {
+    pub const LIGHT: Theme = Theme {
+        background: [200, 200, 200, 255],
+        alive: [0, 0, 0, 255],
+        dead: [255, 255, 255, 255],
+        dying: ([200, 0, 0, 255], [255, 210, 140, 255]),
+        grid_line: [215, 215, 215, 255],
+        shape: CellShape::Square,
+    };
+
+    pub const DARK: Theme = Theme {
+        background: [40, 40, 40, 255],
+        alive: [235, 235, 235, 255],
+        dead: [18, 18, 18, 255],
+        dying: ([230, 90, 20, 255], [70, 30, 10, 255]),
+        grid_line: [50, 50, 50, 255],
+        shape: CellShape::Rounded,
+    };
+
+    pub const TERMINAL: Theme = Theme {
+        background: [10, 10, 10, 255],
+        alive: [60, 255, 110, 255],
+        dead: [0, 0, 0, 255],
+        dying: ([0, 170, 70, 255], [0, 40, 20, 255]),
+        grid_line: [15, 40, 20, 255],
+        shape: CellShape::Dot,
+    };
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "light" => Some(Theme::LIGHT),
+            "dark" => Some(Theme::DARK),
+            "terminal" | "green" => Some(Theme::TERMINAL),
+            _ => None,
+        }
+    }
+
+    pub fn color(&self, shade: Shade) -> [u8; 4] {
+        match shade {
+            Shade::Dead => self.dead,
+            Shade::Alive => self.alive,
+            Shade::Dying(t) => lerp(self.dying.0, self.dying.1, t),
+            Shade::Rgba(rgba) => rgba,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::LIGHT
+    }
+}
+
+// How square-lattice cells are drawn within their squares: filling them, as
+// rounded squares or circles, or as dots half as wide, which keep cells
+// apart on boards drawn small.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CellShape {
+    #[default]
+    Square,
+    Rounded,
+    Circle,
+    Dot,
+}
+
+impl CellShape {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "square" => Some(CellShape::Square),
+            "rounded" => Some(CellShape::Rounded),
+            "circle" | "round" => Some(CellShape::Circle),
+            "dot" | "dots" => Some(CellShape::Dot),
+            _ => None,
+        }
+    }
+}
+
+// "RRGGBB" or "RRGGBBAA" in hex, with or without a leading '#'.
+pub fn parse_color(text: &str) -> Option<[u8; 4]> {
+    let hex = text.strip_prefix('#').unwrap_or(text);
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let mut rgba = [255; 4];
+    for (i, channel) in rgba.iter_mut().take(hex.len() / 2).enumerate() {
+        *channel = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(rgba)
+}
+
+// Pointy-top hexagons `cell_size` pixels across; rows sit 3/4 of a hexagon
+// apart and each row is shifted half a cell right of the one above.
+fn hex_radius(cell_size: usize) -> f32 {
+    cell_size as f32 / 3f32.sqrt()
+}
+
+// Equilateral triangles `cell_size` pixels wide at the base, each starting
+// half a base after the previous one in its row.
+fn triangle_height(cell_size: usize) -> f32 {
+    cell_size as f32 * 3f32.sqrt() / 2.0
+}
+
+// Pixel dimensions of the board drawn for `sim`.
+pub fn frame_size(sim: &dyn Simulator, cell_size: usize) -> (usize, usize) {
+    let (rows, cols) = sim.dimensions();
+    match sim.lattice() {
+        Lattice::Square => (cols * cell_size, rows * cell_size),
+        Lattice::Triangular => {
+            let height = rows as f32 * triangle_height(cell_size);
+            ((cols + 1) * cell_size / 2, height.ceil() as usize)
+        }
+        Lattice::Hexagonal => {
+            let radius = hex_radius(cell_size);
+            let width = cell_size as f32 * (cols as f32 + rows.saturating_sub(1) as f32 / 2.0);
+            let height = radius * (1.5 * rows.saturating_sub(1) as f32 + 2.0);
+            (width.ceil() as usize, height.ceil() as usize)
+        }
+    }
+}
+
+// The cell covering frame pixel (x, y), if any.
+pub fn cell_at_pixel(sim: &dyn Simulator, cell_size: usize, x: usize, y: usize) -> Option<(usize, usize)> {
+    lattice_cell_at(sim.lattice(), sim.dimensions(), cell_size, x, y)
+}
+
+// The same for any board of `rows` by `cols` cells on `lattice`.
+pub fn lattice_cell_at(lattice: Lattice, (rows, cols): (usize, usize), cell_size: usize, x: usize, y: usize) -> Option<(usize, usize)> {
+    let (row, col) = match lattice {
+        Lattice::Square => (y / cell_size, x / cell_size),
+        Lattice::Hexagonal => {
+            let radius = hex_radius(cell_size);
+            let px = x as f32 + 0.5 - cell_size as f32 / 2.0;
+            let py = y as f32 + 0.5 - radius;
+            let q = (3f32.sqrt() / 3.0 * px - py / 3.0) / radius;
+            let r = (2.0 / 3.0 * py) / radius;
+            // Round in cube coordinates, fixing up whichever axis moved most.
+            let (mut rq, mut rr, rs) = (q.round(), r.round(), (-q - r).round());
+            let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs + q + r).abs());
+            if dq > dr && dq > ds {
+                rq = -rr - rs;
+            } else if dr > ds {
+                rr = -rq - rs;
+            }
+            if rq < 0.0 || rr < 0.0 {
+                return None;
+            }
+            (rr as usize, rq as usize)
+        }
+        Lattice::Triangular => {
+            let height = triangle_height(cell_size);
+            let row = ((y as f32 + 0.5) / height) as usize;
+            // Each half-base strip is split by one sloped edge between the
+            // triangle starting there and the one starting a strip earlier.
+            let t = (y as f32 + 0.5) / height - row as f32;
+            let u = (x as f32 + 0.5) / (cell_size as f32 / 2.0);
+            let (strip, f) = (u as usize, u.fract());
+            let edge = if (row + strip).is_multiple_of(2) { 1.0 - t } else { t };
+            match (f >= edge, strip.checked_sub(1)) {
+                (true, _) => (row, strip),
+                (false, Some(col)) => (row, col),
+                (false, None) => return None,
+            }
+        }
+    };
+    (row < rows && col < cols).then_some((row, col))
+}
+
+pub fn draw_cells(sim: &dyn Simulator, frame: &mut [u8], cell_size: usize, theme: &Theme) {
+    paint_cells(sim, frame, cell_size, theme, |row, col| theme.color(sim.shade(row, col)));
+}
+
+// The same with each cell in whatever `color` gives it.
+fn paint_cells(sim: &dyn Simulator, frame: &mut [u8], cell_size: usize, theme: &Theme, color: impl Fn(usize, usize) -> [u8; 4]) {
+    if sim.lattice() == Lattice::Square {
+        let mask = Mask::new(theme.shape, cell_size, theme.dead);
+        return paint_square_cells(sim, frame, cell_size, mask.as_ref(), color);
+    }
+    let (width, _) = frame_size(sim, cell_size);
+    for (y, line) in frame.chunks_exact_mut(width * 4).enumerate() {
+        for (x, pixel) in line.chunks_exact_mut(4).enumerate() {
+            let color = match cell_at_pixel(sim, cell_size, x, y) {
+                Some((row, col)) => color(row, col),
+                None => theme.background,
+            };
+            pixel.copy_from_slice(&color);
+        }
+    }
+}
+
+// Paints each row of cells into its first pixel line and copies that line
+// down the rest of the row, rather than working out the cell under every
+// pixel. Shaped cells differ from line to line, so each line of them is
+// painted.
+fn paint_square_cells(
+    sim: &dyn Simulator,
+    frame: &mut [u8],
+    cell_size: usize,
+    mask: Option<&Mask>,
+    color: impl Fn(usize, usize) -> [u8; 4],
+) {
+    let (rows, cols) = sim.dimensions();
+    let line = cols * cell_size * 4;
+    if line == 0 {
+        return;
+    }
+    for (row, band) in frame.chunks_exact_mut(line * cell_size).take(rows).enumerate() {
+        if let Some(mask) = mask {
+            let colors: Vec<_> = (0..cols).map(|col| color(row, col)).collect();
+            for (y, pixels) in band.chunks_exact_mut(line).enumerate() {
+                for (run, &color) in pixels.chunks_exact_mut(cell_size * 4).zip(&colors) {
+                    mask.paint(run, y, color);
+                }
+            }
+            continue;
+        }
+        let (first, rest) = band.split_at_mut(line);
+        for (col, run) in first.chunks_exact_mut(cell_size * 4).enumerate() {
+            fill_run(run, color(row, col));
+        }
+        for copy in rest.chunks_exact_mut(line) {
+            copy.copy_from_slice(first);
+        }
+    }
+}
+
+fn fill_run(run: &mut [u8], color: [u8; 4]) {
+    for pixel in run.chunks_exact_mut(4) {
+        pixel.copy_from_slice(&color);
+    }
+}
+
+// Repaints one square-lattice cell: its top pixel line, then copies of it.
+fn fill_cell(frame: &mut [u8], width: usize, cell_size: usize, row: usize, col: usize, color: [u8; 4]) {
+    let (start, span) = ((row * cell_size * width + col * cell_size) * 4, cell_size * 4);
+    fill_run(&mut frame[start..start + span], color);
+    for y in 1..cell_size {
+        frame.copy_within(start..start + span, start + y * width * 4);
+    }
+}
+
+// The same for a shaped cell, line by line.
+fn fill_shaped_cell(frame: &mut [u8], width: usize, mask: &Mask, row: usize, col: usize, color: [u8; 4]) {
+    let size = mask.size;
+    for y in 0..size {
+        let start = ((row * size + y) * width + col * size) * 4;
+        mask.paint(&mut frame[start..start + size * 4], y, color);
+    }
+}
+
+// The pixels of a square cell `size` across that a shape covers, row by
+// row, and the color showing around it.
+struct Mask {
+    size: usize,
+    covered: Vec<bool>,
+    gap: [u8; 4],
+}
+
+impl Mask {
+    // None for plain squares, which cover the lot.
+    fn new(shape: CellShape, size: usize, gap: [u8; 4]) -> Option<Self> {
+        let half = size as f32 / 2.0;
+        // Rounded boxes: how far each corner is rounded, and how far in
+        // from the center the box reaches.
+        let (radius, reach) = match shape {
+            CellShape::Square => return None,
+            CellShape::Rounded => (half / 2.0, half),
+            CellShape::Circle => (half, half),
+            CellShape::Dot => {
+                let reach = (half / 2.0).max(0.5);
+                (0.0, reach)
+            }
+        };
+        let covered = (0..size * size)
+            .map(|i| {
+                let (y, x) = ((i / size) as f32 + 0.5, (i % size) as f32 + 0.5);
+                let dx = ((x - half).abs() - (reach - radius)).max(0.0);
+                let dy = ((y - half).abs() - (reach - radius)).max(0.0);
+                (x - half).abs() <= reach && (y - half).abs() <= reach && dx * dx + dy * dy <= radius * radius
+            })
+            .collect();
+        Some(Self { size, covered, gap })
+    }
+
+    // Paints line `y` of a cell into the `size` pixels of `run`.
+    fn paint(&self, run: &mut [u8], y: usize, color: [u8; 4]) {
+        let covered = &self.covered[y * self.size..(y + 1) * self.size];
+        for (pixel, &covered) in run.chunks_exact_mut(4).zip(covered) {
+            pixel.copy_from_slice(if covered { &color } else { &self.gap });
+        }
+    }
+}
+
+// Lays `color` over a cell as far as its alpha says.
+fn blend_cell(frame: &mut [u8], width: usize, cell_size: usize, row: usize, col: usize, color: [u8; 4]) {
+    let alpha = color[3] as f32 / 255.0;
+    for y in row * cell_size..(row + 1) * cell_size {
+        let start = (y * width + col * cell_size) * 4;
+        for pixel in frame[start..start + cell_size * 4].chunks_exact_mut(4) {
+            let under = [pixel[0], pixel[1], pixel[2], 255];
+            pixel.copy_from_slice(&lerp(under, [color[0], color[1], color[2], 255], alpha));
+        }
+    }
+}
+
+pub const GRAPH_BACKGROUND: [u8; 4] = [24, 24, 24, 255];
+// The live-cell chart in the bottom-left corner, one generation a pixel.
+pub const CHART_LINE: [u8; 4] = [110, 230, 120, 255];
+const CHART_SIZE: (usize, usize) = (200, 60);
+
+// Rolling population history drawn as one line per series, one sample per
+// pixel column, scaled to the largest count still on screen.
+pub struct PopulationGraph {
+    width: usize,
+    height: usize,
+    series: Vec<(&'static str, [u8; 4])>,
+    history: VecDeque<Vec<usize>>,
+}
+
+impl PopulationGraph {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            series: Vec::new(),
+            history: VecDeque::with_capacity(width),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // Keeps the most recent samples that still fit.
+    pub fn set_width(&mut self, width: usize) {
+        self.width = width;
+        while self.history.len() > width {
+            self.history.pop_front();
+        }
+    }
+
+    pub fn record(&mut self, populations: &[Population]) {
+        if self.series.len() != populations.len() {
+            self.series = populations.iter().map(|p| (p.name, p.color)).collect();
+            self.history.clear();
+        }
+        if self.history.len() == self.width {
+            self.history.pop_front();
+        }
+        self.history.push_back(populations.iter().map(|p| p.count).collect());
+    }
+
+    // Draws into a frame region exactly `width` by `height` pixels.
+    pub fn draw(&self, frame: &mut [u8]) {
+        self.draw_at(frame, self.width, (0, 0));
+    }
+
+    // Draws with its top-left corner at `at` in a frame `stride` pixels wide.
+    pub fn draw_at(&self, frame: &mut [u8], stride: usize, (x0, y0): (usize, usize)) {
+        font::fill_rect(frame, stride, (x0, y0), (self.width, self.height), GRAPH_BACKGROUND);
+        let peak = self.history.iter().flatten().copied().max().unwrap_or(0).max(1);
+        let y = |count: usize| (self.height - 1) - count * (self.height - 1) / peak;
+        for (i, (_, color)) in self.series.iter().enumerate() {
+            let mut previous = None;
+            for (x, sample) in self.history.iter().enumerate() {
+                let current = y(sample[i]);
+                let (top, bottom) = match previous {
+                    Some(prev) => (current.min(prev), current.max(prev)),
+                    None => (current, current),
+                };
+                font::fill_rect(frame, stride, (x0 + x, y0 + top), (1, bottom - top + 1), *color);
+                previous = Some(current);
+            }
+        }
+    }
+}
+
+// Ways of coloring the board by something other than each cell's state, on
+// Viridis. Neighbors goes from none to eight live neighbors, and simulators
+// that don't count them are drawn as usual; activity goes from cells that
+// have never changed to the cell that's changed most.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Heatmap {
+    Neighbors,
+    Activity,
+}
+
+impl Heatmap {
+    // The next one along, or None after the last.
+    pub fn next(heatmap: Option<Self>) -> Option<Self> {
+        match heatmap {
+            None => Some(Heatmap::Neighbors),
+            Some(Heatmap::Neighbors) => Some(Heatmap::Activity),
+            Some(Heatmap::Activity) => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Heatmap::Neighbors => "neighbors",
+            Heatmap::Activity => "activity",
+        }
+    }
+
+    // The color of `cell` on the view `sim`, which is `board_cell` on the
+    // board, if there's one to give.
+    fn color(
+        self,
+        sim: &dyn Simulator,
+        (row, col): (usize, usize),
+        activity: &Activity,
+        board_cell: (usize, usize),
+    ) -> Option<[u8; 4]> {
+        let value = match self {
+            Heatmap::Neighbors => sim.neighbors(row, col)? as f32 / 8.0,
+            Heatmap::Activity => activity.level(board_cell),
+        };
+        Some(Colormap::Viridis.color(value))
+    }
+}
+
+// How many times each cell of the board has changed over the run.
+pub struct Activity {
+    dimensions: (usize, usize),
+    counts: Vec<u32>,
+    most: u32,
+}
+
+impl Activity {
+    pub fn new() -> Self {
+        Self {
+            dimensions: (0, 0),
+            counts: Vec::new(),
+            most: 0,
+        }
+    }
+
+    // Counts `changes` on a board of `dimensions`, starting over if the
+    // board's changed size.
+    pub fn record(&mut self, dimensions: (usize, usize), changes: &[(usize, usize)]) {
+        if dimensions != self.dimensions {
+            *self = Self::new();
+            self.dimensions = dimensions;
+            self.counts = vec![0; dimensions.0 * dimensions.1];
+        }
+        // Changes can come from a board that's since been resized.
+        for &(row, col) in changes.iter().filter(|&&(row, col)| row < dimensions.0 && col < dimensions.1) {
+            let count = &mut self.counts[row * dimensions.1 + col];
+            *count = count.saturating_add(1);
+            self.most = self.most.max(*count);
+        }
+    }
+
+    // From 0 for a cell that's never changed to 1 for the busiest, on a log
+    // scale so that a few busy cells don't wash out the rest.
+    pub fn level(&self, (row, col): (usize, usize)) -> f32 {
+        if row >= self.dimensions.0 || col >= self.dimensions.1 || self.most == 0 {
+            return 0.0;
+        }
+        let count = self.counts[row * self.dimensions.1 + col];
+        (count as f32).ln_1p() / (self.most as f32).ln_1p()
+    }
+}
+
+impl Default for Activity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Live cells colored by age go from NEWBORN to OLD over their first
+// AGE_SPAN generations. Cells that die fade from TRAIL to the theme's dead
+// color over TRAIL_SPAN.
+pub const NEWBORN: [u8; 4] = [255, 180, 20, 255];
+pub const OLD: [u8; 4] = [40, 10, 80, 255];
+const AGE_SPAN: f32 = 100.0;
+pub const TRAIL: [u8; 4] = [110, 140, 230, 255];
+const TRAIL_SPAN: u32 = 12;
+// Cells that were born or died in the last generation, when those are shown.
+pub const BORN: [u8; 4] = [40, 200, 60, 255];
+pub const DIED: [u8; 4] = [220, 40, 40, 255];
+
+// How many generations each cell of the board has gone without changing,
+// counted from when tracking started, and whether it has changed at all
+// since, which tells a cell that just died from one that was never alive.
+pub struct Ages {
+    dimensions: (usize, usize),
+    ages: Vec<u32>,
+    changed: Vec<bool>,
+}
+
+impl Ages {
+    pub fn new() -> Self {
+        Self {
+            dimensions: (0, 0),
+            ages: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    // Ages every cell on a board of `dimensions` by a generation, bar the
+    // ones in `changes`, which start again; None starts them all again.
+    pub fn step(&mut self, dimensions: (usize, usize), changes: Option<&[(usize, usize)]>) {
+        for age in &mut self.ages {
+            *age = age.saturating_add(1);
+        }
+        self.restart(dimensions, changes);
+    }
+
+    // Starts the cells in `changes` again without aging the rest, as after
+    // an edit.
+    pub fn restart(&mut self, dimensions: (usize, usize), changes: Option<&[(usize, usize)]>) {
+        match changes {
+            Some(changes) if dimensions == self.dimensions => {
+                for &(row, col) in changes.iter().filter(|&&(row, col)| row < dimensions.0 && col < dimensions.1) {
+                    self.ages[row * dimensions.1 + col] = 0;
+                    self.changed[row * dimensions.1 + col] = true;
+                }
+            }
+            _ => {
+                self.dimensions = dimensions;
+                self.ages = vec![0; dimensions.0 * dimensions.1];
+                self.changed = vec![false; dimensions.0 * dimensions.1];
+            }
+        }
+    }
+
+    // Generations the cell has gone unchanged, and whether it ever has.
+    pub fn age(&self, (row, col): (usize, usize)) -> (u32, bool) {
+        if row < self.dimensions.0 && col < self.dimensions.1 {
+            let i = row * self.dimensions.1 + col;
+            (self.ages[i], self.changed[i])
+        } else {
+            (0, false)
+        }
+    }
+
+    // The color in `theme` of a cell shaded `shade`, live ones going by
+    // their age if `by_age` and dead ones fading out if `trails`, unless
+    // `births` and it was born or died in the last generation.
+    pub fn color(
+        &self,
+        cell: (usize, usize),
+        shade: Shade,
+        theme: &Theme,
+        (by_age, trails, births): (bool, bool, bool),
+    ) -> [u8; 4] {
+        let (age, changed) = self.age(cell);
+        match shade {
+            Shade::Alive if births && changed && age == 0 => BORN,
+            Shade::Dead if births && changed && age == 0 => DIED,
+            Shade::Alive if by_age => lerp(NEWBORN, OLD, age as f32 / AGE_SPAN),
+            Shade::Dead if trails && changed && age < TRAIL_SPAN => {
+                lerp(TRAIL, theme.dead, age as f32 / TRAIL_SPAN as f32)
+            }
+            shade => theme.color(shade),
+        }
+    }
+}
+
+impl Default for Ages {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub const OVERLAY_BACKGROUND: [u8; 4] = [0, 0, 0, 255];
+pub const OVERLAY_TEXT: [u8; 4] = [255, 255, 255, 255];
+const OVERLAY_SCALE: usize = 2;
+
+// Where the last frame's time went, how many generations ran over the last
+// second, and how much the simulator has allocated.
+pub struct Profile {
+    step: Duration,
+    draw: Duration,
+    present: Duration,
+    // Step time since the last frame was drawn.
+    pending_step: Duration,
+    rate: Rate,
+    memory: usize,
+}
+
+// How many of something happen a second, worked out afresh every second.
+struct Rate {
+    count: usize,
+    since: Instant,
+    per_second: f64,
+}
+
+impl Rate {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            since: Instant::now(),
+            per_second: 0.0,
+        }
+    }
+
+    fn add(&mut self, count: usize) {
+        self.count += count;
+        let elapsed = self.since.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            self.per_second = self.count as f64 / elapsed.as_secs_f64();
+            self.count = 0;
+            self.since = Instant::now();
+        }
+    }
+}
+
+impl Profile {
+    pub fn new() -> Self {
+        Self {
+            step: Duration::ZERO,
+            draw: Duration::ZERO,
+            present: Duration::ZERO,
+            pending_step: Duration::ZERO,
+            rate: Rate::new(),
+            memory: 0,
+        }
+    }
+
+    // Notes `generations` steps that took `took` altogether.
+    pub fn record_step(&mut self, took: Duration, generations: usize) {
+        self.pending_step += took;
+        self.rate.add(generations);
+    }
+
+    // Total bytes in the simulator's buffers, left off the overlay while 0.
+    pub fn set_memory(&mut self, bytes: usize) {
+        self.memory = bytes;
+    }
+
+    pub fn text(&self) -> String {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let mut text = format!(
+            "step {:.2} ms\ndraw {:.2} ms\npresent {:.2} ms\n{:.1} gen/s",
+            ms(self.step),
+            ms(self.draw),
+            ms(self.present),
+            self.rate.per_second
+        );
+        if self.memory > 0 {
+            text.push_str(&format!("\nmemory {:.1} MiB", mebibytes(self.memory)));
+        }
+        text
+    }
+
+    // Draws the timings over the top-left corner of a frame `width` pixels
+    // wide and returns the size of the box they take up.
+    fn draw(&self, frame: &mut [u8], width: usize) -> (usize, usize) {
+        let text = self.text();
+        let (w, h) = font::text_size(&text, OVERLAY_SCALE);
+        let pad = OVERLAY_SCALE * 2;
+        let size = (w + 2 * pad, h + 2 * pad);
+        font::fill_rect(frame, width, (0, 0), size, OVERLAY_BACKGROUND);
+        font::draw_text(frame, width, pad, pad, &text, OVERLAY_SCALE, OVERLAY_TEXT);
+        size
+    }
+}
+
+// The generation, live cells, and generations and frames a second, over the
+// top-right corner of the board.
+pub struct Hud {
+    generations: Rate,
+    frames: Rate,
+}
+
+impl Hud {
+    pub fn new() -> Self {
+        Self {
+            generations: Rate::new(),
+            frames: Rate::new(),
+        }
+    }
+
+    pub fn record_step(&mut self, generations: usize) {
+        self.generations.add(generations);
+    }
+
+    pub fn text(&self, sim: &dyn Simulator) -> String {
+        let mut text = String::new();
+        if let Some(generation) = sim.generation() {
+            text += &format!("gen {generation}\n");
+        }
+        text += &format!(
+            "pop {}\n{:.1} gen/s\n{:.0} fps",
+            sim.population(),
+            self.generations.per_second,
+            self.frames.per_second
+        );
+        text
+    }
+
+    // Counts a frame and draws over a frame `width` pixels wide, returning
+    // where the box went and its size.
+    fn draw(&mut self, frame: &mut [u8], width: usize, sim: &dyn Simulator) -> ((usize, usize), (usize, usize)) {
+        self.frames.add(1);
+        let text = self.text(sim);
+        let (w, h) = font::text_size(&text, OVERLAY_SCALE);
+        let pad = OVERLAY_SCALE * 2;
+        let size = ((w + 2 * pad).min(width), h + 2 * pad);
+        let at = (width - size.0, 0);
+        font::fill_rect(frame, width, at, size, OVERLAY_BACKGROUND);
+        font::draw_text(frame, width, at.0 + pad, pad, &text, OVERLAY_SCALE, OVERLAY_TEXT);
+        (at, size)
+    }
+}
+
+impl Default for Hud {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn mebibytes(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct PixelsRenderer {
+    pixels: Pixels,
+    cell_size: usize,
+    graph: Option<PopulationGraph>,
+    // Cells to repaint on the next render, or None to repaint everything.
+    dirty: Option<Vec<(usize, usize)>>,
+    profile: Option<Profile>,
+    gpu: bool,
+    // Built on first use for the board size at the time.
+    cells: Option<CellPass>,
+    // Size of the overlay box drawn last frame.
+    overlay: (usize, usize),
+    hud: Option<Hud>,
+    // Where the HUD's box went last frame, and its size.
+    hud_box: ((usize, usize), (usize, usize)),
+    // Drawn last frame, if the view didn't cover the board.
+    minimap: Option<Minimap>,
+    chart: Option<PopulationGraph>,
+    viewport: Viewport,
+    split: Option<Split>,
+    glide: Glide,
+    // Where the right half of a split screen starts across the frame.
+    split_at: usize,
+    // Size and lattice of the board last seen, for mapping cells into the
+    // viewport between renders.
+    board: ((usize, usize), Lattice),
+    marks: Marks,
+    // The command palette's text, while it's open.
+    palette: Option<String>,
+    theme: Theme,
+    grid_lines: bool,
+    glow: bool,
+    age_colors: bool,
+    trails: bool,
+    births: bool,
+    // Kept only while cells are colored by age, leave trails or show births
+    // and deaths.
+    ages: Option<Ages>,
+    heatmap: Option<Heatmap>,
+    // Counted from the start, whether or not its heatmap is showing.
+    activity: Activity,
+}
+
+// Drawn over square boards: the selected rectangle's corners, a pattern
+// with where its top-left corner would be pasted, and the cursor's cell.
+#[derive(Default)]
+struct Marks {
+    selection: Option<((usize, usize), (usize, usize))>,
+    ghost: Option<(Grid, (usize, usize))>,
+    cursor: Option<(usize, usize)>,
+}
+
+impl Marks {
+    fn is_empty(&self) -> bool {
+        self.selection.is_none() && self.ghost.is_none() && self.cursor.is_none()
+    }
+
+    // Draws them over the frame of `viewport` onto a board of `dimensions`.
+    // With `gpu` the frame is transparent over the cells and the ghost is
+    // left for the shader to blend; otherwise it's blended here.
+    fn draw(&self, frame: &mut [u8], width: usize, size: usize, viewport: Viewport, dimensions: (usize, usize), gpu: bool) {
+        let lattice = Lattice::Square;
+        if let Some((pattern, (row, col))) = &self.ghost {
+            for (r, c) in pattern.live_cells() {
+                if let Some((r, c)) = viewport.to_view(dimensions, lattice, (row + r, col + c)) {
+                    if gpu {
+                        fill_cell(frame, width, size, r, c, GHOST);
+                    } else {
+                        blend_cell(frame, width, size, r, c, GHOST);
+                    }
+                }
+            }
+        }
+        if let Some(corners) = self.selection {
+            outline(frame, width, size, viewport.region(dimensions, lattice).0, corners, SELECTION);
+        }
+        if let Some(cell) = self.cursor {
+            outline(frame, width, size, viewport.region(dimensions, lattice).0, (cell, cell), CURSOR);
+        }
+    }
+}
+
+// Outlines the cells between two corners, top-left first, in a frame
+// showing the board from `origin` on, which they may start above or left of.
+fn outline(
+    frame: &mut [u8],
+    width: usize,
+    size: usize,
+    (top, left): (usize, usize),
+    ((r0, c0), (r1, c1)): ((usize, usize), (usize, usize)),
+    color: [u8; 4],
+) {
+    let pixel = |cell: usize, origin: usize| (cell as isize - origin as isize) * size as isize;
+    let (y0, x0) = (pixel(r0, top), pixel(c0, left));
+    let (y1, x1) = (pixel(r1 + 1, top) - 1, pixel(c1 + 1, left) - 1);
+    let (from_x, from_y) = (x0.max(0), y0.max(0));
+    if x1 < from_x || y1 < from_y {
+        return;
+    }
+    let (w, h) = ((x1 - from_x + 1) as usize, (y1 - from_y + 1) as usize);
+    for (x, y, w, h) in [(from_x, y0, w, 1), (from_x, y1, w, 1), (x0, from_y, 1, h), (x1, from_y, 1, h)] {
+        if x >= 0 && y >= 0 {
+            font::fill_rect(frame, width, (x as usize, y as usize), (w, h), color);
+        }
+    }
+}
+
+// Most pixels the minimap takes up either way, and the outline of the view
+// drawn on it.
+const MINIMAP_SIZE: usize = 120;
+pub const MINIMAP_VIEW: [u8; 4] = [255, 60, 60, 255];
+
+// The whole board shrunk into the bottom-right corner of the frame, `span`
+// cells to a pixel either way, for while the view shows only part of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Minimap {
+    at: (usize, usize),
+    size: (usize, usize),
+    span: usize,
+}
+
+impl Minimap {
+    // For a board of `dimensions` on a frame `width` by `height` pixels,
+    // taking up no more than a third of it either way.
+    fn new((rows, cols): (usize, usize), (width, height): (usize, usize)) -> Self {
+        let (most_x, most_y) = (MINIMAP_SIZE.min(width / 3).max(1), MINIMAP_SIZE.min(height / 3).max(1));
+        let span = rows.div_ceil(most_y).max(cols.div_ceil(most_x)).max(1);
+        let size = (cols.div_ceil(span), rows.div_ceil(span));
+        let at = (width.saturating_sub(size.0 + 2), height.saturating_sub(size.1 + 2));
+        Self { at, size, span }
+    }
+
+    // Draws the board with a border around it and the cells in `view`, a
+    // corner and size, outlined.
+    fn draw(&self, frame: &mut [u8], width: usize, sim: &dyn Simulator, theme: &Theme, view: ((usize, usize), (usize, usize))) {
+        let (x, y) = self.at;
+        let (rows, cols) = sim.dimensions();
+        let border = (x.saturating_sub(1), y.saturating_sub(1));
+        font::fill_rect(frame, width, border, (self.size.0 + 2, self.size.1 + 2), OVERLAY_BACKGROUND);
+        for py in 0..self.size.1 {
+            for px in 0..self.size.0 {
+                let (row, col) = ((py * self.span).min(rows - 1), (px * self.span).min(cols - 1));
+                font::fill_rect(frame, width, (x + px, y + py), (1, 1), theme.color(sim.shade(row, col)));
+            }
+        }
+        let ((top, left), (down, across)) = view;
+        let (x0, y0) = (x + left / self.span, y + top / self.span);
+        let (x1, y1) = (x + (left + across - 1) / self.span, y + (top + down - 1) / self.span);
+        let (w, h) = (x1 - x0 + 1, y1 - y0 + 1);
+        for (at, size) in [((x0, y0), (w, 1)), ((x0, y1), (w, 1)), ((x0, y0), (1, h)), ((x1, y0), (1, h))] {
+            font::fill_rect(frame, width, at, size, MINIMAP_VIEW);
+        }
+    }
+
+    // The cell under frame pixel (x, y), if it's on the minimap.
+    fn cell_at(&self, (x, y): (usize, usize)) -> Option<(usize, usize)> {
+        let (px, py) = (x.checked_sub(self.at.0)?, y.checked_sub(self.at.1)?);
+        (px < self.size.0 && py < self.size.1).then_some((py * self.span, px * self.span))
+    }
+}
+
+// How much of the way to where it's headed the view goes each frame.
+const EASE: f32 = 0.3;
+
+// `EASE` of the way from `from` to `to`, and at least one further, so
+// steps shrink as they near it.
+fn ease(from: usize, to: usize) -> usize {
+    let step = ((from.abs_diff(to) as f32 * EASE).ceil() as usize).max(1);
+    if from < to { (from + step).min(to) } else { from.saturating_sub(step).max(to) }
+}
+
+// Where the view's headed: the cell size it's zooming to, keeping still the
+// cell under a window position or the middle one, and the board cell it's
+// bringing to the middle.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Glide {
+    zoom: Option<(usize, Option<(f32, f32)>)>,
+    center: Option<(usize, usize)>,
+}
+
+// Pixels between the halves of a split screen.
+const SPLIT_GAP: usize = 4;
+
+// The half of a split screen not being pointed at: what it shows, how big
+// it draws cells and whether it's the left one. The renderer's own viewport
+// and cell size are the other half's, which panning and zooming move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Split {
+    viewport: Viewport,
+    cell_size: usize,
+    left: bool,
+}
+
+// The most cells down and across `viewport` can show of `sim` in `width` by
+// `height` pixels.
+fn fitted(viewport: Viewport, sim: &dyn Simulator, cell_size: usize, (width, height): (usize, usize)) -> (usize, usize) {
+    let mut size = ((height / cell_size).max(1), (width / cell_size).max(1));
+    // Hexagons and triangles reach a little past their squares.
+    let frame = |size| {
+        let mut viewport = viewport;
+        viewport.set_size(size);
+        viewport.frame_size(sim, cell_size)
+    };
+    while size.1 > 1 && frame(size).0 > width {
+        size.1 -= 1;
+    }
+    while size.0 > 1 && frame(size).1 > height {
+        size.0 -= 1;
+    }
+    size
+}
+
+// Lines between square cells `cell_size` pixels across.
+fn draw_grid(frame: &mut [u8], (width, height): (usize, usize), cell_size: usize, color: [u8; 4]) {
+    for x in (cell_size..width).step_by(cell_size) {
+        font::fill_rect(frame, width, (x, 0), (1, height), color);
+    }
+    for y in (cell_size..height).step_by(cell_size) {
+        font::fill_rect(frame, width, (0, y), (width, 1), color);
+    }
+}
+
+// How much of the light blurred out of cells is added back over the frame.
+const GLOW_STRENGTH: f32 = 0.8;
+
+// Gives whatever's drawn brighter than `background` a soft halo about
+// `radius` pixels across: what it adds to the background, blurred by a box
+// twice each way, is added back on top.
+fn glow(frame: &mut [u8], (width, height): (usize, usize), background: [u8; 4], radius: usize) {
+    let mut light: Vec<[f32; 3]> = frame
+        .chunks_exact(4)
+        .map(|pixel| std::array::from_fn(|i| pixel[i].saturating_sub(background[i]) as f32))
+        .collect();
+    let mut line = Vec::new();
+    for _ in 0..2 {
+        for y in 0..height {
+            blur_line(&mut light, (y * width, 1), width, radius, &mut line);
+        }
+        for x in 0..width {
+            blur_line(&mut light, (x, width), height, radius, &mut line);
+        }
+    }
+    for (pixel, light) in frame.chunks_exact_mut(4).zip(light) {
+        for i in 0..3 {
+            pixel[i] = (pixel[i] as f32 + light[i] * GLOW_STRENGTH).min(255.0) as u8;
+        }
+    }
+}
+
+// Averages each of the `len` values from `start` on, `step` apart, with
+// those up to `radius` either side of it, using `line` as scratch space.
+fn blur_line(values: &mut [[f32; 3]], (start, step): (usize, usize), len: usize, radius: usize, line: &mut Vec<[f32; 3]>) {
+    line.clear();
+    line.extend((0..len).map(|i| values[start + i * step]));
+    let mut sum = [0.0; 3];
+    let add = |sum: &mut [f32; 3], value: [f32; 3], sign: f32| {
+        for i in 0..3 {
+            sum[i] += value[i] * sign;
+        }
+    };
+    for &value in &line[..radius.min(len)] {
+        add(&mut sum, value, 1.0);
+    }
+    let across = (2 * radius + 1) as f32;
+    for i in 0..len {
+        if i + radius < len {
+            add(&mut sum, line[i + radius], 1.0);
+        }
+        if i > radius {
+            add(&mut sum, line[i - radius - 1], -1.0);
+        }
+        values[start + i * step] = sum.map(|total| total / across);
+    }
+}
+
+// Where the GPU pass leaves the cells showing through the frame.
+const CLEAR: [u8; 4] = [0; 4];
+
+impl PixelsRenderer {
+    pub fn new(pixels: Pixels, cell_size: usize) -> Self {
+        Self {
+            pixels,
+            cell_size,
+            graph: None,
+            dirty: None,
+            profile: None,
+            gpu: false,
+            cells: None,
+            overlay: (0, 0),
+            hud: None,
+            hud_box: ((0, 0), (0, 0)),
+            minimap: None,
+            chart: None,
+            viewport: Viewport::whole(),
+            split: None,
+            glide: Glide::default(),
+            split_at: 0,
+            board: ((0, 0), Lattice::Square),
+            marks: Marks::default(),
+            palette: None,
+            theme: Theme::default(),
+            grid_lines: false,
+            glow: false,
+            age_colors: false,
+            trails: false,
+            births: false,
+            ages: None,
+            heatmap: None,
+            activity: Activity::new(),
+        }
+    }
+
+    // Shows only the part of the board in `viewport`.
+    pub fn with_viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    // Outlines the rectangle between two cells, or nothing.
+    pub fn set_selection(&mut self, selection: Option<((usize, usize), (usize, usize))>) {
+        self.marks.selection = selection.map(|(a, b)| paint::bounds(a, b));
+        self.invalidate();
+    }
+
+    // Shows `pattern` where it would land if pasted at `at`, or nothing.
+    pub fn set_ghost(&mut self, ghost: Option<(Grid, (usize, usize))>) {
+        self.marks.ghost = ghost;
+        self.invalidate();
+    }
+
+    // Outlines the cell under the cursor, or nothing.
+    pub fn set_cursor(&mut self, cell: Option<(usize, usize)>) {
+        if self.marks.cursor != cell {
+            self.marks.cursor = cell;
+            self.invalidate();
+        }
+    }
+
+    // Board cell under a physical window position on the board last
+    // drawn, for input handled away from the simulator.
+    pub fn board_cell_at(&self, position: (f32, f32)) -> Option<(usize, usize)> {
+        let (x, y) = self.pixel_at(position)?;
+        let (dimensions, lattice) = self.board;
+        self.viewport.cell_at(dimensions, lattice, self.cell_size, x, y)
+    }
+
+    // The board cell under a physical window position on the minimap, if
+    // it's showing and the position's on it.
+    pub fn minimap_cell_at(&self, position: (f32, f32)) -> Option<(usize, usize)> {
+        self.minimap?.cell_at(self.pixels.window_pos_to_pixel(position).ok()?)
+    }
+
+    // Shows a second view of the board beside the first, starting out the
+    // same, or goes back to showing one. Either way the window wants fitting
+    // again.
+    pub fn toggle_split(&mut self) {
+        self.split = match self.split {
+            Some(_) => None,
+            None => Some(Split {
+                viewport: self.viewport,
+                cell_size: self.cell_size,
+                left: false,
+            }),
+        };
+        self.invalidate();
+    }
+
+    // Makes the half of a split screen under a physical window position the
+    // one panned and zoomed, and returns whether that changed which it is.
+    pub fn focus(&mut self, position: (f32, f32)) -> bool {
+        let (Some(split), Ok((x, _))) = (&mut self.split, self.pixels.window_pos_to_pixel(position)) else {
+            return false;
+        };
+        let on_left = if x + SPLIT_GAP < self.split_at {
+            true
+        } else if x >= self.split_at {
+            false
+        } else {
+            return false;
+        };
+        if on_left != split.left {
+            return false;
+        }
+        std::mem::swap(&mut self.viewport, &mut split.viewport);
+        std::mem::swap(&mut self.cell_size, &mut split.cell_size);
+        split.left = !split.left;
+        self.glide = Glide::default();
+        true
+    }
+
+    // Heads for a view with `cell` in the middle, as near as the board
+    // allows, over the next few frames.
+    pub fn glide_to(&mut self, cell: (usize, usize)) {
+        self.glide.center = Some(cell);
+    }
+
+    // Heads for a view moved by whole cells from where it's headed already.
+    pub fn glide(&mut self, (down, right): (isize, isize)) {
+        let (rows, cols) = self.board.0;
+        let (row, col) = self.glide.center.unwrap_or_else(|| self.middle());
+        self.glide.center = Some((
+            row.saturating_add_signed(down).min(rows.saturating_sub(1)),
+            col.saturating_add_signed(right).min(cols.saturating_sub(1)),
+        ));
+    }
+
+    // Heads for cells `cell_size` pixels across, keeping still the cell
+    // under the physical window position `pivot`, or the middle one.
+    pub fn zoom_to(&mut self, cell_size: usize, pivot: Option<(f32, f32)>) {
+        self.glide.zoom = Some((cell_size.max(1), pivot));
+        if pivot.is_some() {
+            self.glide.center = None;
+        }
+    }
+
+    // Heads for the biggest cells, up to `most` pixels across, that show
+    // every live cell in a window `window` frame pixels in size, with them in
+    // the middle, and returns that cell size. None if nothing's alive.
+    pub fn frame_pattern(&mut self, sim: &dyn Simulator, (width, height): (usize, usize), most: usize) -> Option<usize> {
+        let (rows, cols) = sim.dimensions();
+        let live = (0..rows).flat_map(|row| (0..cols).map(move |col| (row, col))).filter(|&(row, col)| sim.cell(row, col));
+        let ((top, left), (bottom, right)) = live.fold(None, |bounds, (row, col)| match bounds {
+            None => Some(((row, col), (row, col))),
+            Some(((top, left), (bottom, right))) => {
+                Some(((top.min(row), left.min(col)), (bottom.max(row), right.max(col))))
+            }
+        })?;
+        let height = height.saturating_sub(self.graph.as_ref().map_or(0, PopulationGraph::height));
+        let width = if self.split.is_some() { width.saturating_sub(SPLIT_GAP) / 2 } else { width };
+        let cell_size = (width / (right - left + 1)).min(height / (bottom - top + 1)).clamp(1, most.max(1));
+        self.glide = Glide {
+            zoom: Some((cell_size, None)),
+            center: Some(((top + bottom) / 2, (left + right) / 2)),
+        };
+        Some(cell_size)
+    }
+
+    // Zooms and moves the view a step further toward where it's headed, in a
+    // window `window` frame pixels in size, and returns whether it has
+    // further to go.
+    pub fn animate(&mut self, sim: &dyn Simulator, window: (usize, usize)) -> Result<bool, Error> {
+        self.board = (sim.dimensions(), sim.lattice());
+        if let Some((cell_size, pivot)) = self.glide.zoom {
+            let step = ease(self.cell_size, cell_size);
+            self.zoom(sim, step, pivot, window)?;
+            if step == cell_size {
+                self.glide.zoom = None;
+            }
+        }
+        if let Some((row, col)) = self.glide.center {
+            let (from, before) = (self.middle(), self.viewport);
+            let to = (ease(from.0, row), ease(from.1, col));
+            self.pan((to.0 as isize - from.0 as isize, to.1 as isize - from.1 as isize));
+            // Done once there, or once the board's edge holds the view back.
+            if to == (row, col) || self.viewport == before {
+                self.glide.center = None;
+            }
+        }
+        Ok(self.glide != Glide::default())
+    }
+
+    // The board cell in the middle of the view.
+    fn middle(&self) -> (usize, usize) {
+        let (dimensions, lattice) = self.board;
+        let ((top, left), (rows, cols)) = self.viewport.region(dimensions, lattice);
+        (top + rows / 2, left + cols / 2)
+    }
+
+    // Moves the viewport by whole cells, as far as the board allows.
+    pub fn pan(&mut self, by: (isize, isize)) {
+        let (dimensions, lattice) = self.board;
+        let before = self.viewport;
+        self.viewport.pan(dimensions, lattice, by);
+        if self.viewport != before {
+            self.invalidate();
+        }
+    }
+
+    // Draws square-lattice boards with a shader instead of on the CPU.
+    pub fn with_gpu(mut self, gpu: bool) -> Self {
+        self.gpu = gpu;
+        self
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    // Draws lines between the cells of square boards, cells big enough.
+    pub fn with_grid_lines(mut self, grid_lines: bool) -> Self {
+        self.grid_lines = grid_lines;
+        self
+    }
+
+    pub fn toggle_grid_lines(&mut self) {
+        self.grid_lines = !self.grid_lines;
+        self.invalidate();
+    }
+
+    // Blurs a halo of light around live cells, which shows up best on a
+    // dark background.
+    pub fn with_glow(mut self, glow: bool) -> Self {
+        self.glow = glow;
+        self
+    }
+
+    // Colors live cells by how many generations they've been alive, timed
+    // from when it's turned on.
+    pub fn toggle_ages(&mut self) {
+        self.age_colors = !self.age_colors;
+        self.track_ages();
+    }
+
+    // Fades cells out over the generations after they die.
+    pub fn with_trails(mut self, trails: bool) -> Self {
+        self.trails = trails;
+        self.track_ages();
+        self
+    }
+
+    pub fn toggle_trails(&mut self) {
+        self.trails = !self.trails;
+        self.track_ages();
+    }
+
+    // Shows cells born in the last generation in BORN and ones that died in
+    // DIED, until the next.
+    pub fn toggle_births(&mut self) {
+        self.births = !self.births;
+        self.track_ages();
+    }
+
+    // Switches to the next heatmap, or back to cell states after the last,
+    // and returns the one showing.
+    pub fn next_heatmap(&mut self) -> Option<Heatmap> {
+        self.heatmap = Heatmap::next(self.heatmap);
+        self.invalidate();
+        self.heatmap
+    }
+
+    fn track_ages(&mut self) {
+        match (self.age_colors || self.trails || self.births, &self.ages) {
+            (true, None) => self.ages = Some(Ages::new()),
+            (false, Some(_)) => self.ages = None,
+            _ => {}
+        }
+        self.invalidate();
+    }
+
+    // Reserves the bottom `graph.height()` rows of the frame for a graph.
+    pub fn with_graph(mut self, graph: PopulationGraph) -> Self {
+        self.graph = Some(graph);
+        self
+    }
+
+    // Samples the simulator's populations and notes which cells changed;
+    // call once per generation.
+    pub fn record(&mut self, sim: &dyn Simulator) {
+        self.board = (sim.dimensions(), sim.lattice());
+        self.note(sim.dimensions(), sim.changes(), &sim.populations(), sim.population());
+    }
+
+    // The same as `record`, from a generation's figures rather than the
+    // simulator itself, `live` cells among them.
+    pub fn note(
+        &mut self,
+        dimensions: (usize, usize),
+        changes: Option<&[(usize, usize)]>,
+        populations: &[Population],
+        live: usize,
+    ) {
+        if let Some(graph) = &mut self.graph {
+            graph.record(populations);
+        }
+        if let Some(changes) = changes {
+            self.activity.record(dimensions, changes);
+        }
+        if let Some(chart) = &mut self.chart {
+            chart.record(&[Population {
+                name: "live",
+                color: CHART_LINE,
+                count: live,
+            }]);
+        }
+        match &mut self.ages {
+            // Every cell gets older, changed or not.
+            Some(ages) => {
+                ages.step(dimensions, changes);
+                self.invalidate();
+            }
+            None => self.mark_changes(dimensions, changes),
+        }
+    }
+
+    // Shows `text` in a box at the top of the board, or nothing.
+    pub fn set_palette(&mut self, text: Option<String>) {
+        self.palette = text;
+        self.invalidate();
+    }
+
+    // Shows or hides the timing overlay.
+    pub fn toggle_profile(&mut self) {
+        self.profile = match self.profile {
+            Some(_) => None,
+            None => Some(Profile::new()),
+        };
+        self.invalidate();
+    }
+
+    // Shows or hides the generation, population and rates.
+    pub fn toggle_hud(&mut self) {
+        self.hud = match self.hud {
+            Some(_) => None,
+            None => Some(Hud::new()),
+        };
+        self.invalidate();
+    }
+
+    // Shows or hides the chart of live cells, which starts empty.
+    pub fn toggle_chart(&mut self) {
+        self.chart = match self.chart {
+            Some(_) => None,
+            None => Some(PopulationGraph::new(CHART_SIZE.0, CHART_SIZE.1)),
+        };
+        self.invalidate();
+    }
+
+    // Feeds the timing overlay and HUD, if they're showing.
+    pub fn record_step(&mut self, took: Duration, generations: usize) {
+        if let Some(profile) = &mut self.profile {
+            profile.record_step(took, generations);
+        }
+        if let Some(hud) = &mut self.hud {
+            hud.record_step(generations);
+        }
+    }
+
+    // Repaints the whole board on the next render.
+    pub fn invalidate(&mut self) {
+        self.dirty = None;
+    }
+
+    // Notes cells to repaint on a board of `dimensions`; None repaints all.
+    pub fn mark_changes(&mut self, dimensions: (usize, usize), changes: Option<&[(usize, usize)]>) {
+        if let Some(ages) = &mut self.ages {
+            ages.restart(dimensions, changes);
+        }
+        let lattice = self.board.1;
+        let (_, size) = self.viewport.region(dimensions, lattice);
+        let changes: Option<Vec<_>> = changes.map(|changes| {
+            changes.iter().filter_map(|&cell| self.viewport.to_view(dimensions, lattice, cell)).collect()
+        });
+        self.mark_view_changes(size, changes.as_deref());
+    }
+
+    // The same, for cells already counted from the viewport's corner. Under
+    // a heatmap cells change color with their neighbors, so any change
+    // repaints the lot.
+    fn mark_view_changes(&mut self, (rows, cols): (usize, usize), changes: Option<&[(usize, usize)]>) {
+        match (&mut self.dirty, changes) {
+            (Some(_), Some(changes)) if self.heatmap.is_some() && !changes.is_empty() => self.dirty = None,
+            (Some(dirty), Some(changes)) if dirty.len() + changes.len() <= rows * cols / 2 => {
+                dirty.extend_from_slice(changes)
+            }
+            _ => self.dirty = None,
+        }
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut Pixels {
+        &mut self.pixels
+    }
+
+    // Reallocates the frame to fit the simulator's board as it is now and
+    // returns the new frame size, graph included.
+    pub fn resize(&mut self, sim: &dyn Simulator) -> Result<(usize, usize), Error> {
+        self.board = (sim.dimensions(), sim.lattice());
+        let (width, board_height) = self.board_size(sim);
+        let mut height = board_height;
+        if let Some(graph) = &mut self.graph {
+            graph.set_width(width);
+            height += graph.height();
+        }
+        self.pixels.resize_buffer(width as u32, height as u32)?;
+        self.cells = None;
+        self.invalidate();
+        Ok((width, height))
+    }
+
+    // Pixel size of the cells' part of the frame: the view, or both halves
+    // of a split screen side by side.
+    fn board_size(&mut self, sim: &dyn Simulator) -> (usize, usize) {
+        let own = self.viewport.frame_size(sim, self.cell_size);
+        let Some(split) = self.split else {
+            return own;
+        };
+        let other = split.viewport.frame_size(sim, split.cell_size);
+        let (left, right) = if split.left { (other, own) } else { (own, other) };
+        self.split_at = left.0 + SPLIT_GAP;
+        (self.split_at + right.0, left.1.max(right.1))
+    }
+
+    // Shows as much of the board as fits in a window `width` by `height`
+    // frame pixels, graph included, and resizes the frame to match, each
+    // half of a split screen getting half the width. A board smaller than
+    // the window is letterboxed.
+    pub fn fit(&mut self, sim: &dyn Simulator, (width, height): (usize, usize)) -> Result<(usize, usize), Error> {
+        let height = height.saturating_sub(self.graph.as_ref().map_or(0, PopulationGraph::height));
+        let width = if self.split.is_some() { width.saturating_sub(SPLIT_GAP) / 2 } else { width };
+        self.viewport.set_size(fitted(self.viewport, sim, self.cell_size, (width, height)));
+        if let Some(split) = &mut self.split {
+            split.viewport.set_size(fitted(split.viewport, sim, split.cell_size, (width, height)));
+            split.viewport.pan(sim.dimensions(), sim.lattice(), (0, 0));
+        }
+        // Keep the view inside the board.
+        self.pan((0, 0));
+        self.resize(sim)
+    }
+
+    // Draws cells `cell_size` pixels across, refitting the view to a window
+    // `window` frame pixels in size, and keeps the board cell under the
+    // physical window position `pivot` where it was, or failing that the
+    // one in the middle of the view.
+    pub fn zoom(
+        &mut self,
+        sim: &dyn Simulator,
+        cell_size: usize,
+        pivot: Option<(f32, f32)>,
+        window: (usize, usize),
+    ) -> Result<(usize, usize), Error> {
+        self.board = (sim.dimensions(), sim.lattice());
+        let (dimensions, lattice) = self.board;
+        let cell = pivot.and_then(|position| self.board_cell_at(position)).unwrap_or(self.middle());
+        self.set_cell_size(cell_size);
+        let size = self.fit(sim, window)?;
+        let (rows, cols) = self.viewport.region(dimensions, lattice).1;
+        let pixel = pivot
+            .and_then(|position| self.pixel_at(position))
+            .unwrap_or((cols * self.cell_size / 2, rows * self.cell_size / 2));
+        let before = self.viewport;
+        self.viewport.pin(dimensions, lattice, cell, self.cell_size, pixel);
+        if self.viewport != before {
+            self.invalidate();
+        }
+        Ok(size)
+    }
+
+    pub fn cell_size(&self) -> usize {
+        self.cell_size
+    }
+
+    // Takes effect with the next `resize` or `fit`.
+    pub fn set_cell_size(&mut self, cell_size: usize) {
+        self.cell_size = cell_size.max(1);
+    }
+
+    // The same for the half of a split screen not being pointed at.
+    pub fn set_split_cell_size(&mut self, cell_size: usize) {
+        if let Some(split) = &mut self.split {
+            split.cell_size = cell_size.max(1);
+        }
+    }
+
+    // Cells shown down and across of the board last seen.
+    pub fn view_size(&self) -> (usize, usize) {
+        let (dimensions, lattice) = self.board;
+        self.viewport.region(dimensions, lattice).1
+    }
+
+    // Frame pixel under a physical window position, if it lands on the frame,
+    // counted on a split screen from the corner of the half pointed at and
+    // None off it.
+    pub fn pixel_at(&self, position: (f32, f32)) -> Option<(usize, usize)> {
+        let (x, y) = self.pixels.window_pos_to_pixel(position).ok()?;
+        match self.split {
+            None => Some((x, y)),
+            Some(split) if split.left => Some((x.checked_sub(self.split_at)?, y)),
+            Some(_) => (x + SPLIT_GAP < self.split_at).then_some((x, y)),
+        }
+    }
+
+    // Board cell under a physical window position, if it lands on the board.
+    pub fn cell_at(&self, sim: &dyn Simulator, position: (f32, f32)) -> Option<(usize, usize)> {
+        let (x, y) = self.pixel_at(position)?;
+        self.viewport.cell_at_pixel(sim, self.cell_size, x, y)
+    }
+}
+
+impl Renderer for PixelsRenderer {
+    fn render(&mut self, sim: &dyn Simulator) -> Result<(), Error> {
+        self.board = (sim.dimensions(), sim.lattice());
+        let whole = sim;
+        let view = self.viewport.of(sim);
+        let sim: &dyn Simulator = &view;
+        // Cells changed outside a step, by a click say, are only in the
+        // simulator's current changes.
+        self.mark_view_changes(sim.dimensions(), sim.changes());
+        // The HUD's box changes size with its numbers, so the cells it covered
+        // last frame are repainted in case it's shrunk.
+        if self.hud.is_some()
+            && let Some(dirty) = &mut self.dirty
+        {
+            let (((x, y), (w, h)), size, (rows, cols)) = (self.hud_box, self.cell_size, sim.dimensions());
+            let cols = x / size..(x + w).div_ceil(size).min(cols);
+            let rows = y / size..(y + h).div_ceil(size).min(rows);
+            dirty.extend(rows.flat_map(|row| cols.clone().map(move |col| (row, col))));
+        }
+        // Changes aren't tracked for the other half of a split screen, and
+        // glow reaches past the cells changed.
+        if self.split.is_some() || self.glow {
+            self.invalidate();
+        }
+        let started = Instant::now();
+        let (width, height) = self.board_size(whole);
+        // The shader only fills whole squares, so shaped cells are drawn here,
+        // and only ever fills the one view.
+        let gpu = self.gpu
+            && sim.lattice() == Lattice::Square
+            && self.theme.shape == CellShape::Square
+            && self.split.is_none()
+            && !self.glow;
+        if gpu && self.cells.is_none() {
+            let context = self.pixels.context();
+            let total = context.texture_extent.height as usize;
+            self.cells = Some(CellPass::new(
+                &context.device,
+                &context.queue,
+                &context.texture,
+                self.pixels.render_texture_format(),
+                sim.dimensions(),
+                (height, total),
+            ));
+            self.dirty = None;
+        }
+        let ((top, left), _) = self.viewport.region(self.board.0, self.board.1);
+        let (theme, ages) = (self.theme, self.ages.as_ref());
+        let modes = (self.age_colors, self.trails, self.births);
+        let (heatmap, activity) = (self.heatmap, &self.activity);
+        // The color of cell (row, col) of `view`, whose corner is board cell
+        // (top, left).
+        let color_in = |view: &dyn Simulator, (top, left): (usize, usize), row: usize, col: usize| {
+            let board_cell = (top + row, left + col);
+            if let Some(color) = heatmap.and_then(|heatmap| heatmap.color(view, (row, col), activity, board_cell)) {
+                return color;
+            }
+            match ages {
+                Some(ages) => ages.color(board_cell, view.shade(row, col), &theme, modes),
+                None => theme.color(view.shade(row, col)),
+            }
+        };
+        let color = |row: usize, col: usize| color_in(sim, (top, left), row, col);
+        let (board, below) = self.pixels.frame_mut().split_at_mut(width * height * 4);
+        let dirty = self.dirty.replace(Vec::new());
+        // Each half is painted on its own and copied into place, over the
+        // background showing between and below them.
+        if let Some(split) = self.split {
+            for pixel in board.chunks_exact_mut(4) {
+                pixel.copy_from_slice(&theme.background);
+            }
+            let own = (self.viewport, self.cell_size);
+            let other = (split.viewport, split.cell_size);
+            let (left, right) = if split.left { (other, own) } else { (own, other) };
+            for ((viewport, size), x) in [(left, 0), (right, self.split_at)] {
+                let view = viewport.of(whole);
+                let (w, h) = frame_size(&view, size);
+                let mut pane = vec![0; w * h * 4];
+                let corner = viewport.region(self.board.0, self.board.1).0;
+                paint_cells(&view, &mut pane, size, &theme, |row, col| color_in(&view, corner, row, col));
+                if view.lattice() == Lattice::Square {
+                    if self.grid_lines && size >= GRID_MIN_CELL {
+                        draw_grid(&mut pane, (w, h), size, theme.grid_line);
+                    }
+                    self.marks.draw(&mut pane, w, size, viewport, self.board.0, false);
+                }
+                for (y, line) in pane.chunks_exact(w * 4).enumerate() {
+                    board[(y * width + x) * 4..][..w * 4].copy_from_slice(line);
+                }
+            }
+        } else {
+            match (dirty, &mut self.cells) {
+                (Some(dirty), Some(cells)) if gpu => {
+                    for (row, col) in dirty {
+                        cells.set(row, col, color(row, col));
+                    }
+                    font::fill_rect(board, width, (0, 0), self.overlay, CLEAR);
+                    font::fill_rect(board, width, self.hud_box.0, self.hud_box.1, CLEAR);
+                }
+                (None, Some(cells)) if gpu => {
+                    let (rows, cols) = sim.dimensions();
+                    for row in 0..rows {
+                        for col in 0..cols {
+                            cells.set(row, col, color(row, col));
+                        }
+                    }
+                    board.fill(0);
+                }
+                (Some(dirty), _) if sim.lattice() == Lattice::Square => {
+                    let mask = Mask::new(theme.shape, self.cell_size, theme.dead);
+                    for (row, col) in dirty {
+                        match &mask {
+                            Some(mask) => fill_shaped_cell(board, width, mask, row, col, color(row, col)),
+                            None => fill_cell(board, width, self.cell_size, row, col, color(row, col)),
+                        }
+                    }
+                }
+                _ => paint_cells(sim, board, self.cell_size, &theme, color),
+            }
+            // Redrawn every frame over the cells just repainted, which is
+            // cheaper than keeping track of which lines they covered.
+            if self.grid_lines && sim.lattice() == Lattice::Square && self.cell_size >= GRID_MIN_CELL {
+                draw_grid(board, (width, height), self.cell_size, theme.grid_line);
+            }
+            if sim.lattice() == Lattice::Square && !self.marks.is_empty() {
+                self.marks.draw(board, width, self.cell_size, self.viewport, self.board.0, gpu);
+                // Nothing keeps track of the cells under the marks, so the next
+                // frame repaints the lot.
+                self.dirty = None;
+            }
+        }
+        if self.glow {
+            glow(board, (width, height), theme.background, self.cell_size.max(2));
+        }
+        if let Some(graph) = &self.graph {
+            graph.draw(below);
+        }
+        let region = self.viewport.region(self.board.0, self.board.1);
+        self.minimap = (region.1 != self.board.0).then(|| Minimap::new(self.board.0, (width, height)));
+        if let Some(minimap) = self.minimap {
+            minimap.draw(board, width, whole, &theme, region);
+        }
+        // Left off boards too small to hold it.
+        if let Some(chart) = &self.chart
+            && width >= CHART_SIZE.0
+            && height >= CHART_SIZE.1
+        {
+            chart.draw_at(board, width, (0, height - CHART_SIZE.1));
+        }
+        if let Some(text) = &self.palette {
+            let (w, h) = font::text_size(text, OVERLAY_SCALE);
+            let pad = OVERLAY_SCALE * 2;
+            let x = width.saturating_sub(w + 2 * pad) / 2;
+            font::fill_rect(board, width, (x, 0), (w + 2 * pad, h + 2 * pad), OVERLAY_BACKGROUND);
+            font::draw_text(board, width, x + pad, pad, text, OVERLAY_SCALE, OVERLAY_TEXT);
+            self.dirty = None;
+        }
+        // Drawn every frame, over whichever cells were just repainted.
+        if let Some(profile) = &mut self.profile {
+            profile.set_memory(Memory::total(&sim.memory_footprint()));
+            self.overlay = profile.draw(board, width);
+            profile.draw = started.elapsed();
+        }
+        if let Some(hud) = &mut self.hud {
+            self.hud_box = hud.draw(board, width, sim);
+        }
+        let presenting = Instant::now();
+        match &self.cells {
+            Some(cells) if gpu => self.pixels.render_with(|encoder, target, context| {
+                context.scaling_renderer.render(encoder, target);
+                cells.render(&context.queue, encoder, target, context.scaling_renderer.clip_rect());
+                Ok(())
+            })?,
+            _ => self.pixels.render()?,
+        }
+        if let Some(profile) = &mut self.profile {
+            profile.present = presenting.elapsed();
+            profile.step = std::mem::take(&mut profile.pending_step);
+        }
+        Ok(())
+    }
+}
+
+pub struct FrameBuffer {
+    width: usize,
+    height: usize,
+    cell_size: usize,
+    frame: Vec<u8>,
+    theme: Theme,
+}
+
+impl FrameBuffer {
+    pub fn new(rows: usize, cols: usize, cell_size: usize) -> Self {
+        let width = cols * cell_size;
+        let height = rows * cell_size;
+        Self {
+            width,
+            height,
+            cell_size,
+            frame: vec![0; width * height * 4],
+            theme: Theme::default(),
+        }
+    }
+
+    // Sized for the simulator's board, whatever its lattice.
+    pub fn for_simulator(sim: &dyn Simulator, cell_size: usize) -> Self {
+        let (width, height) = frame_size(sim, cell_size);
+        Self {
+            width,
+            height,
+            cell_size,
+            frame: vec![0; width * height * 4],
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn frame(&self) -> &[u8] {
+        &self.frame
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let idx = (y * self.width + x) * 4;
+        let mut rgba = [0; 4];
+        rgba.copy_from_slice(&self.frame[idx..idx + 4]);
+        rgba
+    }
+}
+
+impl Renderer for FrameBuffer {
+    fn render(&mut self, sim: &dyn Simulator) -> Result<(), Error> {
+        draw_cells(sim, &mut self.frame, self.cell_size, &self.theme);
+        Ok(())
+    }
+}
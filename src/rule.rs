@@ -0,0 +1,299 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::life::{ALIVE, DEAD};
+use crate::neighborhood::{Hexagonal, Moore, Neighborhood, Triangular, VonNeumann};
+use crate::{CellBehavior, Error};
+
+// Which cells a rule counts: for Moore and von Neumann, every cell within
+// some range of steps, diagonal or not. Hexagonal and range-1 von Neumann
+// rules are written with a trailing "H" or "V" as Golly does; the other
+// neighborhoods have no suffix and are only chosen by name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleNeighborhood {
+    Moore(usize),
+    VonNeumann(usize),
+    Hexagonal,
+    TriangleEdges,
+    TriangleVertices,
+}
+
+// Neighbor counts a rule reacts to, a bit for each from 0 to 255.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Counts([u64; 4]);
+
+impl Counts {
+    fn of(counts: &[u8]) -> Self {
+        counts.iter().fold(Self::default(), |counts, &n| counts.with(n))
+    }
+
+    fn with(mut self, n: u8) -> Self {
+        self.0[n as usize / 64] |= 1 << (n % 64);
+        self
+    }
+
+    fn contains(self, n: u8) -> bool {
+        self.0[n as usize / 64] & (1 << (n % 64)) != 0
+    }
+
+    fn iter(self) -> impl Iterator<Item = u8> {
+        (0..=u8::MAX).filter(move |&n| self.contains(n))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    birth: Counts,
+    survival: Counts,
+    states: u8,
+    neighborhood: RuleNeighborhood,
+}
+
+fn preset_key(name: &str) -> String {
+    name.replace('&', "and")
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|ch| ch.to_ascii_lowercase())
+        .collect()
+}
+
+// A digit to a count, or whole numbers split by commas for counts past 9,
+// as they always are in neighborhoods of more than nine cells: "S10" there
+// is ten, not one and none.
+fn parse_counts(counts: &str, rule: &str, cells: usize) -> Result<Counts, Error> {
+    let bad = |count: &str| Error::Rule(format!("{rule:?}: unexpected {count:?} in neighbor counts"));
+    if counts.contains(',') || cells > 9 {
+        let mut counts = counts.split(',').map(str::trim).filter(|count| !count.is_empty());
+        counts.try_fold(Counts::default(), |set, count| count.parse().map(|n| set.with(n)).map_err(|_| bad(count)))
+    } else {
+        counts.chars().try_fold(Counts::default(), |set, ch| match ch.to_digit(10) {
+            Some(n) => Ok(set.with(n as u8)),
+            None => Err(bad(&ch.to_string())),
+        })
+    }
+}
+
+fn parse_states(digits: &str, rule: &str) -> Result<u8, Error> {
+    match digits.parse::<u8>() {
+        Ok(states) if states >= 2 => Ok(states),
+        _ => Err(Error::Rule(format!("{rule:?}: state count must be between 2 and 255"))),
+    }
+}
+
+fn write_counts(f: &mut fmt::Formatter<'_>, counts: Counts, cells: usize) -> fmt::Result {
+    let counts: Vec<_> = counts.iter().map(|n| n.to_string()).collect();
+    let split = cells > 9 || counts.iter().any(|n| n.len() > 1);
+    f.write_str(&counts.join(if split { "," } else { "" }))
+}
+
+impl Rule {
+    pub const PRESETS: &'static [(&'static str, &'static str)] = &[
+        ("life", "B3/S23"),
+        ("highlife", "B36/S23"),
+        ("seeds", "B2/S"),
+        ("day-and-night", "B3678/S34678"),
+        ("life-without-death", "B3/S012345678"),
+        ("star-wars", "B2/S345/C4"),
+        ("brians-brain", "B2/S/C3"),
+    ];
+
+    pub fn new(birth: &[u8], survival: &[u8], states: u8) -> Self {
+        assert!(states >= 2, "a rule needs at least a dead and an alive state");
+        Self {
+            birth: Counts::of(birth),
+            survival: Counts::of(survival),
+            states,
+            neighborhood: RuleNeighborhood::default(),
+        }
+    }
+
+    // The same counts in another neighborhood, as long as it has enough
+    // cells for all of them.
+    pub fn with_neighborhood(mut self, neighborhood: RuleNeighborhood) -> Result<Self, Error> {
+        let limit = neighborhood.build()?.offsets().len();
+        if let Some(count) = self.birth.iter().chain(self.survival.iter()).find(|&n| n as usize > limit) {
+            return Err(Error::Rule(format!("{:?}: count {count} is past the {limit} cells of its neighborhood", self.to_string())));
+        }
+        self.neighborhood = neighborhood;
+        Ok(self)
+    }
+
+    pub fn life() -> Self {
+        Self::new(&[3], &[2, 3], 2)
+    }
+
+    // Names match loosely, so "Day & Night", "day-and-night" and
+    // "DayAndNight" all select the same preset.
+    pub fn preset(name: &str) -> Option<Self> {
+        let key = preset_key(name);
+        Self::PRESETS
+            .iter()
+            .find(|(preset, _)| preset_key(preset) == key)
+            .map(|(_, rule)| Self::parse(rule).expect("preset rules are well-formed"))
+    }
+
+    pub fn from_name_or_notation(rule: &str) -> Result<Self, Error> {
+        Self::from_name_or_notation_in(rule, None)
+    }
+
+    // The same in `neighborhood` if it's given, whatever suffix the notation
+    // has.
+    pub fn from_name_or_notation_in(rule: &str, neighborhood: Option<RuleNeighborhood>) -> Result<Self, Error> {
+        match (Self::preset(rule), neighborhood) {
+            (Some(preset), Some(neighborhood)) => preset.with_neighborhood(neighborhood),
+            (Some(preset), None) => Ok(preset),
+            (None, _) => Self::parse_in(rule, neighborhood),
+        }
+    }
+
+    pub fn parse(rule: &str) -> Result<Self, Error> {
+        Self::parse_in(rule, None)
+    }
+
+    pub fn parse_in(rule: &str, neighborhood: Option<RuleNeighborhood>) -> Result<Self, Error> {
+        let trimmed = rule.trim();
+        let (body, suffixed) = match trimmed.char_indices().last() {
+            Some((i, 'H' | 'h')) => (&trimmed[..i], RuleNeighborhood::Hexagonal),
+            Some((i, 'V' | 'v')) => (&trimmed[..i], RuleNeighborhood::VonNeumann(1)),
+            _ => (trimmed, RuleNeighborhood::default()),
+        };
+        let neighborhood = neighborhood.unwrap_or(suffixed);
+        let limit = neighborhood.build()?.offsets().len();
+        let parts: Vec<&str> = body.split('/').collect();
+        let tagged = parts
+            .iter()
+            .any(|part| part.starts_with(['B', 'b', 'S', 's']));
+        let (mut birth, mut survival, mut states) = (Counts::default(), Counts::default(), 2);
+        if tagged {
+            for part in &parts {
+                let mut chars = part.chars();
+                match chars.next().map(|ch| ch.to_ascii_uppercase()) {
+                    Some('B') => birth = parse_counts(chars.as_str(), rule, limit)?,
+                    Some('S') => survival = parse_counts(chars.as_str(), rule, limit)?,
+                    Some('C' | 'G') => states = parse_states(chars.as_str(), rule)?,
+                    Some(_) => states = parse_states(part, rule)?,
+                    None => return Err(Error::Rule(format!("{rule:?}: empty section"))),
+                }
+            }
+        } else {
+            match parts.as_slice() {
+                [s, b] => {
+                    survival = parse_counts(s, rule, limit)?;
+                    birth = parse_counts(b, rule, limit)?;
+                }
+                [s, b, c] => {
+                    survival = parse_counts(s, rule, limit)?;
+                    birth = parse_counts(b, rule, limit)?;
+                    states = parse_states(c, rule)?;
+                }
+                _ => return Err(Error::Rule(format!("{rule:?}: expected B/S or S/B/C notation"))),
+            }
+        }
+        if birth.iter().chain(survival.iter()).any(|n| n as usize > limit) {
+            let split = if limit > 9 { ", with commas between them" } else { "" };
+            return Err(Error::Rule(format!("{rule:?}: counts go up to {limit} in this neighborhood{split}")));
+        }
+        Ok(Self {
+            birth,
+            survival,
+            states,
+            neighborhood,
+        })
+    }
+
+    pub fn neighborhood(&self) -> RuleNeighborhood {
+        self.neighborhood
+    }
+
+    pub fn births(&self, alive_neighbors: u8) -> bool {
+        self.birth.contains(alive_neighbors)
+    }
+
+    pub fn survives(&self, alive_neighbors: u8) -> bool {
+        self.survival.contains(alive_neighbors)
+    }
+}
+
+impl Default for RuleNeighborhood {
+    fn default() -> Self {
+        RuleNeighborhood::Moore(1)
+    }
+}
+
+impl RuleNeighborhood {
+    // Moore and von Neumann take a range after their name, as in "moore2"
+    // or "von-neumann-3", and are range 1 without one.
+    pub fn parse(name: &str) -> Option<Self> {
+        let key = preset_key(name);
+        let ranged = |prefix: &str| match key.strip_prefix(prefix)? {
+            "" => Some(1),
+            range => range.parse().ok().filter(|&range| range > 0),
+        };
+        match key.as_str() {
+            "hexagonal" | "hex" => Some(RuleNeighborhood::Hexagonal),
+            "triangle3" | "triangleedges" => Some(RuleNeighborhood::TriangleEdges),
+            "triangle12" | "trianglevertices" => Some(RuleNeighborhood::TriangleVertices),
+            _ => ranged("moore")
+                .map(RuleNeighborhood::Moore)
+                .or_else(|| ranged("vonneumann").map(RuleNeighborhood::VonNeumann)),
+        }
+    }
+
+    // Fails for ranges with more cells than a neighbor count holds.
+    pub fn build(self) -> Result<Box<dyn Neighborhood>, Error> {
+        Ok(match self {
+            RuleNeighborhood::Moore(range) => Box::new(Moore::range(range)?),
+            RuleNeighborhood::VonNeumann(range) => Box::new(VonNeumann::range(range)?),
+            RuleNeighborhood::Hexagonal => Box::new(Hexagonal),
+            RuleNeighborhood::TriangleEdges => Box::new(Triangular::edges()),
+            RuleNeighborhood::TriangleVertices => Box::new(Triangular::vertices()),
+        })
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::life()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells = self.neighborhood.build().map_or(0, |neighborhood| neighborhood.offsets().len());
+        write!(f, "B")?;
+        write_counts(f, self.birth, cells)?;
+        write!(f, "/S")?;
+        write_counts(f, self.survival, cells)?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        match self.neighborhood {
+            RuleNeighborhood::VonNeumann(1) => write!(f, "V"),
+            RuleNeighborhood::Hexagonal => write!(f, "H"),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl CellBehavior for Rule {
+    fn next_state(&self, state: u8, alive_neighbors: u8) -> u8 {
+        match state {
+            DEAD if self.births(alive_neighbors) => ALIVE,
+            DEAD => DEAD,
+            ALIVE if self.survives(alive_neighbors) => ALIVE,
+            dying => (dying + 1) % self.states,
+        }
+    }
+
+    fn states(&self) -> u8 {
+        self.states
+    }
+}
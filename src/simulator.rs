@@ -0,0 +1,211 @@
+use crate::{Anchor, Snapshot};
+use crate::boundary::BoundaryCondition;
+use crate::random::Rng;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shade {
+    Dead,
+    Alive,
+    Dying(f32),
+    Rgba([u8; 4]),
+}
+
+// How cells tile the plane. Cells are always addressed by (row, col); on a
+// hexagonal lattice those are axial coordinates, so row r is drawn shifted
+// half a cell further right than row r - 1. On a triangular lattice cells
+// with an even row + col point up and the rest point down.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Lattice {
+    #[default]
+    Square,
+    Hexagonal,
+    Triangular,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Step {
+    Add(f64),
+    Scale(f64),
+}
+
+// A value that can be tuned while the simulation runs, and how one press of
+// a key nudges it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub value: f64,
+    pub step: Step,
+}
+
+impl Parameter {
+    pub fn new(name: &'static str, value: f64, step: Step) -> Self {
+        Self { name, value, step }
+    }
+
+    pub fn raised(&self) -> f64 {
+        match self.step {
+            Step::Add(by) => self.value + by,
+            Step::Scale(by) => self.value * by,
+        }
+    }
+
+    pub fn lowered(&self) -> f64 {
+        match self.step {
+            Step::Add(by) => self.value - by,
+            Step::Scale(by) => self.value / by,
+        }
+    }
+}
+
+// One tracked species or state and how many cells currently hold it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Population {
+    pub name: &'static str,
+    pub color: [u8; 4],
+    pub count: usize,
+}
+
+// Heap memory held in one of a simulator's buffers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Memory {
+    pub name: &'static str,
+    pub bytes: usize,
+}
+
+impl Memory {
+    pub fn new(name: &'static str, bytes: usize) -> Self {
+        Self { name, bytes }
+    }
+
+    pub fn of<T>(name: &'static str, items: &[T]) -> Self {
+        Self::new(name, std::mem::size_of_val(items))
+    }
+
+    pub fn total(parts: &[Memory]) -> usize {
+        parts.iter().map(|part| part.bytes).sum()
+    }
+}
+
+// Send so it can be stepped on a worker thread, Sync so a borrowed one can
+// be wrapped in another simulator, such as a view of part of its board.
+pub trait Simulator: Send + Sync {
+    fn step(&mut self);
+
+    fn dimensions(&self) -> (usize, usize);
+
+    // Generations stepped so far, for simulators that count them.
+    fn generation(&self) -> Option<u64> {
+        None
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool;
+
+    // How many cells are alive, counted one by one unless the simulator
+    // has a quicker way.
+    fn population(&self) -> usize {
+        let (rows, cols) = self.dimensions();
+        (0..rows).map(|row| (0..cols).filter(|&col| self.cell(row, col)).count()).sum()
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        if self.cell(row, col) { Shade::Alive } else { Shade::Dead }
+    }
+
+    fn lattice(&self) -> Lattice {
+        Lattice::Square
+    }
+
+    // Live cells around a cell, under the simulator's own neighborhood and
+    // boundary, for simulators that count them.
+    fn neighbors(&self, _row: usize, _col: usize) -> Option<u8> {
+        None
+    }
+
+    // What lies past the board edges, for simulators that let it change.
+    fn boundary(&self) -> Option<BoundaryCondition> {
+        None
+    }
+
+    fn set_boundary(&mut self, _boundary: BoundaryCondition) {}
+
+    // Cells that may look different since the previous step, for
+    // simulators that keep track; None means anything may have changed.
+    fn changes(&self) -> Option<&[(usize, usize)]> {
+        None
+    }
+
+    // Changes the board size, keeping the anchored part of what's on it.
+    // Simulators whose state can't be carried over keep their size.
+    fn resize(&mut self, _rows: usize, _cols: usize, _anchor: Anchor) {}
+
+    // Brings a cell to life or kills it, for simulators that can be drawn
+    // on. The rest ignore it.
+    fn set_cell(&mut self, _row: usize, _col: usize, _alive: bool) {}
+
+    // Kills every live cell, through `set_cell`.
+    fn clear(&mut self) {
+        let (rows, cols) = self.dimensions();
+        for row in 0..rows {
+            for col in 0..cols {
+                if self.cell(row, col) {
+                    self.set_cell(row, col, false);
+                }
+            }
+        }
+    }
+
+    // Redraws the board as a random soup with about `density` of its cells
+    // alive, the same soup every time for the same seed.
+    fn randomize(&mut self, density: f64, seed: u64) {
+        let mut rng = Rng::new(seed);
+        let (rows, cols) = self.dimensions();
+        for row in 0..rows {
+            for col in 0..cols {
+                self.set_cell(row, col, rng.chance(density));
+            }
+        }
+    }
+
+    // What the cell holds, in a word or two, for the readout under the
+    // cursor.
+    fn describe(&self, row: usize, col: usize) -> String {
+        match self.shade(row, col) {
+            Shade::Dead => "dead".to_string(),
+            Shade::Alive => "alive".to_string(),
+            Shade::Dying(_) => "dying".to_string(),
+            Shade::Rgba(_) => if self.cell(row, col) { "alive" } else { "dead" }.to_string(),
+        }
+    }
+
+    // Reacts to the user clicking a cell: toggles it, unless the simulator
+    // has something better to do with a click.
+    fn poke(&mut self, row: usize, col: usize) {
+        let alive = self.cell(row, col);
+        self.set_cell(row, col, !alive);
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        Vec::new()
+    }
+
+    fn set_parameter(&mut self, _name: &str, _value: f64) {}
+
+    // Counts worth plotting over time. Empty means no population graph.
+    fn populations(&self) -> Vec<Population> {
+        Vec::new()
+    }
+
+    // The board and generation as they are now, for undo; None for
+    // simulators whose state doesn't fit in a grid.
+    fn save(&self) -> Option<Snapshot> {
+        None
+    }
+
+    // Puts back a board from `save`, size and all.
+    fn load(&mut self, _snapshot: &Snapshot) {}
+
+    // Roughly what the simulator keeps on the heap, buffer by buffer.
+    fn memory_footprint(&self) -> Vec<Memory> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::neighborhood::Neighborhood;
+use crate::{Anchor, Grid, Lattice, Memory, Parameter, Rule, Simulator, Step};
+
+// Two-state Life on an unbounded plane, storing only the live cells. The
+// board it shows is a fixed-size window onto the plane whose top-left
+// corner is `camera`; with `follow` on, the window recenters on the
+// pattern's bounding box after every step.
+//
+// Only cells next to a live cell are ever considered for birth, so B0 rules
+// behave as if they lacked B0. Neighborhoods use their fixed offsets, which
+// rules out the triangular ones.
+pub struct SparseLife {
+    cells: HashSet<(i64, i64)>,
+    rule: Rule,
+    neighborhood: Box<dyn Neighborhood>,
+    view: (usize, usize),
+    camera: (i64, i64),
+    follow: bool,
+    generation: u64,
+}
+
+impl SparseLife {
+    pub fn new(rule: Rule, view: (usize, usize)) -> Self {
+        Self {
+            cells: HashSet::new(),
+            rule,
+            neighborhood: rule.neighborhood().build().expect("a rule's neighborhood is built when it's set"),
+            view,
+            camera: (0, 0),
+            follow: true,
+            generation: 0,
+        }
+    }
+
+    // The grid's live cells at their grid coordinates, viewed through a
+    // window the size of the grid.
+    pub fn from_grid(grid: &Grid, rule: Rule) -> Self {
+        let mut life = Self::new(rule, (grid.rows(), grid.cols()));
+        life.cells = grid.live_cells().map(|(r, c)| (r as i64, c as i64)).collect();
+        life
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: Box<dyn Neighborhood>) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    pub fn with_follow(mut self, follow: bool) -> Self {
+        self.follow = follow;
+        self
+    }
+
+    pub fn is_alive(&self, row: i64, col: i64) -> bool {
+        self.cells.contains(&(row, col))
+    }
+
+    pub fn set_alive(&mut self, row: i64, col: i64, alive: bool) {
+        if alive {
+            self.cells.insert((row, col));
+        } else {
+            self.cells.remove(&(row, col));
+        }
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn camera(&self) -> (i64, i64) {
+        self.camera
+    }
+
+    pub fn set_camera(&mut self, camera: (i64, i64)) {
+        self.camera = camera;
+    }
+
+    // Smallest (top, left, bottom, right) box holding every live cell.
+    pub fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        let mut cells = self.cells.iter();
+        let &(row, col) = cells.next()?;
+        Some(cells.fold((row, col, row, col), |(top, left, bottom, right), &(r, c)| {
+            (top.min(r), left.min(c), bottom.max(r), right.max(c))
+        }))
+    }
+
+    pub fn center_camera(&mut self) {
+        if let Some((top, left, bottom, right)) = self.bounds() {
+            let (rows, cols) = (self.view.0 as i64, self.view.1 as i64);
+            self.camera = ((top + bottom - rows + 1).div_euclid(2), (left + right - cols + 1).div_euclid(2));
+        }
+    }
+}
+
+impl Simulator for SparseLife {
+    fn step(&mut self) {
+        let mut counts: HashMap<(i64, i64), u8> = HashMap::with_capacity(self.cells.len() * 8);
+        for &(row, col) in &self.cells {
+            for &(dr, dc) in self.neighborhood.offsets() {
+                *counts.entry((row + dr as i64, col + dc as i64)).or_insert(0) += 1;
+            }
+        }
+        self.cells = counts
+            .into_iter()
+            .filter(|(cell, count)| {
+                if self.cells.contains(cell) { self.rule.survives(*count) } else { self.rule.births(*count) }
+            })
+            .map(|(cell, _)| cell)
+            .collect();
+        self.generation += 1;
+        if self.follow {
+            self.center_camera();
+        }
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        self.view
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+
+    fn population(&self) -> usize {
+        SparseLife::population(self)
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.is_alive(self.camera.0 + row as i64, self.camera.1 + col as i64)
+    }
+
+    fn lattice(&self) -> Lattice {
+        self.neighborhood.lattice()
+    }
+
+    // The plane itself is unbounded, so only the window changes.
+    fn resize(&mut self, rows: usize, cols: usize, anchor: Anchor) {
+        let (dr, dc) = anchor.offset(self.view, (rows, cols));
+        self.view = (rows, cols);
+        self.camera = (self.camera.0 - dr as i64, self.camera.1 - dc as i64);
+    }
+
+    fn set_cell(&mut self, row: usize, col: usize, alive: bool) {
+        self.set_alive(self.camera.0 + row as i64, self.camera.1 + col as i64, alive);
+    }
+
+    // The whole plane, not just the window onto it.
+    fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    // 1 keeps the camera on the pattern, 0 leaves it where it is.
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![Parameter::new("follow", self.follow as u8 as f64, Step::Add(1.0))]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) {
+        if name == "follow" {
+            self.follow = value.rem_euclid(2.0) >= 0.5;
+            if self.follow {
+                self.center_camera();
+            }
+        }
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        vec![Memory::new("cells", self.cells.capacity() * std::mem::size_of::<(i64, i64)>())]
+    }
+}
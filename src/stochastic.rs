@@ -0,0 +1,138 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use crate::life::{ALIVE, DEAD};
+use crate::random::Rng;
+use crate::{CellBehavior, Error};
+
+// A two-state rule where each neighbor count births or survives with its own
+// probability. Written like B/S notation with optional ":p" after a count,
+// e.g. "B3/S2:0.98,3" or "B3,6:0.05/S23". A probability covers every digit
+// of its comma-separated item; items without one always apply.
+//
+// The random stream is consumed in the simulator's cell order, so the same
+// seed and starting board always give the same run.
+#[derive(Debug)]
+pub struct StochasticRule {
+    birth: [f64; 9],
+    survival: [f64; 9],
+    rng: Mutex<Rng>,
+}
+
+fn parse_chances(section: &str, rule: &str) -> Result<[f64; 9], Error> {
+    let bad = |msg: &str| Error::Rule(format!("{rule:?}: {msg}"));
+    let mut chances = [0.0; 9];
+    for item in section.split(',').filter(|item| !item.is_empty()) {
+        let (counts, chance) = match item.split_once(':') {
+            Some((counts, chance)) => {
+                let chance: f64 = chance.parse().map_err(|_| bad("probabilities must be numbers"))?;
+                if !(0.0..=1.0).contains(&chance) {
+                    return Err(bad("probabilities must be between 0 and 1"));
+                }
+                (counts, chance)
+            }
+            None => (item, 1.0),
+        };
+        for ch in counts.chars() {
+            match ch.to_digit(10) {
+                Some(n) if n <= 8 => chances[n as usize] = chance,
+                _ => return Err(bad(&format!("unexpected {ch:?} in neighbor counts"))),
+            }
+        }
+    }
+    Ok(chances)
+}
+
+fn write_chances(f: &mut fmt::Formatter<'_>, chances: &[f64; 9]) -> fmt::Result {
+    let items: Vec<String> = (0..=8)
+        .filter(|&n| chances[n] > 0.0)
+        .map(|n| if chances[n] >= 1.0 { n.to_string() } else { format!("{n}:{}", chances[n]) })
+        .collect();
+    write!(f, "{}", items.join(","))
+}
+
+impl StochasticRule {
+    pub fn new(birth: [f64; 9], survival: [f64; 9], seed: u64) -> Self {
+        Self {
+            birth,
+            survival,
+            rng: Mutex::new(Rng::new(seed)),
+        }
+    }
+
+    pub fn parse(rule: &str) -> Result<Self, Error> {
+        let (mut birth, mut survival) = ([0.0; 9], [0.0; 9]);
+        for part in rule.trim().split('/') {
+            let mut chars = part.chars();
+            match chars.next().map(|ch| ch.to_ascii_uppercase()) {
+                Some('B') => birth = parse_chances(chars.as_str(), rule)?,
+                Some('S') => survival = parse_chances(chars.as_str(), rule)?,
+                _ => return Err(Error::Rule(format!("{rule:?}: expected B.../S... sections"))),
+            }
+        }
+        Ok(Self::new(birth, survival, 0))
+    }
+
+    // Restarts the random stream; clones otherwise continue where the
+    // original left off.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        *self.rng.get_mut().expect("rng lock poisoned") = Rng::new(seed);
+        self
+    }
+
+    pub fn birth_chance(&self, alive_neighbors: u8) -> f64 {
+        self.birth.get(alive_neighbors as usize).copied().unwrap_or(0.0)
+    }
+
+    pub fn survival_chance(&self, alive_neighbors: u8) -> f64 {
+        self.survival.get(alive_neighbors as usize).copied().unwrap_or(0.0)
+    }
+
+    // Certain outcomes skip the generator so deterministic counts don't
+    // shift the stream.
+    fn roll(&self, chance: f64) -> bool {
+        chance >= 1.0 || (chance > 0.0 && self.rng.lock().expect("rng lock poisoned").chance(chance))
+    }
+}
+
+impl Clone for StochasticRule {
+    fn clone(&self) -> Self {
+        Self {
+            birth: self.birth,
+            survival: self.survival,
+            rng: Mutex::new(self.rng.lock().expect("rng lock poisoned").clone()),
+        }
+    }
+}
+
+impl FromStr for StochasticRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for StochasticRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B")?;
+        write_chances(f, &self.birth)?;
+        write!(f, "/S")?;
+        write_chances(f, &self.survival)
+    }
+}
+
+impl CellBehavior for StochasticRule {
+    fn next_state(&self, state: u8, alive_neighbors: u8) -> u8 {
+        let lives = match state {
+            DEAD => self.roll(self.birth_chance(alive_neighbors)),
+            _ => self.roll(self.survival_chance(alive_neighbors)),
+        };
+        if lives { ALIVE } else { DEAD }
+    }
+
+    fn parallel(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,268 @@
+use crate::render::{self, frame_size};
+use crate::{Lattice, Memory, Population, Shade, Simulator};
+
+// The part of the board that's on screen: up to `size` cells down and
+// across from `origin`, or the whole board if it's smaller than that.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Viewport {
+    origin: (usize, usize),
+    size: (usize, usize),
+}
+
+impl Viewport {
+    pub fn new(size: (usize, usize)) -> Self {
+        Self {
+            origin: (0, 0),
+            size: (size.0.max(1), size.1.max(1)),
+        }
+    }
+
+    // Shows the whole of any board.
+    pub fn whole() -> Self {
+        Self::new((usize::MAX, usize::MAX))
+    }
+
+    // Shows up to `size` cells from the same origin.
+    pub fn set_size(&mut self, size: (usize, usize)) {
+        self.size = (size.0.max(1), size.1.max(1));
+    }
+
+    // The first cell and size of what's shown of a board of `dimensions`,
+    // pulled back inside it where the origin has run off the far edge.
+    pub fn region(&self, (rows, cols): (usize, usize), lattice: Lattice) -> ((usize, usize), (usize, usize)) {
+        let size = (self.size.0.min(rows), self.size.1.min(cols));
+        let (mut row, mut col) = (self.origin.0.min(rows - size.0), self.origin.1.min(cols - size.1));
+        // Triangles point up or down by the parity of row + col, which has to
+        // come out the same on screen as on the board.
+        if lattice == Lattice::Triangular && !(row + col).is_multiple_of(2) {
+            if col > 0 { col -= 1 } else { row -= 1 }
+        }
+        ((row, col), size)
+    }
+
+    // Moves the view by whole cells over a board of `dimensions`.
+    pub fn pan(&mut self, dimensions: (usize, usize), lattice: Lattice, (down, right): (isize, isize)) {
+        let ((row, col), _) = self.region(dimensions, lattice);
+        let (row, mut col) = (row.saturating_add_signed(down), col.saturating_add_signed(right));
+        // Keep going the way the view was moving rather than let `region`
+        // snap it back where it came from.
+        if lattice == Lattice::Triangular && !(row + col).is_multiple_of(2) {
+            col = if right < 0 { col.saturating_sub(1) } else { col + 1 };
+        }
+        self.origin = (row, col);
+        self.origin = self.region(dimensions, lattice).0;
+    }
+
+    // Moves the view so board cell `cell` sits under frame pixel (x, y) of
+    // cells `cell_size` across, as near as the board allows.
+    pub fn pin(&mut self, dimensions: (usize, usize), lattice: Lattice, cell: (usize, usize), cell_size: usize, (x, y): (usize, usize)) {
+        let ((top, left), _) = self.region(dimensions, lattice);
+        let under = self
+            .cell_at(dimensions, lattice, cell_size, x, y)
+            .unwrap_or((top + y / cell_size.max(1), left + x / cell_size.max(1)));
+        self.pan(dimensions, lattice, (cell.0 as isize - under.0 as isize, cell.1 as isize - under.1 as isize));
+    }
+
+    // Board cell `cell` as it sits in the view, if it's in it.
+    pub fn to_view(&self, dimensions: (usize, usize), lattice: Lattice, (row, col): (usize, usize)) -> Option<(usize, usize)> {
+        let ((top, left), (rows, cols)) = self.region(dimensions, lattice);
+        let (row, col) = (row.checked_sub(top)?, col.checked_sub(left)?);
+        (row < rows && col < cols).then_some((row, col))
+    }
+
+    // Pixel size of the view of `sim`.
+    pub fn frame_size(&self, sim: &dyn Simulator, cell_size: usize) -> (usize, usize) {
+        frame_size(&self.of(sim), cell_size)
+    }
+
+    // The board cell under frame pixel (x, y), if any.
+    pub fn cell_at_pixel(&self, sim: &dyn Simulator, cell_size: usize, x: usize, y: usize) -> Option<(usize, usize)> {
+        self.cell_at(sim.dimensions(), sim.lattice(), cell_size, x, y)
+    }
+
+    // The same for any board of `dimensions` on `lattice`.
+    pub fn cell_at(&self, dimensions: (usize, usize), lattice: Lattice, cell_size: usize, x: usize, y: usize) -> Option<(usize, usize)> {
+        let ((top, left), size) = self.region(dimensions, lattice);
+        let (row, col) = render::lattice_cell_at(lattice, size, cell_size, x, y)?;
+        Some((top + row, left + col))
+    }
+
+    // `sim` cropped to the view, for drawing.
+    pub fn of<'a>(&self, sim: &'a dyn Simulator) -> View<'a> {
+        let (origin, size) = self.region(sim.dimensions(), sim.lattice());
+        let changes = sim.changes().map(|changes| {
+            changes.iter().filter_map(|&cell| self.to_view(sim.dimensions(), sim.lattice(), cell)).collect()
+        });
+        View {
+            sim,
+            origin,
+            size,
+            changes,
+        }
+    }
+}
+
+// A simulator seen through a viewport, with its cells renumbered from the
+// viewport's corner. It only looks; stepping is left to the simulator.
+pub struct View<'a> {
+    sim: &'a dyn Simulator,
+    origin: (usize, usize),
+    size: (usize, usize),
+    changes: Option<Vec<(usize, usize)>>,
+}
+
+impl Simulator for View<'_> {
+    fn step(&mut self) {}
+
+    fn dimensions(&self) -> (usize, usize) {
+        self.size
+    }
+
+    fn generation(&self) -> Option<u64> {
+        self.sim.generation()
+    }
+
+    fn cell(&self, row: usize, col: usize) -> bool {
+        self.sim.cell(self.origin.0 + row, self.origin.1 + col)
+    }
+
+    // The whole board's, not just what's in view.
+    fn population(&self) -> usize {
+        self.sim.population()
+    }
+
+    fn shade(&self, row: usize, col: usize) -> Shade {
+        self.sim.shade(self.origin.0 + row, self.origin.1 + col)
+    }
+
+    fn lattice(&self) -> Lattice {
+        self.sim.lattice()
+    }
+
+    fn neighbors(&self, row: usize, col: usize) -> Option<u8> {
+        self.sim.neighbors(self.origin.0 + row, self.origin.1 + col)
+    }
+
+    fn changes(&self) -> Option<&[(usize, usize)]> {
+        self.changes.as_deref()
+    }
+
+    fn populations(&self) -> Vec<Population> {
+        self.sim.populations()
+    }
+
+    fn memory_footprint(&self) -> Vec<Memory> {
+        self.sim.memory_footprint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A view of `size` cells panned as near `origin` as a `board` allows.
+    fn at(board: (usize, usize), lattice: Lattice, size: (usize, usize), origin: (usize, usize)) -> Viewport {
+        let mut viewport = Viewport::new(size);
+        viewport.pan(board, lattice, (origin.0 as isize, origin.1 as isize));
+        viewport
+    }
+
+    #[test]
+    fn region_fits_inside_the_board() {
+        assert_eq!(Viewport::whole().region((4, 6), Lattice::Square), ((0, 0), (4, 6)));
+        let viewport = at((20, 20), Lattice::Square, (5, 5), (3, 7));
+        assert_eq!(viewport.region((20, 20), Lattice::Square), ((3, 7), (5, 5)));
+        // The board shrinking under the view pulls it back in.
+        assert_eq!(viewport.region((6, 9), Lattice::Square), ((1, 4), (5, 5)));
+        assert_eq!(viewport.region((3, 3), Lattice::Square), ((0, 0), (3, 3)));
+    }
+
+    #[test]
+    fn pan_stops_at_the_edge() {
+        let viewport = at((10, 10), Lattice::Square, (4, 4), (50, 50));
+        assert_eq!(viewport.region((10, 10), Lattice::Square), ((6, 6), (4, 4)));
+        let mut viewport = viewport;
+        viewport.pan((10, 10), Lattice::Square, (-50, -2));
+        assert_eq!(viewport.region((10, 10), Lattice::Square), ((0, 4), (4, 4)));
+    }
+
+    #[test]
+    fn triangular_views_keep_parity() {
+        let viewport = Viewport { origin: (3, 4), size: (2, 2) };
+        assert_eq!(viewport.region((10, 10), Lattice::Triangular), ((3, 3), (2, 2)));
+        assert_eq!(viewport.region((10, 10), Lattice::Square), ((3, 4), (2, 2)));
+        let viewport = Viewport { origin: (3, 0), size: (2, 2) };
+        assert_eq!(viewport.region((10, 10), Lattice::Triangular), ((2, 0), (2, 2)));
+        // Clamped past the edge to (6, 7), then snapped.
+        let viewport = Viewport { origin: (9, 9), size: (4, 4) };
+        assert_eq!(viewport.region((10, 11), Lattice::Triangular), ((6, 6), (4, 4)));
+        // Panning one cell across still moves the view the way it was asked.
+        let mut viewport = Viewport::new((4, 4));
+        viewport.pan((10, 10), Lattice::Triangular, (0, 1));
+        assert_eq!(viewport.region((10, 10), Lattice::Triangular).0, (0, 2));
+        viewport.pan((10, 10), Lattice::Triangular, (0, -1));
+        assert_eq!(viewport.region((10, 10), Lattice::Triangular).0, (0, 0));
+    }
+
+    #[test]
+    fn to_view_numbers_from_the_corner() {
+        let viewport = at((10, 10), Lattice::Square, (4, 4), (2, 3));
+        let to_view = |cell| viewport.to_view((10, 10), Lattice::Square, cell);
+        assert_eq!(to_view((2, 3)), Some((0, 0)));
+        assert_eq!(to_view((5, 6)), Some((3, 3)));
+        assert_eq!(to_view((1, 3)), None);
+        assert_eq!(to_view((2, 7)), None);
+        assert_eq!(to_view((6, 4)), None);
+    }
+
+    #[test]
+    fn cell_at_adds_the_corner() {
+        let viewport = at((10, 10), Lattice::Square, (4, 4), (2, 3));
+        assert_eq!(viewport.cell_at((10, 10), Lattice::Square, 4, 5, 9), Some((4, 4)));
+        assert_eq!(viewport.cell_at((10, 10), Lattice::Square, 4, 0, 0), Some((2, 3)));
+        assert_eq!(viewport.cell_at((10, 10), Lattice::Square, 4, 16, 0), None);
+        for (x, y) in [(3, 2), (9, 5), (14, 13)] {
+            let cell = viewport.cell_at((10, 10), Lattice::Square, 4, x, y).unwrap();
+            assert_eq!(viewport.to_view((10, 10), Lattice::Square, cell), Some((y / 4, x / 4)));
+        }
+    }
+
+    #[test]
+    fn triangles_under_the_cursor_point_the_same_way() {
+        let viewport = Viewport { origin: (3, 4), size: (4, 6) };
+        let (dimensions, lattice) = ((10, 10), Lattice::Triangular);
+        for (x, y) in (0..28).flat_map(|x| (0..28).map(move |y| (x, y))) {
+            let Some(shown) = render::lattice_cell_at(lattice, (4, 6), 8, x, y) else { continue };
+            let cell = viewport.cell_at(dimensions, lattice, 8, x, y).unwrap();
+            assert_eq!((cell.0 + cell.1) % 2, (shown.0 + shown.1) % 2);
+            assert_eq!(viewport.to_view(dimensions, lattice, cell), Some(shown));
+        }
+    }
+
+    #[test]
+    fn zooming_keeps_the_cell_under_the_cursor() {
+        for lattice in [Lattice::Square, Lattice::Hexagonal] {
+            let dimensions = (100, 100);
+            let mut viewport = at(dimensions, lattice, (10, 10), (40, 40));
+            let (cursor, size) = ((37, 21), 8);
+            let cell = viewport.cell_at(dimensions, lattice, size, cursor.0, cursor.1).unwrap();
+            for (size, cells) in [(16, (5, 5)), (4, (20, 20)), (2, (40, 40)), (8, (10, 10))] {
+                viewport.set_size(cells);
+                viewport.pin(dimensions, lattice, cell, size, cursor);
+                let under = viewport.cell_at(dimensions, lattice, size, cursor.0, cursor.1);
+                assert_eq!(under, Some(cell), "{lattice:?} at {size}");
+            }
+        }
+    }
+
+    #[test]
+    fn zooming_by_the_edge_stays_on_the_board() {
+        let dimensions = (20, 20);
+        let mut viewport = at(dimensions, Lattice::Square, (10, 10), (0, 0));
+        viewport.set_size((5, 5));
+        viewport.pin(dimensions, Lattice::Square, (1, 1), 16, (70, 70));
+        assert_eq!(viewport.region(dimensions, Lattice::Square), ((0, 0), (5, 5)));
+        viewport.pin(dimensions, Lattice::Square, (19, 19), 16, (0, 0));
+        assert_eq!(viewport.region(dimensions, Lattice::Square), ((15, 15), (5, 5)));
+    }
+}
@@ -0,0 +1,256 @@
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::{History, Parameter, Population, Rewind, Simulator, Timeline};
+
+type Edit = Box<dyn FnOnce(&mut dyn Simulator) + Send>;
+type Read = Box<dyn FnOnce(&dyn Simulator) + Send>;
+
+enum Command {
+    Step(usize),
+    Edit(Edit),
+    Stroke(Edit),
+    Read(Read),
+    Replace(Box<dyn Simulator>),
+    Undo,
+    Redo,
+    Back,
+    Seek(i64),
+}
+
+// What the worker tells the owner after each generation or edit, with the
+// simulator's changes copied out so nobody has to wait on the lock to
+// learn them.
+pub enum Report {
+    Stepped {
+        took: Duration,
+        changes: Option<Vec<(usize, usize)>>,
+        populations: Vec<Population>,
+        // Live cells, all species together.
+        live: usize,
+    },
+    Edited {
+        changes: Option<Vec<(usize, usize)>>,
+        parameters: Vec<Parameter>,
+    },
+}
+
+// Steps a simulator on its own thread. Commands queue up in order; the lock
+// is only held for one generation or edit at a time, so the owner can read
+// the board between them.
+pub struct Worker {
+    simulator: Arc<Mutex<Box<dyn Simulator>>>,
+    commands: Option<Sender<Command>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    // `report` is called on the worker thread as each command finishes.
+    // Each batch of steps, replacement and edit that changes the board goes
+    // in `history` first, so it can be undone, and each generation in
+    // `rewind`, so it can be gone back to, and in `timeline`, so it can be
+    // sought.
+    pub fn spawn(
+        simulator: Box<dyn Simulator>,
+        mut history: History,
+        mut rewind: Rewind,
+        mut timeline: Timeline,
+        report: impl Fn(Report) + Send + 'static,
+    ) -> Self {
+        let simulator = Arc::new(Mutex::new(simulator));
+        let (commands, received) = mpsc::channel();
+        let shared = Arc::clone(&simulator);
+        let thread = thread::spawn(move || {
+            // Whether the last edit to change the board can take strokes
+            // into its undo step.
+            let mut stroking = false;
+            for command in received {
+                let joins = matches!(command, Command::Stroke(_));
+                if !matches!(command, Command::Edit(_) | Command::Stroke(_) | Command::Read(_)) {
+                    stroking = false;
+                }
+                if let Command::Step(_) | Command::Replace(_) = command
+                    && let Some(snapshot) = lock(&shared).save()
+                {
+                    history.record(snapshot);
+                }
+                match command {
+                    Command::Step(generations) => {
+                        for _ in 0..generations {
+                            let mut sim = lock(&shared);
+                            if let Some(snapshot) = sim.save() {
+                                timeline.record(&snapshot);
+                                rewind.record(snapshot);
+                            }
+                            let started = Instant::now();
+                            sim.step();
+                            let took = started.elapsed();
+                            let changes = sim.changes().map(<[_]>::to_vec);
+                            let populations = sim.populations();
+                            let live = sim.population();
+                            drop(sim);
+                            report(Report::Stepped {
+                                took,
+                                changes,
+                                populations,
+                                live,
+                            });
+                        }
+                    }
+                    Command::Edit(edit) | Command::Stroke(edit) => {
+                        let mut sim = lock(&shared);
+                        let before = sim.save();
+                        edit(sim.as_mut());
+                        // An edit that leaves the board as it was has nothing
+                        // to undo, and a drag is undone all at once.
+                        match before {
+                            Some(before) if sim.save().as_ref() != Some(&before) => {
+                                // Whatever the board went on to after it no
+                                // longer follows from it.
+                                timeline.truncate(before.generation);
+                                if !(joins && stroking) {
+                                    history.record(before);
+                                }
+                                stroking = true;
+                            }
+                            _ => stroking &= joins,
+                        }
+                        let changes = sim.changes().map(<[_]>::to_vec);
+                        let parameters = sim.parameters();
+                        drop(sim);
+                        report(Report::Edited { changes, parameters });
+                    }
+                    Command::Read(read) => read(lock(&shared).as_ref()),
+                    // Nothing on the new board can be assumed to match the
+                    // old one.
+                    Command::Replace(simulator) => {
+                        let mut sim = lock(&shared);
+                        *sim = simulator;
+                        rewind.clear();
+                        timeline.clear();
+                        let parameters = sim.parameters();
+                        drop(sim);
+                        report(Report::Edited { changes: None, parameters });
+                    }
+                    Command::Back => {
+                        let mut sim = lock(&shared);
+                        if let Some(snapshot) = rewind.back() {
+                            sim.load(&snapshot);
+                        }
+                        let changes = sim.changes().map(<[_]>::to_vec);
+                        let parameters = sim.parameters();
+                        drop(sim);
+                        report(Report::Edited { changes, parameters });
+                    }
+                    // Stepping on from a checkpoint leaves nothing in common
+                    // with the board drawn last.
+                    Command::Seek(by) => {
+                        let mut sim = lock(&shared);
+                        let sought = sim.save().and_then(|current| timeline.seek(current.generation.saturating_add_signed(by)));
+                        if let Some((checkpoint, generations)) = sought {
+                            sim.load(checkpoint);
+                            for _ in 0..generations {
+                                sim.step();
+                            }
+                            rewind.clear();
+                        }
+                        let parameters = sim.parameters();
+                        drop(sim);
+                        report(Report::Edited { changes: None, parameters });
+                    }
+                    Command::Undo | Command::Redo => {
+                        let mut sim = lock(&shared);
+                        let restored = sim.save().and_then(|current| match command {
+                            Command::Undo => history.undo(current),
+                            _ => history.redo(current),
+                        });
+                        if let Some(snapshot) = restored {
+                            sim.load(&snapshot);
+                        }
+                        let changes = sim.changes().map(<[_]>::to_vec);
+                        let parameters = sim.parameters();
+                        drop(sim);
+                        report(Report::Edited { changes, parameters });
+                    }
+                }
+            }
+        });
+        Self {
+            simulator,
+            commands: Some(commands),
+            thread: Some(thread),
+        }
+    }
+
+    pub fn step(&self, generations: usize) {
+        self.send(Command::Step(generations));
+    }
+
+    // Runs `edit` on the worker thread after whatever is already queued.
+    pub fn edit(&self, edit: impl FnOnce(&mut dyn Simulator) + Send + 'static) {
+        self.send(Command::Edit(Box::new(edit)));
+    }
+
+    // Runs `edit` as part of the undo step of the edit before it, as long as
+    // nothing else came in between, so a drag is undone in one go.
+    pub fn stroke(&self, edit: impl FnOnce(&mut dyn Simulator) + Send + 'static) {
+        self.send(Command::Stroke(Box::new(edit)));
+    }
+
+    // Runs `read` on the worker thread after whatever is already queued,
+    // without touching the undo history.
+    pub fn read(&self, read: impl FnOnce(&dyn Simulator) + Send + 'static) {
+        self.send(Command::Read(Box::new(read)));
+    }
+
+    // Goes back to before the last batch of steps, edit or replacement.
+    pub fn undo(&self) {
+        self.send(Command::Undo);
+    }
+
+    pub fn redo(&self) {
+        self.send(Command::Redo);
+    }
+
+    // Goes back a generation, as far as `rewind` remembers.
+    pub fn back(&self) {
+        self.send(Command::Back);
+    }
+
+    // Moves `by` generations through the timeline, as far as it goes.
+    pub fn seek(&self, by: i64) {
+        self.send(Command::Seek(by));
+    }
+
+    // Swaps in another simulator after whatever is already queued.
+    pub fn replace(&self, simulator: Box<dyn Simulator>) {
+        self.send(Command::Replace(simulator));
+    }
+
+    // The simulator, unless the worker is busy with it.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, Box<dyn Simulator>>> {
+        self.simulator.try_lock().ok()
+    }
+
+    fn send(&self, command: Command) {
+        // The thread only stops once `commands` is dropped.
+        if let Some(commands) = &self.commands {
+            commands.send(command).expect("worker thread is running");
+        }
+    }
+}
+
+fn lock(simulator: &Mutex<Box<dyn Simulator>>) -> MutexGuard<'_, Box<dyn Simulator>> {
+    simulator.lock().expect("simulator lock poisoned")
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.commands = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}